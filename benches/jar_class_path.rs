@@ -0,0 +1,78 @@
+//! Compares looking up classes in a JAR file through [`JarClassPath`], which reads and indexes the
+//! archive once, with reopening the archive for every lookup.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use mokapot::jvm::{
+    class::Class,
+    class_loader::{class_paths::JarClassPath, ClassPath},
+};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+const CLASSES_DIR: &str = concat!(env!("OUT_DIR"), "/openjdk/java_classes");
+
+/// Packs the compiled OpenJDK test data into a JAR file and returns the binary names of the
+/// classes in it.
+fn build_jar(jar_path: &Path) -> Vec<String> {
+    let mut class_files = Vec::new();
+    let mut dirs = vec![PathBuf::from(CLASSES_DIR)];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|it| it == "class") {
+                class_files.push(path);
+            }
+        }
+    }
+    let mut jar = ZipWriter::new(File::create(jar_path).unwrap());
+    let mut binary_names = Vec::new();
+    for path in class_files {
+        let entry = path.strip_prefix(CLASSES_DIR).unwrap().to_str().unwrap();
+        jar.start_file(entry, FileOptions::default()).unwrap();
+        jar.write_all(&fs::read(&path).unwrap()).unwrap();
+        binary_names.push(entry.trim_end_matches(".class").to_owned());
+    }
+    jar.finish().unwrap();
+    binary_names
+}
+
+/// Looks up a class the way [`JarClassPath`] did before the archive was kept open.
+fn find_class_reopening(jar_path: &Path, binary_name: &str) -> Class {
+    let mut archive = ZipArchive::new(File::open(jar_path).unwrap()).unwrap();
+    let mut class_file = archive.by_name(&format!("{binary_name}.class")).unwrap();
+    Class::from_reader(&mut class_file).unwrap()
+}
+
+fn jar_lookups(c: &mut Criterion) {
+    let temp_dir = tempdir::TempDir::new("mokapot-bench").unwrap();
+    let jar_path = temp_dir.path().join("classes.jar");
+    let binary_names = build_jar(&jar_path);
+
+    let mut group = c.benchmark_group("jar_lookups");
+    group.bench_function("indexed", |b| {
+        b.iter(|| {
+            let class_path = JarClassPath::new(&jar_path);
+            for name in &binary_names {
+                class_path.find_class(name).unwrap();
+            }
+        });
+    });
+    group.bench_function("reopen_per_lookup", |b| {
+        b.iter(|| {
+            for name in &binary_names {
+                find_class_reopening(&jar_path, name);
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, jar_lookups);
+criterion_main!(benches);
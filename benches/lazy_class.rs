@@ -0,0 +1,49 @@
+//! Compares parsing the structure of classes with [`LazyClass`] against fully parsing them with
+//! [`Class::from_reader`].
+
+use std::{fs, path::PathBuf};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use mokapot::jvm::{class::Class, parsing::LazyClass};
+
+const CLASSES_DIR: &str = concat!(env!("OUT_DIR"), "/openjdk/java_classes");
+
+/// Reads the compiled OpenJDK test data.
+fn class_files() -> Vec<Vec<u8>> {
+    let mut class_files = Vec::new();
+    let mut dirs = vec![PathBuf::from(CLASSES_DIR)];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|it| it == "class") {
+                class_files.push(fs::read(path).unwrap());
+            }
+        }
+    }
+    class_files
+}
+
+fn parse_structure(c: &mut Criterion) {
+    let class_files = class_files();
+    let mut group = c.benchmark_group("parse_structure");
+    group.bench_function("lazy", |b| {
+        b.iter(|| {
+            for bytes in &class_files {
+                LazyClass::from_reader(bytes.as_slice()).unwrap();
+            }
+        });
+    });
+    group.bench_function("eager", |b| {
+        b.iter(|| {
+            for bytes in &class_files {
+                Class::from_reader(bytes.as_slice()).unwrap();
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_structure);
+criterion_main!(benches);
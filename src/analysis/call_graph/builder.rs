@@ -0,0 +1,296 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::{
+    analysis::{
+        class_hierarchy::ClassHierarchy,
+        resolution::{InvocationKind, MethodResolver, ResolutionError},
+    },
+    ir::{expression::Expression, MokaIRMethodExt, MokaInstruction},
+    jvm::{
+        class::{Class, MethodHandle},
+        class_loader::{CachingClassLoader, ClassPath},
+        code::ProgramCounter,
+        method::{self, Method},
+        references::{ClassRef, MethodRef},
+    },
+};
+
+use super::{Algorithm, CallGraph, EntryPoint, Error};
+
+/// Builds a [`CallGraph`] from a set of application classes.
+#[derive(Debug)]
+pub struct CallGraphBuilder<'l, P> {
+    resolver: MethodResolver<'l, P>,
+    application_classes: Vec<&'l Class>,
+    hierarchy: ClassHierarchy,
+    algorithm: Algorithm,
+    entry_points: Vec<EntryPoint>,
+}
+
+/// A virtual call site whose targets depend on the instantiated types under RTA.
+#[derive(Debug)]
+struct VirtualCallSite<'l> {
+    caller: MethodRef,
+    pc: ProgramCounter,
+    resolved: &'l Method,
+    receiver_type: ClassRef,
+}
+
+#[derive(Debug, Default)]
+struct State<'l> {
+    graph: CallGraph,
+    worklist: VecDeque<&'l Method>,
+    instantiated: BTreeSet<ClassRef>,
+    virtual_call_sites: Vec<VirtualCallSite<'l>>,
+}
+
+impl<'l, P> CallGraphBuilder<'l, P>
+where
+    P: ClassPath,
+{
+    /// Creates a builder analyzing the given application classes, which are loaded with
+    /// `class_loader`.
+    /// The application classes form the class hierarchy used to find the targets of virtual calls.
+    /// # Errors
+    /// - [`ResolutionError::ClassLoading`] if an application class cannot be loaded.
+    pub fn new(
+        class_loader: &'l CachingClassLoader<P>,
+        application_classes: impl IntoIterator<Item = ClassRef>,
+    ) -> Result<Self, ResolutionError> {
+        let resolver = MethodResolver::new(class_loader);
+        let application_classes: Vec<_> = application_classes
+            .into_iter()
+            .map(|it| resolver.load(&it))
+            .collect::<Result<_, _>>()?;
+        let hierarchy = ClassHierarchy::from_classes(application_classes.iter().copied());
+        Ok(Self {
+            resolver,
+            application_classes,
+            hierarchy,
+            algorithm: Algorithm::default(),
+            entry_points: Vec::new(),
+        })
+    }
+
+    /// Sets the algorithm for resolving virtual calls.
+    #[must_use]
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Adds an entry point.
+    #[must_use]
+    pub fn entry_point(mut self, entry_point: EntryPoint) -> Self {
+        self.entry_points.push(entry_point);
+        self
+    }
+
+    /// Builds the call graph with the methods reachable from the entry points.
+    /// # Errors
+    /// - [`ResolutionError`] if a method specified by [`EntryPoint::Method`] cannot be resolved.
+    pub fn build(mut self) -> Result<CallGraph, ResolutionError> {
+        let mut state = State::default();
+        let mut entry_points = Vec::new();
+        for entry_point in &self.entry_points {
+            entry_points.extend(entry_point.methods(&self.resolver, &self.application_classes)?);
+        }
+        for method in entry_points {
+            if !method.access_flags.contains(method::AccessFlags::STATIC) {
+                // The receiver of an instance entry point is assumed to exist.
+                self.instantiate(&method.owner, &mut state);
+            }
+            state.graph.add_entry_point(method.as_ref());
+            state.worklist.push_back(method);
+        }
+        while let Some(method) = state.worklist.pop_front() {
+            self.process_method(method, &mut state);
+        }
+        Ok(state.graph)
+    }
+
+    fn process_method(&mut self, method: &'l Method, state: &mut State<'l>) {
+        let Some(body) = method.body.as_ref() else {
+            return;
+        };
+        let ir = match method.brew() {
+            Ok(it) => it,
+            Err(source) => {
+                state.graph.add_error(Error::Brewing {
+                    method: method.as_ref(),
+                    source,
+                });
+                return;
+            }
+        };
+        let caller = method.as_ref();
+        for (pc, insn) in &ir.instructions {
+            let MokaInstruction::Definition { expr, .. } = insn else {
+                continue;
+            };
+            match expr {
+                Expression::Call {
+                    method: method_ref, ..
+                } => {
+                    let Some((kind, _)) = body.instruction_at(*pc).and_then(InvocationKind::of)
+                    else {
+                        continue;
+                    };
+                    self.process_call(&caller, *pc, kind, method_ref, state);
+                }
+                Expression::Closure {
+                    bootstrap_method_index,
+                    ..
+                } => self.process_closure(&caller, *pc, *bootstrap_method_index, state),
+                Expression::New(class) => self.instantiate(class, state),
+                _ => {}
+            }
+        }
+    }
+
+    fn process_call(
+        &mut self,
+        caller: &MethodRef,
+        pc: ProgramCounter,
+        kind: InvocationKind,
+        method_ref: &MethodRef,
+        state: &mut State<'l>,
+    ) {
+        let resolved = match self
+            .resolver
+            .dispatch(kind, method_ref, &caller.owner, None)
+        {
+            Ok(it) => it,
+            Err(source) => {
+                state.graph.add_error(Error::Resolution {
+                    caller: caller.clone(),
+                    pc,
+                    source,
+                });
+                return;
+            }
+        };
+        let is_monomorphic = matches!(kind, InvocationKind::Static | InvocationKind::Special)
+            || resolved
+                .access_flags
+                .intersects(method::AccessFlags::PRIVATE | method::AccessFlags::FINAL)
+            || resolved.is_signature_polymorphic();
+        if is_monomorphic {
+            Self::add_edge(caller, pc, resolved, state);
+            return;
+        }
+        let receiver_type = method_ref.owner.clone();
+        match self.algorithm {
+            Algorithm::ClassHierarchy => {
+                if let Ok(receiver_class) = self.resolver.load(&receiver_type) {
+                    self.hierarchy.insert(receiver_class);
+                }
+                for receiver in self.hierarchy.concrete_subtypes(&receiver_type) {
+                    self.add_virtual_edge(caller, pc, resolved, &receiver, state);
+                }
+            }
+            Algorithm::RapidType => {
+                let receivers: Vec<_> = state
+                    .instantiated
+                    .iter()
+                    .filter(|it| self.is_subtype_of(it, &receiver_type))
+                    .cloned()
+                    .collect();
+                for receiver in receivers {
+                    self.add_virtual_edge(caller, pc, resolved, &receiver, state);
+                }
+                state.virtual_call_sites.push(VirtualCallSite {
+                    caller: caller.clone(),
+                    pc,
+                    resolved,
+                    receiver_type,
+                });
+            }
+        }
+    }
+
+    fn process_closure(
+        &mut self,
+        caller: &MethodRef,
+        pc: ProgramCounter,
+        bootstrap_method_index: u16,
+        state: &mut State<'l>,
+    ) {
+        let Ok(caller_class) = self.resolver.load(&caller.owner) else {
+            return;
+        };
+        let Some(bootstrap_method) = caller_class
+            .bootstrap_methods
+            .get(usize::from(bootstrap_method_index))
+        else {
+            return;
+        };
+        let Some(implementation) = bootstrap_method.lambda_implementation() else {
+            return;
+        };
+        let (kind, method_ref) = match implementation {
+            MethodHandle::RefInvokeStatic(it) => (InvocationKind::Static, it),
+            MethodHandle::RefInvokeSpecial(it) => (InvocationKind::Special, it),
+            MethodHandle::RefInvokeVirtual(it) => (InvocationKind::Virtual, it),
+            MethodHandle::RefInvokeInterface(it) => (InvocationKind::Interface, it),
+            MethodHandle::RefNewInvokeSpecial(it) => {
+                self.instantiate(&it.owner, state);
+                (InvocationKind::Special, it)
+            }
+            _ => return,
+        };
+        self.process_call(caller, pc, kind, method_ref, state);
+    }
+
+    fn instantiate(&mut self, class: &ClassRef, state: &mut State<'l>) {
+        if !state.instantiated.insert(class.clone()) {
+            return;
+        }
+        if let Ok(loaded) = self.resolver.load(class) {
+            self.hierarchy.insert(loaded);
+        }
+        if self.algorithm != Algorithm::RapidType {
+            return;
+        }
+        let affected: Vec<_> = state
+            .virtual_call_sites
+            .iter()
+            .filter(|site| self.is_subtype_of(class, &site.receiver_type))
+            .map(|site| (site.caller.clone(), site.pc, site.resolved))
+            .collect();
+        for (caller, pc, resolved) in affected {
+            self.add_virtual_edge(&caller, pc, resolved, class, state);
+        }
+    }
+
+    fn add_virtual_edge(
+        &self,
+        caller: &MethodRef,
+        pc: ProgramCounter,
+        resolved: &'l Method,
+        receiver: &ClassRef,
+        state: &mut State<'l>,
+    ) {
+        match self.resolver.select(resolved, receiver) {
+            Ok(target) => Self::add_edge(caller, pc, target, state),
+            Err(source) => state.graph.add_error(Error::Resolution {
+                caller: caller.clone(),
+                pc,
+                source,
+            }),
+        }
+    }
+
+    fn add_edge(caller: &MethodRef, pc: ProgramCounter, target: &'l Method, state: &mut State<'l>) {
+        if state.graph.add_edge(caller.clone(), pc, target.as_ref()) {
+            state.worklist.push_back(target);
+        }
+    }
+
+    fn is_subtype_of(&self, class: &ClassRef, super_type: &ClassRef) -> bool {
+        self.resolver
+            .load(class)
+            .and_then(|it| self.resolver.is_subtype_of(it, super_type))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,291 @@
+//! Call graphs of JVM programs.
+//!
+//! A [`CallGraph`] is built by a [`CallGraphBuilder`] with either Class Hierarchy Analysis (CHA)
+//! or Rapid Type Analysis (RTA). See [`Algorithm`] for the differences.
+
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
+
+use crate::{
+    ir::MokaIRBrewingError,
+    jvm::{
+        class::{self, Class},
+        class_loader::ClassPath,
+        code::ProgramCounter,
+        method::{self, Method},
+        references::MethodRef,
+    },
+};
+
+use super::resolution::{MethodResolver, ResolutionError};
+
+mod builder;
+
+pub use builder::CallGraphBuilder;
+
+/// The algorithm used to determine the targets of virtual calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Class Hierarchy Analysis.
+    /// A virtual call may target the implementation in any concrete subtype of the declared
+    /// receiver type.
+    #[default]
+    ClassHierarchy,
+    /// Rapid Type Analysis.
+    /// A virtual call may target the implementation in a concrete subtype of the declared receiver
+    /// type only if the subtype is instantiated (i.e., by a `new` expression) in a reachable method.
+    RapidType,
+}
+
+/// A set of methods where the analyzed program starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryPoint {
+    /// The `public static void main(String[])` methods in the application classes.
+    MainMethods,
+    /// The `public` and `protected` methods with a body in the `public` application classes.
+    PublicApi,
+    /// The static initializers (i.e., `<clinit>`) of the application classes.
+    StaticInitializers,
+    /// A specific method.
+    Method(MethodRef),
+}
+
+impl EntryPoint {
+    /// Finds the methods specified by this entry point in the given application classes.
+    pub(crate) fn methods<'l, P: ClassPath>(
+        &self,
+        resolver: &MethodResolver<'l, P>,
+        application_classes: &[&'l Class],
+    ) -> Result<Vec<&'l Method>, ResolutionError> {
+        let application_methods = application_classes.iter().flat_map(|it| it.methods.iter());
+        let methods = match self {
+            Self::MainMethods => application_methods
+                .filter(|m| m.name == "main")
+                .filter(|m| m.descriptor.to_string() == "([Ljava/lang/String;)V")
+                .filter(|m| {
+                    m.access_flags
+                        .contains(method::AccessFlags::PUBLIC | method::AccessFlags::STATIC)
+                })
+                .collect(),
+            Self::PublicApi => application_classes
+                .iter()
+                .filter(|it| it.access_flags.contains(class::AccessFlags::PUBLIC))
+                .flat_map(|it| it.methods.iter())
+                .filter(|m| m.body.is_some())
+                .filter(|m| {
+                    m.access_flags
+                        .intersects(method::AccessFlags::PUBLIC | method::AccessFlags::PROTECTED)
+                })
+                .collect(),
+            Self::StaticInitializers => application_methods
+                .filter(|m| m.is_static_initializer_block())
+                .collect(),
+            Self::Method(method_ref) => vec![resolver.resolve(method_ref)?],
+        };
+        Ok(methods)
+    }
+}
+
+/// An error that occurs when building a call graph.
+/// Such errors do not stop the construction; the affected call sites or methods are left without
+/// outgoing edges.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The target of a call could not be determined.
+    #[error("Failed to resolve the call at {pc} in {caller}: {source}")]
+    Resolution {
+        /// The method containing the call site.
+        caller: MethodRef,
+        /// The location of the call site.
+        pc: ProgramCounter,
+        /// The cause of the failure.
+        #[source]
+        source: ResolutionError,
+    },
+    /// The body of a reachable method could not be converted to Moka IR.
+    #[error("Failed to generate Moka IR for {method}: {source}")]
+    Brewing {
+        /// The method that could not be converted.
+        method: MethodRef,
+        /// The cause of the failure.
+        #[source]
+        source: MokaIRBrewingError,
+    },
+}
+
+/// A call graph, where the nodes are methods and the edges are calls at specific call sites.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    entry_points: BTreeSet<MethodRef>,
+    edges: BTreeMap<MethodRef, BTreeMap<ProgramCounter, BTreeSet<MethodRef>>>,
+    errors: Vec<Error>,
+}
+
+impl CallGraph {
+    /// Adds a method to the graph.
+    /// Returns `true` if the method was not in the graph.
+    pub fn add_method(&mut self, method: MethodRef) -> bool {
+        match self.edges.entry(method) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(BTreeMap::new());
+                true
+            }
+        }
+    }
+
+    /// Adds an entry point to the graph.
+    pub fn add_entry_point(&mut self, method: MethodRef) {
+        self.add_method(method.clone());
+        self.entry_points.insert(method);
+    }
+
+    /// Adds a call from `source` at `pc` to `target`.
+    /// Returns `true` if `target` was not in the graph.
+    pub fn add_edge(&mut self, source: MethodRef, pc: ProgramCounter, target: MethodRef) -> bool {
+        let is_new = self.add_method(target.clone());
+        self.edges
+            .entry(source)
+            .or_default()
+            .entry(pc)
+            .or_default()
+            .insert(target);
+        is_new
+    }
+
+    /// Returns the entry points of the graph.
+    pub fn entry_points(&self) -> impl Iterator<Item = &MethodRef> {
+        self.entry_points.iter()
+    }
+
+    /// Returns the methods in the graph.
+    pub fn methods(&self) -> impl Iterator<Item = &MethodRef> {
+        self.edges.keys()
+    }
+
+    /// Checks whether the method is in the graph.
+    #[must_use]
+    pub fn contains(&self, method: &MethodRef) -> bool {
+        self.edges.contains_key(method)
+    }
+
+    /// Returns the call sites in `caller` and their targets.
+    pub fn call_sites(
+        &self,
+        caller: &MethodRef,
+    ) -> impl Iterator<Item = (ProgramCounter, &BTreeSet<MethodRef>)> {
+        self.edges
+            .get(caller)
+            .into_iter()
+            .flatten()
+            .map(|(pc, callees)| (*pc, callees))
+    }
+
+    /// Returns the possible targets of the call at `pc` in `caller`.
+    pub fn callees(
+        &self,
+        caller: &MethodRef,
+        pc: ProgramCounter,
+    ) -> impl Iterator<Item = &MethodRef> {
+        self.edges
+            .get(caller)
+            .and_then(|it| it.get(&pc))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the call sites that may call `callee`.
+    pub fn callers<'a>(
+        &'a self,
+        callee: &'a MethodRef,
+    ) -> impl Iterator<Item = (&'a MethodRef, ProgramCounter)> + 'a {
+        self.edges()
+            .filter(move |(_, _, it)| *it == callee)
+            .map(|(caller, pc, _)| (caller, pc))
+    }
+
+    /// Returns the edges in the graph as `(caller, call site, callee)`.
+    pub fn edges(&self) -> impl Iterator<Item = (&MethodRef, ProgramCounter, &MethodRef)> {
+        self.edges.iter().flat_map(|(caller, sites)| {
+            sites
+                .iter()
+                .flat_map(move |(pc, callees)| callees.iter().map(move |it| (caller, *pc, it)))
+        })
+    }
+
+    /// Records an error occurred when building the graph.
+    pub(crate) fn add_error(&mut self, error: Error) {
+        self.errors.push(error);
+    }
+
+    /// Returns the errors occurred when building the graph.
+    #[must_use]
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+}
+
+#[cfg(feature = "petgraph")]
+impl CallGraph {
+    /// Converts the call graph into a [`petgraph::Graph`], where the edges are weighted by the
+    /// program counters of the call sites.
+    #[must_use]
+    pub fn to_petgraph(&self) -> petgraph::Graph<MethodRef, ProgramCounter> {
+        let mut graph = petgraph::Graph::new();
+        let node_indices: BTreeMap<_, _> = self
+            .methods()
+            .map(|it| (it, graph.add_node(it.clone())))
+            .collect();
+        for (caller, pc, callee) in self.edges() {
+            graph.add_edge(node_indices[caller], node_indices[callee], pc);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jvm::references::ClassRef;
+
+    use super::*;
+
+    fn method(name: &str) -> MethodRef {
+        MethodRef {
+            owner: ClassRef::new("Test"),
+            name: name.to_owned(),
+            descriptor: "()V".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn edges_and_callers() {
+        let mut graph = CallGraph::default();
+        graph.add_entry_point(method("main"));
+        assert!(graph.add_edge(method("main"), 1.into(), method("a")));
+        assert!(graph.add_edge(method("main"), 4.into(), method("b")));
+        assert!(!graph.add_edge(method("a"), 0.into(), method("b")));
+
+        assert_eq!(graph.methods().count(), 3);
+        assert_eq!(graph.edges().count(), 3);
+        assert_eq!(
+            graph.callees(&method("main"), 4.into()).collect::<Vec<_>>(),
+            vec![&method("b")]
+        );
+        let callee = method("b");
+        let callers: BTreeSet<_> = graph.callers(&callee).collect();
+        assert_eq!(
+            callers,
+            BTreeSet::from([(&method("a"), 0.into()), (&method("main"), 4.into())])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "petgraph")]
+    fn to_petgraph() {
+        let mut graph = CallGraph::default();
+        graph.add_edge(method("main"), 1.into(), method("a"));
+        graph.add_edge(method("a"), 2.into(), method("main"));
+        let petgraph = graph.to_petgraph();
+        assert_eq!(petgraph.node_count(), 2);
+        assert_eq!(petgraph.edge_count(), 2);
+    }
+}
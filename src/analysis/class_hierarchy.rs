@@ -0,0 +1,143 @@
+//! The class hierarchy of a set of classes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::jvm::{
+    class::{self, Class},
+    references::ClassRef,
+};
+
+/// The subtype relations among a set of classes and interfaces.
+#[derive(Debug, Clone, Default)]
+pub struct ClassHierarchy {
+    direct_subtypes: BTreeMap<ClassRef, BTreeSet<ClassRef>>,
+    concrete_classes: BTreeSet<ClassRef>,
+    interfaces: BTreeSet<ClassRef>,
+}
+
+impl ClassHierarchy {
+    /// Builds the class hierarchy of the given classes.
+    /// Supertypes that are not in `classes` only appear as the roots of the hierarchy.
+    pub fn from_classes<'c>(classes: impl IntoIterator<Item = &'c Class>) -> Self {
+        let mut hierarchy = Self::default();
+        for class in classes {
+            hierarchy.insert(class);
+        }
+        hierarchy
+    }
+
+    /// Adds a class to the hierarchy.
+    pub fn insert(&mut self, class: &Class) {
+        let this = class.as_ref();
+        for super_type in class.super_class.iter().chain(class.interfaces.iter()) {
+            self.direct_subtypes
+                .entry(super_type.clone())
+                .or_default()
+                .insert(this.clone());
+        }
+        if !class
+            .access_flags
+            .intersects(class::AccessFlags::ABSTRACT | class::AccessFlags::INTERFACE)
+        {
+            self.concrete_classes.insert(this.clone());
+        }
+        if class.access_flags.contains(class::AccessFlags::INTERFACE) {
+            self.interfaces.insert(this.clone());
+        }
+        self.direct_subtypes.entry(this).or_default();
+    }
+
+    /// Returns the direct subclasses and subinterfaces of the given type.
+    pub fn direct_subtypes(&self, class: &ClassRef) -> impl Iterator<Item = &ClassRef> {
+        self.direct_subtypes.get(class).into_iter().flatten()
+    }
+
+    /// Returns the given type and all its direct and indirect subtypes.
+    #[must_use]
+    pub fn subtypes(&self, class: &ClassRef) -> BTreeSet<ClassRef> {
+        let mut result = BTreeSet::new();
+        let mut stack = vec![class];
+        while let Some(current) = stack.pop() {
+            if result.insert(current.clone()) {
+                stack.extend(self.direct_subtypes(current));
+            }
+        }
+        result
+    }
+
+    /// Returns the given type and all its subtypes that can be instantiated, i.e., those are
+    /// neither `abstract` nor interfaces.
+    #[must_use]
+    pub fn concrete_subtypes(&self, class: &ClassRef) -> BTreeSet<ClassRef> {
+        self.subtypes(class)
+            .into_iter()
+            .filter(|it| self.concrete_classes.contains(it))
+            .collect()
+    }
+
+    /// Checks whether `class` is `super_type` or one of its subtypes.
+    #[must_use]
+    pub fn is_subtype_of(&self, class: &ClassRef, super_type: &ClassRef) -> bool {
+        self.subtypes(super_type).contains(class)
+    }
+
+    /// Checks whether the given type is an interface in the hierarchy.
+    #[must_use]
+    pub fn is_interface(&self, class: &ClassRef) -> bool {
+        self.interfaces.contains(class)
+    }
+
+    /// Returns the types in the hierarchy.
+    pub fn classes(&self) -> impl Iterator<Item = &ClassRef> {
+        self.direct_subtypes.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hierarchy() -> ClassHierarchy {
+        let mut hierarchy = ClassHierarchy::default();
+        let edges = [
+            ("A", "java/lang/Object"),
+            ("B", "A"),
+            ("C", "A"),
+            ("D", "B"),
+        ];
+        for (sub, sup) in edges {
+            hierarchy
+                .direct_subtypes
+                .entry(ClassRef::new(sup))
+                .or_default()
+                .insert(ClassRef::new(sub));
+            hierarchy
+                .direct_subtypes
+                .entry(ClassRef::new(sub))
+                .or_default();
+        }
+        hierarchy.concrete_classes = ["B", "D"].into_iter().map(ClassRef::new).collect();
+        hierarchy
+    }
+
+    #[test]
+    fn transitive_subtypes() {
+        let hierarchy = hierarchy();
+        let subtypes = hierarchy.subtypes(&ClassRef::new("A"));
+        let expected = ["A", "B", "C", "D"]
+            .into_iter()
+            .map(ClassRef::new)
+            .collect();
+        assert_eq!(subtypes, expected);
+        assert!(hierarchy.is_subtype_of(&ClassRef::new("D"), &ClassRef::new("java/lang/Object")));
+        assert!(!hierarchy.is_subtype_of(&ClassRef::new("C"), &ClassRef::new("B")));
+    }
+
+    #[test]
+    fn concrete_subtypes() {
+        let hierarchy = hierarchy();
+        let concrete = hierarchy.concrete_subtypes(&ClassRef::new("A"));
+        let expected = ["B", "D"].into_iter().map(ClassRef::new).collect();
+        assert_eq!(concrete, expected);
+    }
+}
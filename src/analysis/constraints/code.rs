@@ -0,0 +1,101 @@
+use crate::{
+    analysis::Location,
+    jvm::{
+        code::{Instruction, MethodBody, ProgramCounter},
+        method::Method,
+    },
+    macros::see_jvm_spec,
+};
+
+use super::{Diagnostic, Element, Violation};
+
+/// The first major version where `jsr`, `jsr_w`, and `ret` are not allowed.
+const NO_SUBROUTINE_MAJOR_VERSION: u16 = 51;
+
+/// Returns the locations an instruction may branch to.
+fn branch_targets(instruction: &Instruction) -> Vec<ProgramCounter> {
+    #[allow(clippy::enum_glob_use)]
+    use Instruction::*;
+
+    match instruction {
+        IfEq(target) | IfNe(target) | IfLt(target) | IfGe(target) | IfGt(target) | IfLe(target)
+        | IfICmpEq(target) | IfICmpNe(target) | IfICmpLt(target) | IfICmpGe(target)
+        | IfICmpGt(target) | IfICmpLe(target) | IfACmpEq(target) | IfACmpNe(target)
+        | IfNull(target) | IfNonNull(target) | Goto(target) | GotoW(target) | Jsr(target)
+        | JsrW(target) => vec![*target],
+        TableSwitch {
+            jump_targets,
+            default,
+            ..
+        } => jump_targets
+            .iter()
+            .chain(std::iter::once(default))
+            .copied()
+            .collect(),
+        LookupSwitch {
+            default,
+            match_targets,
+        } => match_targets
+            .values()
+            .chain(std::iter::once(default))
+            .copied()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Checks the static constraints on the code of a method.
+#[doc = see_jvm_spec!(4, 9, 1)]
+pub(super) fn check(method: &Method, body: &MethodBody, major_version: u16) -> Vec<Diagnostic> {
+    let method_ref = method.as_ref();
+    let mut diagnostics = Vec::new();
+    let mut report = |pc, violation| {
+        diagnostics.push(Diagnostic {
+            element: Element::Code(Location {
+                method: method_ref.clone(),
+                pc,
+            }),
+            violation,
+        });
+    };
+    let is_instruction = |pc: ProgramCounter| body.instruction_at(pc).is_some();
+
+    let Some(last_pc) = body.instructions.iter().map(|(pc, _)| *pc).last() else {
+        report(ProgramCounter::ZERO, Violation::EmptyCode);
+        return diagnostics;
+    };
+    for (pc, instruction) in &body.instructions {
+        for target in branch_targets(instruction) {
+            if !is_instruction(target) {
+                report(*pc, Violation::InvalidBranchTarget(target));
+            }
+        }
+        let is_subroutine = matches!(
+            instruction,
+            Instruction::Jsr(_) | Instruction::JsrW(_) | Instruction::Ret(_)
+        );
+        if is_subroutine && major_version >= NO_SUBROUTINE_MAJOR_VERSION {
+            report(*pc, Violation::ForbiddenInstruction(instruction.name()));
+        }
+    }
+    for entry in &body.exception_table {
+        let start = *entry.covered_pc.start();
+        // The end of the range is exclusive and may be the length of the code.
+        let end = *entry.covered_pc.end();
+        if start >= end || !is_instruction(start) || !(is_instruction(end) || end > last_pc) {
+            report(start, Violation::InvalidExceptionRange { start, end });
+        }
+        if !is_instruction(entry.handler_pc) {
+            report(
+                entry.handler_pc,
+                Violation::InvalidExceptionHandler(entry.handler_pc),
+            );
+        }
+    }
+    for entry in body.line_number_table.iter().flatten() {
+        if !is_instruction(entry.start_pc) {
+            report(entry.start_pc, Violation::InvalidLineNumber(entry.start_pc));
+        }
+    }
+    diagnostics
+}
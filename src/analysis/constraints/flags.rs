@@ -0,0 +1,80 @@
+use bitflags::Flags;
+
+use super::{Diagnostic, Element, Violation};
+
+/// Collects the violations of the rules on a set of access flags.
+pub(super) struct FlagChecker<'e, F> {
+    flags: F,
+    element: &'e Element,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Returns the name of a single flag.
+fn name_of<F: Flags>(flag: &F) -> &'static str {
+    flag.iter_names().next().map_or("UNKNOWN", |(name, _)| name)
+}
+
+impl<'e, F: Flags + Copy> FlagChecker<'e, F> {
+    pub(super) fn new(flags: F, element: &'e Element) -> Self {
+        Self {
+            flags,
+            element,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, violation: Violation) {
+        self.diagnostics.push(Diagnostic {
+            element: self.element.clone(),
+            violation,
+        });
+    }
+
+    /// Reports `flag` if it is not set.
+    pub(super) fn require(&mut self, flag: F) {
+        if !self.flags.contains(flag) {
+            self.report(Violation::MissingFlag(name_of(&flag)));
+        }
+    }
+
+    /// Reports `flag` if it is set.
+    pub(super) fn forbid(&mut self, flag: F) {
+        if self.flags.contains(flag) {
+            self.report(Violation::ForbiddenFlag(name_of(&flag)));
+        }
+    }
+
+    /// Reports every flag that is set but not in `allowed`.
+    pub(super) fn forbid_all_except(&mut self, allowed: F) {
+        let forbidden = self.flags.difference(allowed);
+        for (name, _) in forbidden.iter_names() {
+            self.report(Violation::ForbiddenFlag(name));
+        }
+    }
+
+    /// Reports `first` and `second` if both of them are set.
+    pub(super) fn conflict(&mut self, first: F, second: F) {
+        if self.flags.contains(first) && self.flags.contains(second) {
+            self.report(Violation::ConflictingFlags(
+                name_of(&first),
+                name_of(&second),
+            ));
+        }
+    }
+
+    /// Reports if more than one of the visibility flags is set.
+    pub(super) fn single_visibility(&mut self, public: F, private: F, protected: F) {
+        let count = [public, private, protected]
+            .into_iter()
+            .filter(|it| self.flags.contains(*it))
+            .count();
+        if count > 1 {
+            self.report(Violation::MultipleVisibilities);
+        }
+    }
+
+    /// Returns the violations found.
+    pub(super) fn finish(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
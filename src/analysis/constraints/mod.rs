@@ -0,0 +1,556 @@
+//! Checking of the static constraints on class files.
+//!
+//! The [`ConstraintChecker`] looks for violations of the format checks in JVMS §4.8 and the
+//! static constraints in JVMS §4.9 that the parser does not reject, such as conflicting access
+//! flags, branches into the middle of an instruction, or attributes that are not defined in the
+//! version of the class file.
+//! Every violation is reported as a [`Diagnostic`] so that linters can present all of them at
+//! once.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    jvm::{
+        class::{self, Class},
+        code::ProgramCounter,
+        field::{self, ConstantValue, Field},
+        method::{self, Method},
+        references::{ClassRef, FieldRef, MethodRef},
+    },
+    macros::see_jvm_spec,
+    types::{
+        field_type::{FieldType, PrimitiveType},
+        method_descriptor::ReturnType,
+    },
+};
+
+use super::Location;
+
+mod code;
+mod flags;
+
+use flags::FlagChecker;
+
+/// The element of a class that violates a constraint.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Element {
+    /// The class itself.
+    Class(ClassRef),
+    /// A field of the class.
+    Field(FieldRef),
+    /// A method of the class.
+    Method(MethodRef),
+    /// An instruction or an entry of a table in the code of a method.
+    Code(Location),
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Class(class) => class.fmt(f),
+            Self::Field(field) => field.fmt(f),
+            Self::Method(method) => method.fmt(f),
+            Self::Code(location) => location.fmt(f),
+        }
+    }
+}
+
+/// A constraint violated by a class file.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Violation {
+    /// Two access flags that exclude each other are both set.
+    #[error("The flags {0} and {1} cannot be set together")]
+    ConflictingFlags(&'static str, &'static str),
+    /// More than one of `PUBLIC`, `PRIVATE`, and `PROTECTED` is set.
+    #[error("At most one of PUBLIC, PRIVATE, and PROTECTED can be set")]
+    MultipleVisibilities,
+    /// An access flag required for the element is not set.
+    #[error("The flag {0} must be set")]
+    MissingFlag(&'static str),
+    /// An access flag not allowed for the element is set.
+    #[error("The flag {0} is not allowed")]
+    ForbiddenFlag(&'static str),
+    /// The superclass is missing or not allowed.
+    #[error("Invalid superclass")]
+    InvalidSuperClass,
+    /// An instance or class initialization method is malformed.
+    #[error("Invalid initialization method: {0}")]
+    InvalidInitializer(&'static str),
+    /// A method that must have code has none.
+    #[error("The method must have a Code attribute")]
+    MissingCode,
+    /// An abstract or native method has code.
+    #[error("Abstract and native methods cannot have a Code attribute")]
+    UnexpectedCode,
+    /// The `ConstantValue` attribute does not match the type of the field.
+    #[error("The constant {value} does not match the field type {field_type}")]
+    InvalidConstantValue {
+        /// The type of the field.
+        field_type: FieldType,
+        /// The constant value.
+        value: ConstantValue,
+    },
+    /// The code of a method is empty.
+    #[error("The code is empty")]
+    EmptyCode,
+    /// A branch does not target the start of an instruction.
+    #[error("Invalid branch target {0}")]
+    InvalidBranchTarget(ProgramCounter),
+    /// An instruction is not allowed in the version of the class file.
+    #[error("The instruction {0} is not allowed")]
+    ForbiddenInstruction(&'static str),
+    /// The range of an exception handler is empty or does not start and end at instructions.
+    #[error("Invalid exception handler range [{start}, {end})")]
+    InvalidExceptionRange {
+        /// The start of the range (inclusive).
+        start: ProgramCounter,
+        /// The end of the range (exclusive).
+        end: ProgramCounter,
+    },
+    /// An exception handler does not start at an instruction.
+    #[error("Invalid exception handler {0}")]
+    InvalidExceptionHandler(ProgramCounter),
+    /// A line number entry does not start at an instruction.
+    #[error("Invalid line number entry at {0}")]
+    InvalidLineNumber(ProgramCounter),
+    /// An attribute appears in a class file older than the version that defines it.
+    #[error("The {attribute} attribute requires class file version {since} or later")]
+    UnsupportedAttribute {
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The first major version defining the attribute.
+        since: u16,
+    },
+}
+
+/// A violation of a constraint found in a class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The element violating the constraint.
+    pub element: Element,
+    /// The violated constraint.
+    pub violation: Violation,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.element, self.violation)
+    }
+}
+
+/// A checker for the static constraints on a class file.
+#[doc = see_jvm_spec!(4, 8)]
+#[doc = see_jvm_spec!(4, 9)]
+#[derive(Debug)]
+pub struct ConstraintChecker<'a> {
+    class: &'a Class,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> ConstraintChecker<'a> {
+    /// Creates a checker for `class`.
+    #[must_use]
+    pub fn new(class: &'a Class) -> Self {
+        Self {
+            class,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Checks the class, its fields, and its methods, and returns the violations found.
+    #[must_use]
+    pub fn check(mut self) -> Vec<Diagnostic> {
+        self.check_class();
+        for field in &self.class.fields {
+            self.check_field(field);
+        }
+        for method in &self.class.methods {
+            self.check_method(method);
+        }
+        self.diagnostics
+    }
+
+    fn major_version(&self) -> u16 {
+        self.class.version.major()
+    }
+
+    fn is_interface(&self) -> bool {
+        self.class
+            .access_flags
+            .contains(class::AccessFlags::INTERFACE)
+    }
+
+    fn report(&mut self, element: &Element, violation: Violation) {
+        self.diagnostics.push(Diagnostic {
+            element: element.clone(),
+            violation,
+        });
+    }
+
+    /// Reports an attribute that is present in a class file older than `since`.
+    fn check_attribute(
+        &mut self,
+        element: &Element,
+        present: bool,
+        attribute: &'static str,
+        since: u16,
+    ) {
+        if present && self.major_version() < since {
+            self.report(
+                element,
+                Violation::UnsupportedAttribute { attribute, since },
+            );
+        }
+    }
+
+    #[doc = see_jvm_spec!(4, 1)]
+    fn check_class(&mut self) {
+        use class::AccessFlags;
+
+        let class = self.class;
+        let element = Element::Class(class.as_ref());
+        let mut flags = FlagChecker::new(class.access_flags, &element);
+        if class.access_flags.contains(AccessFlags::MODULE) {
+            flags.forbid_all_except(AccessFlags::MODULE);
+        } else if self.is_interface() {
+            flags.require(AccessFlags::ABSTRACT);
+            flags.forbid(AccessFlags::FINAL);
+            flags.forbid(AccessFlags::SUPER);
+            flags.forbid(AccessFlags::ENUM);
+        } else {
+            flags.forbid(AccessFlags::ANNOTATION);
+            flags.conflict(AccessFlags::FINAL, AccessFlags::ABSTRACT);
+        }
+        let mut diagnostics = flags.finish();
+        self.diagnostics.append(&mut diagnostics);
+
+        let is_valid_super_class = match &class.super_class {
+            None => {
+                class.binary_name == "java/lang/Object"
+                    || class.access_flags.contains(AccessFlags::MODULE)
+            }
+            Some(super_class) => {
+                !self.is_interface() || super_class.binary_name == "java/lang/Object"
+            }
+        };
+        if !is_valid_super_class {
+            self.report(&element, Violation::InvalidSuperClass);
+        }
+
+        let attributes = [
+            (class.signature.is_some(), "Signature", 49),
+            (class.enclosing_method.is_some(), "EnclosingMethod", 49),
+            (
+                class.source_debug_extension.is_some(),
+                "SourceDebugExtension",
+                49,
+            ),
+            (
+                !class.runtime_visible_annotations.is_empty(),
+                "RuntimeVisibleAnnotations",
+                49,
+            ),
+            (
+                !class.runtime_invisible_annotations.is_empty(),
+                "RuntimeInvisibleAnnotations",
+                49,
+            ),
+            (!class.bootstrap_methods.is_empty(), "BootstrapMethods", 51),
+            (
+                !class.runtime_visible_type_annotations.is_empty(),
+                "RuntimeVisibleTypeAnnotations",
+                52,
+            ),
+            (
+                !class.runtime_invisible_type_annotations.is_empty(),
+                "RuntimeInvisibleTypeAnnotations",
+                52,
+            ),
+            (class.module.is_some(), "Module", 53),
+            (!class.module_packages.is_empty(), "ModulePackages", 53),
+            (class.module_main_class.is_some(), "ModuleMainClass", 53),
+            (class.nest_host.is_some(), "NestHost", 55),
+            (!class.nest_members.is_empty(), "NestMembers", 55),
+            (class.record.is_some(), "Record", 60),
+            (
+                !class.permitted_subclasses.is_empty(),
+                "PermittedSubclasses",
+                61,
+            ),
+        ];
+        for (present, attribute, since) in attributes {
+            self.check_attribute(&element, present, attribute, since);
+        }
+    }
+
+    #[doc = see_jvm_spec!(4, 5)]
+    fn check_field(&mut self, field: &Field) {
+        use field::AccessFlags;
+
+        let element = Element::Field(field.as_ref());
+        let mut flags = FlagChecker::new(field.access_flags, &element);
+        flags.single_visibility(
+            AccessFlags::PUBLIC,
+            AccessFlags::PRIVATE,
+            AccessFlags::PROTECTED,
+        );
+        flags.conflict(AccessFlags::FINAL, AccessFlags::VOLATILE);
+        if self.is_interface() {
+            flags.require(AccessFlags::PUBLIC);
+            flags.require(AccessFlags::STATIC);
+            flags.require(AccessFlags::FINAL);
+            flags.forbid_all_except(
+                AccessFlags::PUBLIC
+                    | AccessFlags::STATIC
+                    | AccessFlags::FINAL
+                    | AccessFlags::SYNTHETIC,
+            );
+        }
+        let mut diagnostics = flags.finish();
+        self.diagnostics.append(&mut diagnostics);
+
+        // The `ConstantValue` attribute of non-static fields is ignored.
+        if let Some(value) = &field.constant_value {
+            if field.access_flags.contains(AccessFlags::STATIC)
+                && !is_constant_of(value, &field.field_type)
+            {
+                self.report(
+                    &element,
+                    Violation::InvalidConstantValue {
+                        field_type: field.field_type.clone(),
+                        value: value.clone(),
+                    },
+                );
+            }
+        }
+
+        let attributes = [
+            (field.signature.is_some(), "Signature", 49),
+            (
+                !field.runtime_visible_annotations.is_empty(),
+                "RuntimeVisibleAnnotations",
+                49,
+            ),
+            (
+                !field.runtime_invisible_annotations.is_empty(),
+                "RuntimeInvisibleAnnotations",
+                49,
+            ),
+            (
+                !field.runtime_visible_type_annotations.is_empty(),
+                "RuntimeVisibleTypeAnnotations",
+                52,
+            ),
+            (
+                !field.runtime_invisible_type_annotations.is_empty(),
+                "RuntimeInvisibleTypeAnnotations",
+                52,
+            ),
+        ];
+        for (present, attribute, since) in attributes {
+            self.check_attribute(&element, present, attribute, since);
+        }
+    }
+
+    fn check_method_flags(&mut self, method: &Method, element: &Element) {
+        use method::AccessFlags;
+
+        let is_class_initializer = method.name == Method::CLASS_INITIALIZER_NAME;
+        let mut flags = FlagChecker::new(method.access_flags, element);
+        if self.is_interface() && !is_class_initializer {
+            if self.major_version() < 52 {
+                flags.require(AccessFlags::PUBLIC);
+                flags.require(AccessFlags::ABSTRACT);
+            } else if !method
+                .access_flags
+                .intersects(AccessFlags::PUBLIC | AccessFlags::PRIVATE)
+            {
+                flags.require(AccessFlags::PUBLIC);
+            }
+            flags.forbid(AccessFlags::PROTECTED);
+            flags.forbid(AccessFlags::FINAL);
+            flags.forbid(AccessFlags::SYNCHRONIZED);
+            flags.forbid(AccessFlags::NATIVE);
+        }
+        flags.single_visibility(
+            AccessFlags::PUBLIC,
+            AccessFlags::PRIVATE,
+            AccessFlags::PROTECTED,
+        );
+        if method.access_flags.contains(AccessFlags::ABSTRACT) {
+            let mut conflicting = vec![
+                AccessFlags::PRIVATE,
+                AccessFlags::STATIC,
+                AccessFlags::FINAL,
+                AccessFlags::SYNCHRONIZED,
+                AccessFlags::NATIVE,
+            ];
+            if self.major_version() >= 46 && !self.class.version.is_strict_implied() {
+                conflicting.push(AccessFlags::STRICT);
+            }
+            for flag in conflicting {
+                flags.conflict(AccessFlags::ABSTRACT, flag);
+            }
+        }
+        if method.name == Method::CONSTRUCTOR_NAME {
+            flags.forbid(AccessFlags::STATIC);
+            flags.forbid(AccessFlags::FINAL);
+            flags.forbid(AccessFlags::SYNCHRONIZED);
+            flags.forbid(AccessFlags::BRIDGE);
+            flags.forbid(AccessFlags::NATIVE);
+            flags.forbid(AccessFlags::ABSTRACT);
+        }
+        if is_class_initializer && self.major_version() >= 51 {
+            flags.require(AccessFlags::STATIC);
+        }
+        let mut diagnostics = flags.finish();
+        self.diagnostics.append(&mut diagnostics);
+    }
+
+    #[doc = see_jvm_spec!(4, 6)]
+    fn check_method(&mut self, method: &Method) {
+        use method::AccessFlags;
+
+        let element = Element::Method(method.as_ref());
+        self.check_method_flags(method, &element);
+        let is_class_initializer = method.name == Method::CLASS_INITIALIZER_NAME;
+
+        if method.name == Method::CONSTRUCTOR_NAME {
+            if self.is_interface() {
+                self.report(
+                    &element,
+                    Violation::InvalidInitializer("interfaces cannot have constructors"),
+                );
+            }
+            if method.descriptor.return_type != ReturnType::Void {
+                self.report(
+                    &element,
+                    Violation::InvalidInitializer("constructors must return void"),
+                );
+            }
+        }
+        if is_class_initializer
+            && (method.descriptor.return_type != ReturnType::Void
+                || !method.descriptor.parameters_types.is_empty())
+        {
+            self.report(
+                &element,
+                Violation::InvalidInitializer("class initializers must have the descriptor ()V"),
+            );
+        }
+
+        let is_abstract_or_native = method
+            .access_flags
+            .intersects(AccessFlags::ABSTRACT | AccessFlags::NATIVE);
+        match &method.body {
+            Some(_) if is_abstract_or_native => self.report(&element, Violation::UnexpectedCode),
+            None if !is_abstract_or_native => self.report(&element, Violation::MissingCode),
+            Some(body) => {
+                let mut diagnostics = code::check(method, body, self.major_version());
+                self.diagnostics.append(&mut diagnostics);
+                let attributes = [
+                    (body.stack_map_table.is_some(), "StackMapTable", 50),
+                    (
+                        !body.runtime_visible_type_annotations.is_empty(),
+                        "RuntimeVisibleTypeAnnotations",
+                        52,
+                    ),
+                    (
+                        !body.runtime_invisible_type_annotations.is_empty(),
+                        "RuntimeInvisibleTypeAnnotations",
+                        52,
+                    ),
+                ];
+                for (present, attribute, since) in attributes {
+                    self.check_attribute(&element, present, attribute, since);
+                }
+            }
+            None => {}
+        }
+
+        let attributes = [
+            (method.signature.is_some(), "Signature", 49),
+            (
+                !method.runtime_visible_annotations.is_empty(),
+                "RuntimeVisibleAnnotations",
+                49,
+            ),
+            (
+                !method.runtime_invisible_annotations.is_empty(),
+                "RuntimeInvisibleAnnotations",
+                49,
+            ),
+            (
+                !method.runtime_visible_parameter_annotations.is_empty(),
+                "RuntimeVisibleParameterAnnotations",
+                49,
+            ),
+            (
+                !method.runtime_invisible_parameter_annotations.is_empty(),
+                "RuntimeInvisibleParameterAnnotations",
+                49,
+            ),
+            (method.annotation_default.is_some(), "AnnotationDefault", 49),
+            (
+                !method.runtime_visible_type_annotations.is_empty(),
+                "RuntimeVisibleTypeAnnotations",
+                52,
+            ),
+            (
+                !method.runtime_invisible_type_annotations.is_empty(),
+                "RuntimeInvisibleTypeAnnotations",
+                52,
+            ),
+            (!method.parameters.is_empty(), "MethodParameters", 52),
+        ];
+        for (present, attribute, since) in attributes {
+            self.check_attribute(&element, present, attribute, since);
+        }
+    }
+}
+
+/// Checks whether `value` can be the `ConstantValue` of a field of type `field_type`.
+#[doc = see_jvm_spec!(4, 7, 2)]
+fn is_constant_of(value: &ConstantValue, field_type: &FieldType) -> bool {
+    use PrimitiveType::{Boolean, Byte, Char, Double, Float, Int, Long, Short};
+
+    match (value, field_type) {
+        (ConstantValue::Integer(_), FieldType::Base(Int | Short | Char | Byte | Boolean))
+        | (ConstantValue::Long(_), FieldType::Base(Long))
+        | (ConstantValue::Float(_), FieldType::Base(Float))
+        | (ConstantValue::Double(_), FieldType::Base(Double)) => true,
+        (ConstantValue::String(_), FieldType::Object(class)) => {
+            class.binary_name == "java/lang/String"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jvm::field::JavaString;
+
+    use super::*;
+
+    #[test]
+    fn constant_values() {
+        let string = FieldType::Object(ClassRef::new("java/lang/String"));
+        assert!(is_constant_of(
+            &ConstantValue::Integer(1),
+            &FieldType::Base(PrimitiveType::Char)
+        ));
+        assert!(is_constant_of(
+            &ConstantValue::String(JavaString::Utf8("a".to_owned())),
+            &string
+        ));
+        assert!(!is_constant_of(
+            &ConstantValue::Long(1),
+            &FieldType::Base(PrimitiveType::Int)
+        ));
+        assert!(!is_constant_of(
+            &ConstantValue::Integer(1),
+            &FieldType::Object(ClassRef::new("java/lang/Integer"))
+        ));
+        assert!(!is_constant_of(&ConstantValue::Null, &string));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    analysis::{
+        call_graph::{CallGraph, Error},
+        resolution::MethodResolver,
+        Location,
+    },
+    ir::{expression::Expression, MokaIRMethod, MokaIRMethodExt, MokaInstruction},
+    jvm::{
+        class_loader::{CachingClassLoader, ClassPath},
+        code::{LineNumberTableEntry, ProgramCounter},
+        references::{FieldRef, MethodRef},
+    },
+};
+
+/// An interprocedural control flow graph assembled from the control flow graphs of the methods in
+/// a [`CallGraph`].
+#[derive(Debug)]
+pub struct InterproceduralCfg {
+    methods: BTreeMap<MethodRef, MokaIRMethod>,
+    call_graph: CallGraph,
+    line_numbers: BTreeMap<MethodRef, Vec<LineNumberTableEntry>>,
+    resolved_fields: BTreeMap<FieldRef, FieldRef>,
+    errors: Vec<Error>,
+}
+
+impl InterproceduralCfg {
+    /// Creates an interprocedural control flow graph from a call graph and the Moka IR of the
+    /// methods in it.
+    /// Methods without Moka IR are treated as opaque, i.e., calls to them only have
+    /// call-to-return flows.
+    pub fn new(call_graph: CallGraph, methods: impl IntoIterator<Item = MokaIRMethod>) -> Self {
+        let methods = methods
+            .into_iter()
+            .map(|it| (method_ref_of(&it), it))
+            .collect();
+        Self {
+            methods,
+            call_graph,
+            line_numbers: BTreeMap::new(),
+            resolved_fields: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Creates an interprocedural control flow graph by loading the methods in the call graph
+    /// with `class_loader` and converting them to Moka IR.
+    /// Methods that cannot be loaded, have no body, or cannot be converted are treated as opaque.
+    /// The fields accessed by the methods are resolved with `class_loader` as well (see
+    /// [`InterproceduralCfg::resolve_field`]).
+    pub fn from_call_graph<P: ClassPath>(
+        call_graph: CallGraph,
+        class_loader: &CachingClassLoader<P>,
+    ) -> Self {
+        let mut methods = Vec::new();
+        let mut errors = Vec::new();
+        let mut line_numbers = BTreeMap::new();
+        let loaded_methods = call_graph.methods().filter_map(|method_ref| {
+            class_loader
+                .load_class(method_ref.owner.binary_name.as_str())
+                .ok()?
+                .get_method(&method_ref.name, &method_ref.descriptor)
+        });
+        for method in loaded_methods {
+            let Some(body) = &method.body else {
+                continue;
+            };
+            if let Some(line_number_table) = &body.line_number_table {
+                line_numbers.insert(method.as_ref(), line_number_table.clone());
+            }
+            match method.brew() {
+                Ok(ir) => methods.push(ir),
+                Err(source) => errors.push(Error::Brewing {
+                    method: method.as_ref(),
+                    source,
+                }),
+            }
+        }
+        let mut icfg = Self::new(call_graph, methods);
+        let resolver = MethodResolver::new(class_loader);
+        let accessed_fields = icfg
+            .methods()
+            .flat_map(|it| it.instructions.iter())
+            .filter_map(|(_, insn)| match insn {
+                MokaInstruction::Definition {
+                    expr: Expression::Field(access),
+                    ..
+                } => Some(access.field()),
+                _ => None,
+            });
+        let resolved_fields = accessed_fields
+            .filter_map(|field_ref| {
+                let declared = resolver.resolve_field(field_ref).ok()?.as_ref();
+                (declared != *field_ref).then(|| (field_ref.clone(), declared))
+            })
+            .collect();
+        icfg.resolved_fields = resolved_fields;
+        icfg.line_numbers = line_numbers;
+        icfg.errors = errors;
+        icfg
+    }
+
+    /// Returns the Moka IR of a method.
+    #[must_use]
+    pub fn method(&self, method: &MethodRef) -> Option<&MokaIRMethod> {
+        self.methods.get(method)
+    }
+
+    /// Returns the methods with Moka IR.
+    pub fn methods(&self) -> impl Iterator<Item = &MokaIRMethod> {
+        self.methods.values()
+    }
+
+    /// Returns the instruction at a location.
+    #[must_use]
+    pub fn instruction_at(&self, location: &Location) -> Option<&MokaInstruction> {
+        self.method(&location.method)?
+            .instructions
+            .get(&location.pc)
+    }
+
+    /// Returns the first location of a method.
+    #[must_use]
+    pub fn start_point(&self, method: &MethodRef) -> Location {
+        let pc = self.method(method).map_or(ProgramCounter::ZERO, |it| {
+            it.control_flow_graph.entry_point()
+        });
+        Location {
+            method: method.clone(),
+            pc,
+        }
+    }
+
+    /// Returns the intraprocedural successors of a location.
+    pub fn successors<'a>(&'a self, location: &'a Location) -> impl Iterator<Item = Location> + 'a {
+        self.method(&location.method)
+            .and_then(|it| it.control_flow_graph.edges_from(location.pc))
+            .into_iter()
+            .flatten()
+            .map(|(_, pc, _)| Location {
+                method: location.method.clone(),
+                pc,
+            })
+    }
+
+    /// Checks whether a location exits its method, i.e., it has no successors.
+    #[must_use]
+    pub fn is_exit(&self, location: &Location) -> bool {
+        self.successors(location).next().is_none()
+    }
+
+    /// Returns the methods with Moka IR that may be called at a location.
+    pub fn callees<'a>(&'a self, call_site: &Location) -> impl Iterator<Item = &'a MethodRef> {
+        self.call_graph
+            .callees(&call_site.method, call_site.pc)
+            .filter(|it| self.methods.contains_key(*it))
+    }
+
+    /// Checks whether a location is a call site in the call graph.
+    #[must_use]
+    pub fn is_call(&self, location: &Location) -> bool {
+        self.call_graph
+            .callees(&location.method, location.pc)
+            .next()
+            .is_some()
+    }
+
+    /// Returns the source line of a location, or [`None`] if the method has no line number table.
+    #[must_use]
+    pub fn line_number_at(&self, location: &Location) -> Option<u16> {
+        self.line_numbers
+            .get(&location.method)?
+            .iter()
+            .filter(|it| it.start_pc <= location.pc)
+            .max_by_key(|it| it.start_pc)
+            .map(|it| it.line_number)
+    }
+
+    /// Returns the field that a field reference resolves to, i.e., the reference to the field
+    /// through its declaring class.
+    /// References that are not accessed in the methods or cannot be resolved, and all references
+    /// if the graph is not created with [`InterproceduralCfg::from_call_graph`], are returned as
+    /// is.
+    #[must_use]
+    pub fn resolve_field<'a>(&'a self, field: &'a FieldRef) -> &'a FieldRef {
+        self.resolved_fields.get(field).unwrap_or(field)
+    }
+
+    /// Returns the underlying call graph.
+    #[must_use]
+    pub fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
+    /// Returns the errors occurred when converting methods to Moka IR.
+    #[must_use]
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+}
+
+fn method_ref_of(method: &MokaIRMethod) -> MethodRef {
+    MethodRef {
+        owner: method.owner.clone(),
+        name: method.name.clone(),
+        descriptor: method.descriptor.clone(),
+    }
+}
@@ -0,0 +1,300 @@
+//! Interprocedural dataflow analysis with the IFDS and IDE frameworks.
+//!
+//! An [`IfdsProblem`] propagates a finite set of facts along an [`InterproceduralCfg`], while an
+//! [`IdeProblem`] additionally computes a value for each fact with edge functions.
+//! Both are solved with the tabulation algorithm, which computes a summary for each pair of a
+//! method and an entry fact and reuses it at every call site with the same fact.
+//!
+//! The zero fact (Λ) is always propagated along all edges with the identity function, so flow
+//! functions only need to specify the facts they generate from it.
+//!
+//! See Reps, Horwitz and Sagiv, "Precise Interprocedural Dataflow Analysis via Graph
+//! Reachability" (POPL '95), and Sagiv, Reps and Horwitz, "Precise Interprocedural Dataflow
+//! Analysis with Applications to Constant Propagation" (TCS '96).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::jvm::references::MethodRef;
+
+use super::Location;
+
+mod icfg;
+mod solver;
+
+pub use icfg::InterproceduralCfg;
+
+/// An IDE problem, i.e., a distributive dataflow problem whose facts are associated with values
+/// computed by edge functions.
+pub trait IdeProblem {
+    /// The type of the dataflow facts.
+    type Fact: Clone + Ord;
+    /// The type of the values associated with the facts.
+    type Value: Clone + PartialEq;
+    /// The type of the edge functions that transform the values.
+    type EdgeFunction: Clone + PartialEq;
+
+    /// Returns the zero fact (Λ), which holds at every reachable location.
+    fn zero(&self) -> Self::Fact;
+
+    /// Returns the methods where the analysis starts and the facts holding at their entries.
+    fn initial_seeds(&self) -> Vec<(MethodRef, Self::Fact)>;
+
+    /// Computes the facts at `successor` from a fact at `current`, where `current` is not a call
+    /// site or an exit of a method.
+    fn normal_flow(
+        &self,
+        current: &Location,
+        successor: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, Self::EdgeFunction)>;
+
+    /// Computes the facts at the entry of `callee` from a fact at `call_site`.
+    fn call_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, Self::EdgeFunction)>;
+
+    /// Computes the facts at `return_site` from a fact at `exit` of `callee`.
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        exit: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, Self::EdgeFunction)>;
+
+    /// Computes the facts at `return_site` from a fact at `call_site` that are not affected by
+    /// the callees (e.g., facts about local variables not passed to the callees).
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, Self::EdgeFunction)>;
+
+    /// Returns the top element of the value lattice, i.e., the identity of [`join_values`](IdeProblem::join_values).
+    fn top_value(&self) -> Self::Value;
+
+    /// Returns the value of the initial seeds.
+    fn initial_value(&self) -> Self::Value;
+
+    /// Joins two values where the control flow merges.
+    fn join_values(&self, lhs: &Self::Value, rhs: &Self::Value) -> Self::Value;
+
+    /// Returns the identity edge function.
+    fn identity(&self) -> Self::EdgeFunction;
+
+    /// Applies an edge function to a value.
+    fn apply(&self, function: &Self::EdgeFunction, value: &Self::Value) -> Self::Value;
+
+    /// Composes two edge functions, applying `first` and then `second`.
+    fn compose(
+        &self,
+        first: &Self::EdgeFunction,
+        second: &Self::EdgeFunction,
+    ) -> Self::EdgeFunction;
+
+    /// Joins two edge functions.
+    fn join_functions(
+        &self,
+        lhs: &Self::EdgeFunction,
+        rhs: &Self::EdgeFunction,
+    ) -> Self::EdgeFunction;
+
+    /// Solves the problem on the given interprocedural control flow graph.
+    fn solve(&self, icfg: &InterproceduralCfg) -> IdeResults<Self::Fact, Self::Value>
+    where
+        Self: Sized,
+    {
+        solver::solve(self, icfg)
+    }
+}
+
+/// An IFDS problem, i.e., an interprocedural, finite, distributive, subset problem.
+pub trait IfdsProblem {
+    /// The type of the dataflow facts.
+    type Fact: Clone + Ord;
+
+    /// Returns the zero fact (Λ), which holds at every reachable location.
+    fn zero(&self) -> Self::Fact;
+
+    /// Returns the methods where the analysis starts and the facts holding at their entries.
+    fn initial_seeds(&self) -> Vec<(MethodRef, Self::Fact)>;
+
+    /// Computes the facts at `successor` from a fact at `current`, where `current` is not a call
+    /// site or an exit of a method.
+    fn normal_flow(
+        &self,
+        current: &Location,
+        successor: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact>;
+
+    /// Computes the facts at the entry of `callee` from a fact at `call_site`.
+    fn call_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact>;
+
+    /// Computes the facts at `return_site` from a fact at `exit` of `callee`.
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        exit: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact>;
+
+    /// Computes the facts at `return_site` from a fact at `call_site` that are not affected by
+    /// the callees.
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact>;
+
+    /// Solves the problem on the given interprocedural control flow graph.
+    fn solve(&self, icfg: &InterproceduralCfg) -> IfdsResults<Self::Fact>
+    where
+        Self: Sized,
+    {
+        IfdsResults(Ifds(self).solve(icfg))
+    }
+}
+
+/// Solves an [`IfdsProblem`] as an [`IdeProblem`] with trivial values and edge functions.
+struct Ifds<'p, P>(&'p P);
+
+impl<P: IfdsProblem> IdeProblem for Ifds<'_, P> {
+    type Fact = P::Fact;
+    type Value = ();
+    type EdgeFunction = ();
+
+    fn zero(&self) -> Self::Fact {
+        self.0.zero()
+    }
+
+    fn initial_seeds(&self) -> Vec<(MethodRef, Self::Fact)> {
+        self.0.initial_seeds()
+    }
+
+    fn normal_flow(
+        &self,
+        current: &Location,
+        successor: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, ())> {
+        with_unit(self.0.normal_flow(current, successor, fact))
+    }
+
+    fn call_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, ())> {
+        with_unit(self.0.call_flow(call_site, callee, fact))
+    }
+
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        exit: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, ())> {
+        with_unit(
+            self.0
+                .return_flow(call_site, callee, exit, return_site, fact),
+        )
+    }
+
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        return_site: &Location,
+        fact: &Self::Fact,
+    ) -> Vec<(Self::Fact, ())> {
+        with_unit(self.0.call_to_return_flow(call_site, return_site, fact))
+    }
+
+    fn top_value(&self) {}
+
+    fn initial_value(&self) {}
+
+    fn join_values(&self, (): &(), (): &()) {}
+
+    fn identity(&self) {}
+
+    fn apply(&self, (): &(), (): &()) {}
+
+    fn compose(&self, (): &(), (): &()) {}
+
+    fn join_functions(&self, (): &(), (): &()) {}
+}
+
+fn with_unit<F>(facts: BTreeSet<F>) -> Vec<(F, ())> {
+    facts.into_iter().map(|it| (it, ())).collect()
+}
+
+/// The facts at the entry of a method and the facts they lead to at the exits.
+pub type Summary<F> = BTreeMap<F, BTreeSet<(Location, F)>>;
+
+/// The result of solving an [`IdeProblem`].
+#[derive(Debug, Clone)]
+pub struct IdeResults<F, V> {
+    values: BTreeMap<Location, BTreeMap<F, V>>,
+    summaries: BTreeMap<MethodRef, Summary<F>>,
+}
+
+impl<F: Ord, V> IdeResults<F, V> {
+    /// Returns the facts holding at a location and their values, including the zero fact.
+    pub fn values_at(&self, location: &Location) -> impl Iterator<Item = (&F, &V)> {
+        self.values.get(location).into_iter().flatten()
+    }
+
+    /// Returns the value of a fact at a location, or [`None`] if the fact does not hold.
+    #[must_use]
+    pub fn value_at(&self, location: &Location, fact: &F) -> Option<&V> {
+        self.values.get(location)?.get(fact)
+    }
+
+    /// Returns the summary of a method, which maps the facts at its entry to the facts at its
+    /// exits.
+    #[must_use]
+    pub fn summary(&self, method: &MethodRef) -> Option<&Summary<F>> {
+        self.summaries.get(method)
+    }
+}
+
+/// The result of solving an [`IfdsProblem`].
+#[derive(Debug, Clone)]
+pub struct IfdsResults<F>(IdeResults<F, ()>);
+
+impl<F: Ord> IfdsResults<F> {
+    /// Returns the facts holding at a location, including the zero fact.
+    pub fn facts_at(&self, location: &Location) -> impl Iterator<Item = &F> {
+        self.0.values_at(location).map(|(fact, ())| fact)
+    }
+
+    /// Checks whether a fact holds at a location.
+    #[must_use]
+    pub fn holds_at(&self, location: &Location, fact: &F) -> bool {
+        self.0.value_at(location, fact).is_some()
+    }
+
+    /// Returns the summary of a method, which maps the facts at its entry to the facts at its
+    /// exits.
+    #[must_use]
+    pub fn summary(&self, method: &MethodRef) -> Option<&Summary<F>> {
+        self.0.summary(method)
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{analysis::Location, jvm::references::MethodRef};
+
+use super::{IdeProblem, IdeResults, InterproceduralCfg, Summary};
+
+/// Maps the facts at the start point of a method to edge functions.
+type JumpFunctions<P> = BTreeMap<<P as IdeProblem>::Fact, <P as IdeProblem>::EdgeFunction>;
+
+/// Maps a pair of a location and a fact to edge functions.
+type EdgeFunctions<P> =
+    BTreeMap<(Location, <P as IdeProblem>::Fact), <P as IdeProblem>::EdgeFunction>;
+
+pub(super) fn solve<P: IdeProblem>(
+    problem: &P,
+    icfg: &InterproceduralCfg,
+) -> IdeResults<P::Fact, P::Value> {
+    let mut solver = Solver {
+        problem,
+        icfg,
+        zero: problem.zero(),
+        jump_functions: BTreeMap::new(),
+        worklist: VecDeque::new(),
+        incoming: BTreeMap::new(),
+        end_summaries: BTreeMap::new(),
+    };
+    let seeds: Vec<_> = problem
+        .initial_seeds()
+        .into_iter()
+        .filter(|(method, _)| icfg.method(method).is_some())
+        .collect();
+    for (method, fact) in &seeds {
+        let start_point = icfg.start_point(method);
+        solver.propagate(fact, start_point, fact.clone(), problem.identity());
+    }
+    solver.compute_jump_functions();
+    let values = solver.compute_values(seeds);
+    let summaries = solver.summaries();
+    IdeResults { values, summaries }
+}
+
+struct Solver<'a, P: IdeProblem> {
+    problem: &'a P,
+    icfg: &'a InterproceduralCfg,
+    zero: P::Fact,
+    /// The path edges from the start point of a method to a location, indexed by the target.
+    jump_functions: BTreeMap<(Location, P::Fact), JumpFunctions<P>>,
+    worklist: VecDeque<(P::Fact, Location, P::Fact)>,
+    /// The call edges to the start point of a method, indexed by the callee and the entry fact.
+    incoming: BTreeMap<(MethodRef, P::Fact), EdgeFunctions<P>>,
+    /// The path edges from the start point of a method to its exits.
+    end_summaries: BTreeMap<(MethodRef, P::Fact), EdgeFunctions<P>>,
+}
+
+impl<P: IdeProblem> Solver<'_, P> {
+    fn propagate(
+        &mut self,
+        source_fact: &P::Fact,
+        target: Location,
+        target_fact: P::Fact,
+        function: P::EdgeFunction,
+    ) {
+        let functions = self
+            .jump_functions
+            .entry((target.clone(), target_fact.clone()))
+            .or_default();
+        let function = match functions.get(source_fact) {
+            Some(current) => {
+                let joined = self.problem.join_functions(current, &function);
+                if joined == *current {
+                    return;
+                }
+                joined
+            }
+            None => function,
+        };
+        functions.insert(source_fact.clone(), function);
+        self.worklist
+            .push_back((source_fact.clone(), target, target_fact));
+    }
+
+    /// Propagates the zero fact along every edge in addition to the facts from the flow functions.
+    fn with_zero(
+        &self,
+        fact: &P::Fact,
+        mut outputs: Vec<(P::Fact, P::EdgeFunction)>,
+    ) -> Vec<(P::Fact, P::EdgeFunction)> {
+        if *fact == self.zero && outputs.iter().all(|(it, _)| *it != self.zero) {
+            outputs.push((self.zero.clone(), self.problem.identity()));
+        }
+        outputs
+    }
+
+    fn compose_all<'f>(
+        &self,
+        functions: impl IntoIterator<Item = &'f P::EdgeFunction>,
+    ) -> P::EdgeFunction
+    where
+        P::EdgeFunction: 'f,
+    {
+        functions
+            .into_iter()
+            .fold(self.problem.identity(), |composed, it| {
+                self.problem.compose(&composed, it)
+            })
+    }
+
+    fn compute_jump_functions(&mut self) {
+        while let Some((source_fact, location, fact)) = self.worklist.pop_front() {
+            let function =
+                self.jump_functions[&(location.clone(), fact.clone())][&source_fact].clone();
+            if self.icfg.is_call(&location) {
+                self.process_call(&source_fact, &location, &fact, &function);
+            } else if self.icfg.is_exit(&location) {
+                self.process_exit(&source_fact, &location, &fact, &function);
+            } else {
+                self.process_normal(&source_fact, &location, &fact, &function);
+            }
+        }
+    }
+
+    fn process_normal(
+        &mut self,
+        source_fact: &P::Fact,
+        location: &Location,
+        fact: &P::Fact,
+        function: &P::EdgeFunction,
+    ) {
+        let successors: Vec<_> = self.icfg.successors(location).collect();
+        for successor in successors {
+            let outputs = self.problem.normal_flow(location, &successor, fact);
+            for (output, output_function) in self.with_zero(fact, outputs) {
+                let composed = self.problem.compose(function, &output_function);
+                self.propagate(source_fact, successor.clone(), output, composed);
+            }
+        }
+    }
+
+    fn process_call(
+        &mut self,
+        source_fact: &P::Fact,
+        call_site: &Location,
+        fact: &P::Fact,
+        function: &P::EdgeFunction,
+    ) {
+        let return_sites: Vec<_> = self.icfg.successors(call_site).collect();
+        let callees: Vec<_> = self.icfg.callees(call_site).cloned().collect();
+        for callee in callees {
+            let outputs = self.problem.call_flow(call_site, &callee, fact);
+            for (entry_fact, call_function) in self.with_zero(fact, outputs) {
+                let start_point = self.icfg.start_point(&callee);
+                let identity = self.problem.identity();
+                self.propagate(&entry_fact, start_point, entry_fact.clone(), identity);
+
+                let call_functions = self
+                    .incoming
+                    .entry((callee.clone(), entry_fact.clone()))
+                    .or_default();
+                let call_function = match call_functions.get(&(call_site.clone(), fact.clone())) {
+                    Some(current) => self.problem.join_functions(current, &call_function),
+                    None => call_function,
+                };
+                call_functions.insert((call_site.clone(), fact.clone()), call_function.clone());
+
+                // Reuses the summaries already computed for the callee.
+                let summaries = self
+                    .end_summaries
+                    .get(&(callee.clone(), entry_fact))
+                    .cloned()
+                    .unwrap_or_default();
+                for ((exit, exit_fact), summary_function) in summaries {
+                    for return_site in &return_sites {
+                        let outputs = self.problem.return_flow(
+                            call_site,
+                            &callee,
+                            &exit,
+                            return_site,
+                            &exit_fact,
+                        );
+                        for (output, return_function) in self.with_zero(&exit_fact, outputs) {
+                            let composed = self.compose_all([
+                                function,
+                                &call_function,
+                                &summary_function,
+                                &return_function,
+                            ]);
+                            self.propagate(source_fact, return_site.clone(), output, composed);
+                        }
+                    }
+                }
+            }
+        }
+        for return_site in return_sites {
+            let outputs = self
+                .problem
+                .call_to_return_flow(call_site, &return_site, fact);
+            for (output, output_function) in self.with_zero(fact, outputs) {
+                let composed = self.problem.compose(function, &output_function);
+                self.propagate(source_fact, return_site.clone(), output, composed);
+            }
+        }
+    }
+
+    fn process_exit(
+        &mut self,
+        source_fact: &P::Fact,
+        exit: &Location,
+        fact: &P::Fact,
+        function: &P::EdgeFunction,
+    ) {
+        let method = exit.method.clone();
+        self.end_summaries
+            .entry((method.clone(), source_fact.clone()))
+            .or_default()
+            .insert((exit.clone(), fact.clone()), function.clone());
+        let callers = self
+            .incoming
+            .get(&(method.clone(), source_fact.clone()))
+            .cloned()
+            .unwrap_or_default();
+        for ((call_site, call_fact), call_function) in callers {
+            let caller_functions = self
+                .jump_functions
+                .get(&(call_site.clone(), call_fact))
+                .cloned()
+                .unwrap_or_default();
+            let return_sites: Vec<_> = self.icfg.successors(&call_site).collect();
+            for return_site in return_sites {
+                let outputs =
+                    self.problem
+                        .return_flow(&call_site, &method, exit, &return_site, fact);
+                for (output, return_function) in self.with_zero(fact, outputs) {
+                    for (caller_source_fact, caller_function) in &caller_functions {
+                        let composed = self.compose_all([
+                            caller_function,
+                            &call_function,
+                            function,
+                            &return_function,
+                        ]);
+                        self.propagate(
+                            caller_source_fact,
+                            return_site.clone(),
+                            output.clone(),
+                            composed,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_values(
+        &self,
+        seeds: Vec<(MethodRef, P::Fact)>,
+    ) -> BTreeMap<Location, BTreeMap<P::Fact, P::Value>> {
+        // Phase II (i): computes the values at the start points of the methods.
+        let mut start_values: BTreeMap<(MethodRef, P::Fact), P::Value> = BTreeMap::new();
+        let mut worklist = VecDeque::new();
+        for seed in seeds {
+            let value = match start_values.get(&seed) {
+                Some(current) => self
+                    .problem
+                    .join_values(current, &self.problem.initial_value()),
+                None => self.problem.initial_value(),
+            };
+            start_values.insert(seed.clone(), value);
+            worklist.push_back(seed);
+        }
+        let mut calls_by_caller: BTreeMap<&MethodRef, Vec<_>> = BTreeMap::new();
+        for ((callee, entry_fact), call_functions) in &self.incoming {
+            for ((call_site, call_fact), call_function) in call_functions {
+                calls_by_caller.entry(&call_site.method).or_default().push((
+                    call_site,
+                    call_fact,
+                    callee,
+                    entry_fact,
+                    call_function,
+                ));
+            }
+        }
+        while let Some((method, source_fact)) = worklist.pop_front() {
+            let value = start_values[&(method.clone(), source_fact.clone())].clone();
+            let calls = calls_by_caller.get(&method).into_iter().flatten();
+            for (call_site, call_fact, callee, entry_fact, call_function) in calls {
+                let Some(function) = self
+                    .jump_functions
+                    .get(&((*call_site).clone(), (*call_fact).clone()))
+                    .and_then(|it| it.get(&source_fact))
+                else {
+                    continue;
+                };
+                let incoming_value = self
+                    .problem
+                    .apply(call_function, &self.problem.apply(function, &value));
+                let key = ((*callee).clone(), (*entry_fact).clone());
+                let new_value = match start_values.get(&key) {
+                    Some(current) => self.problem.join_values(current, &incoming_value),
+                    None => self
+                        .problem
+                        .join_values(&self.problem.top_value(), &incoming_value),
+                };
+                if start_values.get(&key) != Some(&new_value) {
+                    start_values.insert(key.clone(), new_value);
+                    worklist.push_back(key);
+                }
+            }
+        }
+
+        // Phase II (ii): computes the values at the other locations from the jump functions.
+        let mut values: BTreeMap<Location, BTreeMap<P::Fact, P::Value>> = BTreeMap::new();
+        for ((location, fact), functions) in &self.jump_functions {
+            for (source_fact, function) in functions {
+                let Some(start_value) =
+                    start_values.get(&(location.method.clone(), source_fact.clone()))
+                else {
+                    continue;
+                };
+                let value = self.problem.apply(function, start_value);
+                let values_at = values.entry(location.clone()).or_default();
+                let value = match values_at.get(fact) {
+                    Some(current) => self.problem.join_values(current, &value),
+                    None => value,
+                };
+                values_at.insert(fact.clone(), value);
+            }
+        }
+        values
+    }
+
+    fn summaries(&self) -> BTreeMap<MethodRef, Summary<P::Fact>> {
+        let mut summaries: BTreeMap<MethodRef, Summary<P::Fact>> = BTreeMap::new();
+        for ((method, entry_fact), exits) in &self.end_summaries {
+            summaries
+                .entry(method.clone())
+                .or_default()
+                .entry(entry_fact.clone())
+                .or_default()
+                .extend(exits.keys().cloned());
+        }
+        summaries
+    }
+}
@@ -1,2 +1,29 @@
 //! APIs for static analysis.
+pub mod call_graph;
+pub mod class_hierarchy;
+pub mod constraints;
 pub mod fixed_point;
+pub mod ifds;
+pub mod points_to;
+pub mod resolution;
+pub mod taint;
+pub mod verifier;
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::jvm::{code::ProgramCounter, references::MethodRef};
+
+/// A location in a method.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    /// The method containing the location.
+    pub method: MethodRef,
+    /// The program counter of the location.
+    pub pc: ProgramCounter,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.method, self.pc)
+    }
+}
@@ -0,0 +1,272 @@
+//! Andersen-style points-to analysis over Moka IR.
+//!
+//! The analysis is flow-insensitive and uses allocation sites (i.e., [`Expression::New`](crate::ir::expression::Expression::New),
+//! [`ArrayOperation::New`](crate::ir::expression::ArrayOperation::New),
+//! [`ArrayOperation::NewMultiDim`](crate::ir::expression::ArrayOperation::NewMultiDim), string
+//! and class literals, and closures) as abstract objects.
+//! Field references are resolved to the fields they refer to, so fields are distinguished by their
+//! declaring class and name, and all elements of an array are merged into a single abstract
+//! location.
+//! The call graph is constructed on the fly using the types of the receiver objects.
+//!
+//! The following are not modeled: exceptions (i.e., `throw` and caught exceptions), the arguments
+//! of calls on closures, reflection, and native methods.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    ir::Identifier,
+    jvm::references::{ClassRef, FieldRef, MethodRef},
+    types::field_type::FieldType,
+};
+
+use super::{call_graph::CallGraph, Location};
+
+mod solver;
+
+pub use solver::PointsToBuilder;
+
+/// How the analysis distinguishes different invocations of the same method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContextSensitivity {
+    /// All invocations of a method share a single context.
+    #[default]
+    Insensitive,
+    /// Invocations are distinguished by the last `k` call sites.
+    CallSite(usize),
+    /// Invocations of instance methods are distinguished by the allocation sites of the receiver
+    /// object and the `k - 1` allocation sites of its heap context.
+    /// Static methods are analyzed in the context of their callers.
+    Object(usize),
+}
+
+impl ContextSensitivity {
+    /// Returns the maximum length of the contexts of methods.
+    #[must_use]
+    pub const fn depth(self) -> usize {
+        match self {
+            Self::Insensitive => 0,
+            Self::CallSite(k) | Self::Object(k) => k,
+        }
+    }
+}
+
+/// The context in which a method is analyzed.
+/// Depending on the [`ContextSensitivity`], it consists of call sites or allocation sites, the
+/// most recent one first.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Context(Vec<Location>);
+
+impl Context {
+    /// Creates an empty context.
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Returns the elements of the context, the most recent one first.
+    #[must_use]
+    pub fn elements(&self) -> &[Location] {
+        &self.0
+    }
+
+    /// Creates a new context by prepending `location` and keeping at most `depth` elements.
+    #[must_use]
+    pub fn push(&self, location: Location, depth: usize) -> Self {
+        let elements = std::iter::once(location)
+            .chain(self.0.iter().cloned())
+            .take(depth)
+            .collect();
+        Self(elements)
+    }
+
+    /// Creates a new context by keeping at most the first `depth` elements.
+    #[must_use]
+    pub fn truncate(&self, depth: usize) -> Self {
+        Self(self.0.iter().take(depth).cloned().collect())
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, location) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{location}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// The type of an [`AbstractObject`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    /// An instance of a class.
+    Instance(ClassRef),
+    /// An array of the given type.
+    Array(FieldType),
+    /// A closure implementing the given functional interface.
+    Closure(ClassRef),
+}
+
+/// An abstract object representing the runtime objects allocated at the same site in the same
+/// heap context.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbstractObject {
+    /// Where the object is allocated.
+    pub site: Location,
+    /// The type of the object.
+    pub kind: ObjectKind,
+    /// The context of the method allocating the object.
+    pub context: Context,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Field {
+    /// An instance field, identified by the reference to its declaring class.
+    Instance(FieldRef),
+    ArrayElement,
+}
+
+/// The result of a points-to analysis.
+#[derive(Debug, Default)]
+pub struct PointsTo {
+    objects: Vec<AbstractObject>,
+    object_ids: BTreeMap<AbstractObject, usize>,
+    variables: BTreeMap<(MethodRef, Identifier, Context), BTreeSet<usize>>,
+    instance_fields: BTreeMap<(usize, Field), BTreeSet<usize>>,
+    static_fields: BTreeMap<FieldRef, BTreeSet<usize>>,
+    resolved_fields: BTreeMap<FieldRef, FieldRef>,
+    reachable_methods: BTreeSet<(MethodRef, Context)>,
+    call_graph: CallGraph,
+}
+
+impl PointsTo {
+    /// Returns the objects that `id` in `method` may point to in any context.
+    #[must_use]
+    pub fn points_to(&self, method: &MethodRef, id: Identifier) -> BTreeSet<&AbstractObject> {
+        self.variables
+            .range((method.clone(), id, Context::empty())..)
+            .take_while(|((m, i, _), _)| m == method && *i == id)
+            .flat_map(|(_, objects)| self.objects_of(objects))
+            .collect()
+    }
+
+    /// Returns the objects that `id` in `method` may point to in the given context.
+    #[must_use]
+    pub fn points_to_in_context(
+        &self,
+        method: &MethodRef,
+        context: &Context,
+        id: Identifier,
+    ) -> BTreeSet<&AbstractObject> {
+        self.variables
+            .get(&(method.clone(), id, context.clone()))
+            .map(|objects| self.objects_of(objects).collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks whether `lhs` and `rhs` in `method` may point to the same object.
+    #[must_use]
+    pub fn may_alias(&self, method: &MethodRef, lhs: Identifier, rhs: Identifier) -> bool {
+        let lhs = self.points_to(method, lhs);
+        let rhs = self.points_to(method, rhs);
+        !lhs.is_disjoint(&rhs)
+    }
+
+    /// Returns the objects that the instance field `field` of `object` may point to.
+    /// `field` may refer to the field through a subclass of its declaring class as long as the
+    /// analyzed code accesses it through the same reference.
+    #[must_use]
+    pub fn field_points_to(
+        &self,
+        object: &AbstractObject,
+        field: &FieldRef,
+    ) -> BTreeSet<&AbstractObject> {
+        self.heap_points_to(object, Field::Instance(self.resolved_field(field).clone()))
+    }
+
+    /// Returns the objects that the elements of the array `object` may point to.
+    #[must_use]
+    pub fn array_element_points_to(&self, object: &AbstractObject) -> BTreeSet<&AbstractObject> {
+        self.heap_points_to(object, Field::ArrayElement)
+    }
+
+    /// Returns the objects that the static field may point to.
+    /// See [`PointsTo::field_points_to`] for how `field` is resolved.
+    #[must_use]
+    pub fn static_field_points_to(&self, field: &FieldRef) -> BTreeSet<&AbstractObject> {
+        self.static_fields
+            .get(self.resolved_field(field))
+            .map(|objects| self.objects_of(objects).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the abstract objects.
+    pub fn objects(&self) -> impl Iterator<Item = &AbstractObject> {
+        self.objects.iter()
+    }
+
+    /// Returns the reachable methods and the contexts they are analyzed in.
+    pub fn reachable_methods(&self) -> impl Iterator<Item = (&MethodRef, &Context)> {
+        self.reachable_methods.iter().map(|(m, c)| (m, c))
+    }
+
+    /// Returns the call graph constructed during the analysis.
+    /// Errors occurred during the analysis are recorded in the call graph.
+    #[must_use]
+    pub fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
+    fn resolved_field<'a>(&'a self, field: &'a FieldRef) -> &'a FieldRef {
+        self.resolved_fields.get(field).unwrap_or(field)
+    }
+
+    fn heap_points_to(&self, object: &AbstractObject, field: Field) -> BTreeSet<&AbstractObject> {
+        self.object_ids
+            .get(object)
+            .and_then(|id| self.instance_fields.get(&(*id, field)))
+            .map(|objects| self.objects_of(objects).collect())
+            .unwrap_or_default()
+    }
+
+    fn objects_of<'a>(
+        &'a self,
+        ids: &'a BTreeSet<usize>,
+    ) -> impl Iterator<Item = &'a AbstractObject> + 'a {
+        ids.iter().map(|id| &self.objects[*id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(pc: u16) -> Location {
+        Location {
+            method: MethodRef {
+                owner: ClassRef::new("Test"),
+                name: "test".to_owned(),
+                descriptor: "()V".parse().unwrap(),
+            },
+            pc: pc.into(),
+        }
+    }
+
+    #[test]
+    fn context_push_keeps_most_recent() {
+        let context = Context::empty()
+            .push(location(1), 2)
+            .push(location(2), 2)
+            .push(location(3), 2);
+        assert_eq!(context.elements(), &[location(3), location(2)]);
+        assert_eq!(context.truncate(1).elements(), &[location(3)]);
+        assert_eq!(Context::empty().push(location(1), 0), Context::empty());
+    }
+}
@@ -0,0 +1,825 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    rc::Rc,
+};
+
+use crate::{
+    analysis::{
+        call_graph::{CallGraph, EntryPoint, Error},
+        resolution::{InvocationKind, MethodResolver, ResolutionError},
+        Location,
+    },
+    ir::{
+        expression::{ArrayOperation, Conversion, Expression, FieldAccess},
+        Argument, Identifier, LocalValue, MokaIRMethod, MokaIRMethodExt, MokaInstruction,
+    },
+    jvm::{
+        class::{Class, MethodHandle},
+        class_loader::{CachingClassLoader, ClassPath},
+        code::ProgramCounter,
+        field::{self, ConstantValue},
+        method::{self, Method},
+        references::{ClassRef, FieldRef, MethodRef},
+    },
+    types::{field_type::FieldType, method_descriptor::ReturnType},
+};
+
+use super::{AbstractObject, Context, ContextSensitivity, Field, ObjectKind, PointsTo};
+
+/// Runs a points-to analysis on a set of application classes.
+#[derive(Debug)]
+pub struct PointsToBuilder<'l, P> {
+    resolver: MethodResolver<'l, P>,
+    application_classes: Vec<&'l Class>,
+    context_sensitivity: ContextSensitivity,
+    entry_points: Vec<EntryPoint>,
+}
+
+impl<'l, P> PointsToBuilder<'l, P>
+where
+    P: ClassPath,
+{
+    /// Creates a builder analyzing the given application classes, which are loaded with
+    /// `class_loader`.
+    /// # Errors
+    /// - [`ResolutionError::ClassLoading`] if an application class cannot be loaded.
+    pub fn new(
+        class_loader: &'l CachingClassLoader<P>,
+        application_classes: impl IntoIterator<Item = ClassRef>,
+    ) -> Result<Self, ResolutionError> {
+        let resolver = MethodResolver::new(class_loader);
+        let application_classes = application_classes
+            .into_iter()
+            .map(|it| resolver.load(&it))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            resolver,
+            application_classes,
+            context_sensitivity: ContextSensitivity::default(),
+            entry_points: Vec::new(),
+        })
+    }
+
+    /// Sets the context sensitivity of the analysis.
+    #[must_use]
+    pub fn context_sensitivity(mut self, context_sensitivity: ContextSensitivity) -> Self {
+        self.context_sensitivity = context_sensitivity;
+        self
+    }
+
+    /// Adds an entry point.
+    /// The entry points are analyzed in the empty context, and their parameters point to nothing.
+    #[must_use]
+    pub fn entry_point(mut self, entry_point: EntryPoint) -> Self {
+        self.entry_points.push(entry_point);
+        self
+    }
+
+    /// Runs the analysis until a fixed point is reached.
+    /// # Errors
+    /// - [`ResolutionError`] if a method specified by [`EntryPoint::Method`] cannot be resolved.
+    pub fn build(self) -> Result<PointsTo, ResolutionError> {
+        let mut solver = Solver::new(&self);
+        for entry_point in &self.entry_points {
+            for method in entry_point.methods(&self.resolver, &self.application_classes)? {
+                solver.call_graph.add_entry_point(method.as_ref());
+                solver.add_reachable(method, Context::empty());
+            }
+        }
+        solver.solve();
+        Ok(solver.finish())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Pointer {
+    Variable {
+        method: MethodRef,
+        context: Context,
+        id: Identifier,
+    },
+    Return {
+        method: MethodRef,
+        context: Context,
+    },
+    InstanceField {
+        object: usize,
+        field: Field,
+    },
+    StaticField(FieldRef),
+}
+
+/// A node in the pointer flow graph.
+#[derive(Debug)]
+struct Node {
+    pointer: Pointer,
+    points_to: BTreeSet<usize>,
+    successors: BTreeSet<usize>,
+    /// Field reads with this node as the base, as `(field, destination)`.
+    loads: Vec<(Field, usize)>,
+    /// Field writes with this node as the base, as `(field, source)`.
+    stores: Vec<(Field, usize)>,
+    /// Calls with this node as the receiver.
+    calls: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Dispatch<'l> {
+    /// The callee does not depend on the receiver (e.g., `invokespecial`).
+    Fixed(&'l Method),
+    /// The callee is selected based on the receiver.
+    Virtual(&'l Method),
+}
+
+/// A call whose callee or context depends on the receiver objects.
+#[derive(Debug)]
+struct ReceiverCall<'l> {
+    caller: &'l Method,
+    context: Context,
+    pc: ProgramCounter,
+    declared_type: ClassRef,
+    dispatch: Dispatch<'l>,
+    args: Vec<Argument>,
+    result: Option<LocalValue>,
+}
+
+/// The arguments and the result of a call site.
+struct CallSite<'a> {
+    pc: ProgramCounter,
+    this: Option<&'a Argument>,
+    args: &'a [Argument],
+    result: Option<LocalValue>,
+}
+
+struct Solver<'b, 'l, P> {
+    builder: &'b PointsToBuilder<'l, P>,
+    nodes: Vec<Node>,
+    node_ids: BTreeMap<Pointer, usize>,
+    objects: Vec<AbstractObject>,
+    object_ids: BTreeMap<AbstractObject, usize>,
+    receiver_calls: Vec<ReceiverCall<'l>>,
+    worklist: VecDeque<(usize, BTreeSet<usize>)>,
+    reachable: BTreeSet<(MethodRef, Context)>,
+    pending_methods: VecDeque<(&'l Method, Context)>,
+    ir_cache: BTreeMap<MethodRef, Option<Rc<MokaIRMethod>>>,
+    resolved_fields: BTreeMap<FieldRef, FieldRef>,
+    call_graph: CallGraph,
+}
+
+impl<'b, 'l, P> Solver<'b, 'l, P>
+where
+    P: ClassPath,
+{
+    fn new(builder: &'b PointsToBuilder<'l, P>) -> Self {
+        Self {
+            builder,
+            nodes: Vec::new(),
+            node_ids: BTreeMap::new(),
+            objects: Vec::new(),
+            object_ids: BTreeMap::new(),
+            receiver_calls: Vec::new(),
+            worklist: VecDeque::new(),
+            reachable: BTreeSet::new(),
+            pending_methods: VecDeque::new(),
+            ir_cache: BTreeMap::new(),
+            resolved_fields: BTreeMap::new(),
+            call_graph: CallGraph::default(),
+        }
+    }
+
+    fn solve(&mut self) {
+        loop {
+            if let Some((method, context)) = self.pending_methods.pop_front() {
+                self.process_method(method, &context);
+            } else if let Some((node, objects)) = self.worklist.pop_front() {
+                self.propagate(node, &objects);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn finish(self) -> PointsTo {
+        let mut result = PointsTo {
+            objects: self.objects,
+            object_ids: self.object_ids,
+            reachable_methods: self.reachable,
+            resolved_fields: self.resolved_fields,
+            call_graph: self.call_graph,
+            ..Default::default()
+        };
+        for node in self.nodes {
+            if node.points_to.is_empty() {
+                continue;
+            }
+            match node.pointer {
+                Pointer::Variable {
+                    method,
+                    context,
+                    id,
+                } => {
+                    result
+                        .variables
+                        .insert((method, id, context), node.points_to);
+                }
+                Pointer::InstanceField { object, field } => {
+                    result
+                        .instance_fields
+                        .insert((object, field), node.points_to);
+                }
+                Pointer::StaticField(field) => {
+                    result.static_fields.insert(field, node.points_to);
+                }
+                Pointer::Return { .. } => {}
+            }
+        }
+        result
+    }
+
+    /// Resolves a field reference to the field it refers to, so that references through
+    /// subclasses share the abstract location of the declaring class.
+    /// Fields that cannot be resolved (e.g., in classes missing from the class path) are kept as
+    /// referenced.
+    fn resolve_field(&mut self, field_ref: &FieldRef) -> FieldRef {
+        if let Some(resolved) = self.resolved_fields.get(field_ref) {
+            return resolved.clone();
+        }
+        let resolved = self
+            .builder
+            .resolver
+            .resolve_field(field_ref)
+            .map_or_else(|_| field_ref.clone(), field::Field::as_ref);
+        self.resolved_fields
+            .insert(field_ref.clone(), resolved.clone());
+        resolved
+    }
+
+    fn node(&mut self, pointer: Pointer) -> usize {
+        if let Some(id) = self.node_ids.get(&pointer) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.node_ids.insert(pointer.clone(), id);
+        self.nodes.push(Node {
+            pointer,
+            points_to: BTreeSet::new(),
+            successors: BTreeSet::new(),
+            loads: Vec::new(),
+            stores: Vec::new(),
+            calls: Vec::new(),
+        });
+        id
+    }
+
+    fn variable(&mut self, method: &Method, context: &Context, id: Identifier) -> usize {
+        self.node(Pointer::Variable {
+            method: method.as_ref(),
+            context: context.clone(),
+            id,
+        })
+    }
+
+    fn field(&mut self, object: usize, field: Field) -> usize {
+        self.node(Pointer::InstanceField { object, field })
+    }
+
+    fn object(&mut self, object: AbstractObject) -> usize {
+        if let Some(id) = self.object_ids.get(&object) {
+            return *id;
+        }
+        let id = self.objects.len();
+        self.object_ids.insert(object.clone(), id);
+        self.objects.push(object);
+        id
+    }
+
+    fn add_reachable(&mut self, method: &'l Method, context: Context) {
+        if self.reachable.insert((method.as_ref(), context.clone())) {
+            self.pending_methods.push_back((method, context));
+        }
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize) {
+        if from != to && self.nodes[from].successors.insert(to) {
+            let objects = &self.nodes[from].points_to;
+            if !objects.is_empty() {
+                self.worklist.push_back((to, objects.clone()));
+            }
+        }
+    }
+
+    fn add_load(&mut self, base: usize, field: &Field, destination: usize) {
+        self.nodes[base].loads.push((field.clone(), destination));
+        for object in self.nodes[base].points_to.clone() {
+            let source = self.field(object, field.clone());
+            self.add_edge(source, destination);
+        }
+    }
+
+    fn add_store(&mut self, base: usize, field: &Field, source: usize) {
+        self.nodes[base].stores.push((field.clone(), source));
+        for object in self.nodes[base].points_to.clone() {
+            let destination = self.field(object, field.clone());
+            self.add_edge(source, destination);
+        }
+    }
+
+    fn add_receiver_call(&mut self, receiver: usize, call: usize) {
+        self.nodes[receiver].calls.push(call);
+        for object in self.nodes[receiver].points_to.clone() {
+            self.dispatch(call, object);
+        }
+    }
+
+    fn propagate(&mut self, node: usize, objects: &BTreeSet<usize>) {
+        let delta: BTreeSet<_> = objects
+            .difference(&self.nodes[node].points_to)
+            .copied()
+            .collect();
+        if delta.is_empty() {
+            return;
+        }
+        self.nodes[node].points_to.extend(delta.iter().copied());
+        for successor in self.nodes[node].successors.clone() {
+            self.worklist.push_back((successor, delta.clone()));
+        }
+        for (field, destination) in self.nodes[node].loads.clone() {
+            for object in &delta {
+                let source = self.field(*object, field.clone());
+                self.add_edge(source, destination);
+            }
+        }
+        for (field, source) in self.nodes[node].stores.clone() {
+            for object in &delta {
+                let destination = self.field(*object, field.clone());
+                self.add_edge(source, destination);
+            }
+        }
+        for call in self.nodes[node].calls.clone() {
+            for object in &delta {
+                self.dispatch(call, *object);
+            }
+        }
+    }
+
+    fn ir_of(&mut self, method: &Method) -> Option<Rc<MokaIRMethod>> {
+        if let Some(ir) = self.ir_cache.get(&method.as_ref()) {
+            return ir.clone();
+        }
+        let ir = match method.brew() {
+            Ok(ir) => Some(Rc::new(ir)),
+            Err(source) => {
+                self.call_graph.add_error(Error::Brewing {
+                    method: method.as_ref(),
+                    source,
+                });
+                None
+            }
+        };
+        self.ir_cache.insert(method.as_ref(), ir.clone());
+        ir
+    }
+
+    fn process_method(&mut self, method: &'l Method, context: &Context) {
+        if method.body.is_none() {
+            return;
+        }
+        let Some(ir) = self.ir_of(method) else {
+            return;
+        };
+        for (pc, insn) in &ir.instructions {
+            match insn {
+                MokaInstruction::Definition { value, expr } => {
+                    self.process_definition(method, context, *pc, *value, expr);
+                }
+                MokaInstruction::Return(Some(value)) => {
+                    let return_node = self.node(Pointer::Return {
+                        method: method.as_ref(),
+                        context: context.clone(),
+                    });
+                    for id in value {
+                        let source = self.variable(method, context, *id);
+                        self.add_edge(source, return_node);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn process_definition(
+        &mut self,
+        method: &'l Method,
+        context: &Context,
+        pc: ProgramCounter,
+        value: LocalValue,
+        expr: &Expression,
+    ) {
+        let destination = self.variable(method, context, value.into());
+        let site = Location {
+            method: method.as_ref(),
+            pc,
+        };
+        match expr {
+            Expression::New(class) => {
+                self.allocate(
+                    destination,
+                    site,
+                    context,
+                    ObjectKind::Instance(class.clone()),
+                );
+            }
+            Expression::Const(ConstantValue::String(_)) => {
+                let class = ClassRef::new("java/lang/String");
+                self.allocate(destination, site, context, ObjectKind::Instance(class));
+            }
+            Expression::Const(ConstantValue::Class(_)) => {
+                let class = ClassRef::new("java/lang/Class");
+                self.allocate(destination, site, context, ObjectKind::Instance(class));
+            }
+            Expression::Array(ArrayOperation::New { element_type, .. }) => {
+                let array_type = element_type.clone().into_array_type();
+                self.allocate(destination, site, context, ObjectKind::Array(array_type));
+            }
+            Expression::Array(ArrayOperation::NewMultiDim {
+                element_type,
+                dimensions,
+            }) => {
+                // Each dimension is represented by a distinct object at the same site.
+                let mut array_type = element_type.clone();
+                let mut outer = self.allocate(
+                    destination,
+                    site.clone(),
+                    context,
+                    ObjectKind::Array(array_type.clone()),
+                );
+                for _ in 1..dimensions.len() {
+                    let FieldType::Array(inner_type) = array_type else {
+                        break;
+                    };
+                    array_type = *inner_type;
+                    let elements = self.field(outer, Field::ArrayElement);
+                    outer = self.allocate(
+                        elements,
+                        site.clone(),
+                        context,
+                        ObjectKind::Array(array_type.clone()),
+                    );
+                }
+            }
+            Expression::Array(ArrayOperation::Read { array_ref, .. }) => {
+                for id in array_ref {
+                    let base = self.variable(method, context, *id);
+                    self.add_load(base, &Field::ArrayElement, destination);
+                }
+            }
+            Expression::Array(ArrayOperation::Write {
+                array_ref,
+                value: stored,
+                ..
+            }) => self.process_store(method, context, array_ref, &Field::ArrayElement, stored),
+            Expression::Field(FieldAccess::ReadStatic { field }) => {
+                let field = self.resolve_field(field);
+                let source = self.node(Pointer::StaticField(field));
+                self.add_edge(source, destination);
+            }
+            Expression::Field(FieldAccess::WriteStatic {
+                field,
+                value: stored,
+            }) => {
+                let field = self.resolve_field(field);
+                let static_field = self.node(Pointer::StaticField(field));
+                for id in stored {
+                    let source = self.variable(method, context, *id);
+                    self.add_edge(source, static_field);
+                }
+            }
+            Expression::Field(FieldAccess::ReadInstance { object_ref, field }) => {
+                let field = Field::Instance(self.resolve_field(field));
+                for id in object_ref {
+                    let base = self.variable(method, context, *id);
+                    self.add_load(base, &field, destination);
+                }
+            }
+            Expression::Field(FieldAccess::WriteInstance {
+                object_ref,
+                field,
+                value: stored,
+            }) => {
+                let field = Field::Instance(self.resolve_field(field));
+                self.process_store(method, context, object_ref, &field, stored);
+            }
+            Expression::Conversion(Conversion::CheckCast(casted, _)) => {
+                for id in casted {
+                    let source = self.variable(method, context, *id);
+                    self.add_edge(source, destination);
+                }
+            }
+            Expression::Call {
+                method: method_ref,
+                this,
+                args,
+            } => {
+                let Some((kind, _)) = method
+                    .body
+                    .as_ref()
+                    .and_then(|body| body.instruction_at(pc))
+                    .and_then(InvocationKind::of)
+                else {
+                    return;
+                };
+                let call_site = CallSite {
+                    pc,
+                    this: this.as_ref(),
+                    args,
+                    result: Some(value),
+                };
+                self.process_call(method, context, kind, method_ref, &call_site);
+            }
+            Expression::Closure {
+                captures,
+                bootstrap_method_index,
+                closure_descriptor,
+                ..
+            } => {
+                if let ReturnType::Some(FieldType::Object(interface)) =
+                    &closure_descriptor.return_type
+                {
+                    let kind = ObjectKind::Closure(interface.clone());
+                    self.allocate(destination, site, context, kind);
+                }
+                self.process_closure(method, context, pc, *bootstrap_method_index, captures);
+            }
+            _ => {}
+        }
+    }
+
+    fn process_store(
+        &mut self,
+        method: &Method,
+        context: &Context,
+        object_ref: &Argument,
+        field: &Field,
+        stored: &Argument,
+    ) {
+        for base_id in object_ref {
+            let base = self.variable(method, context, *base_id);
+            for id in stored {
+                let source = self.variable(method, context, *id);
+                self.add_store(base, field, source);
+            }
+        }
+    }
+
+    fn allocate(
+        &mut self,
+        destination: usize,
+        site: Location,
+        context: &Context,
+        kind: ObjectKind,
+    ) -> usize {
+        let depth = self.builder.context_sensitivity.depth();
+        let object = self.object(AbstractObject {
+            site,
+            kind,
+            context: context.truncate(depth.saturating_sub(1)),
+        });
+        self.worklist
+            .push_back((destination, BTreeSet::from([object])));
+        object
+    }
+
+    /// Connects the implementation method of a lambda expression or a method reference.
+    /// The captured values are passed as the leading arguments of the implementation method.
+    fn process_closure(
+        &mut self,
+        method: &'l Method,
+        context: &Context,
+        pc: ProgramCounter,
+        bootstrap_method_index: u16,
+        captures: &[Argument],
+    ) {
+        let Some(implementation) = self
+            .builder
+            .resolver
+            .load(&method.owner)
+            .ok()
+            .and_then(|it| {
+                it.bootstrap_methods
+                    .get(usize::from(bootstrap_method_index))
+            })
+            .and_then(|it| it.lambda_implementation())
+        else {
+            return;
+        };
+        let (kind, method_ref) = match implementation {
+            MethodHandle::RefInvokeStatic(it) => (InvocationKind::Static, it),
+            MethodHandle::RefInvokeSpecial(it) => (InvocationKind::Special, it),
+            MethodHandle::RefInvokeVirtual(it) => (InvocationKind::Virtual, it),
+            MethodHandle::RefInvokeInterface(it) => (InvocationKind::Interface, it),
+            _ => return,
+        };
+        let call_site = match (kind, captures) {
+            (InvocationKind::Static, args) => CallSite {
+                pc,
+                this: None,
+                args,
+                result: None,
+            },
+            (_, [this, args @ ..]) => CallSite {
+                pc,
+                this: Some(this),
+                args,
+                result: None,
+            },
+            // The receiver is not captured but passed when the closure is called.
+            (_, []) => return,
+        };
+        self.process_call(method, context, kind, method_ref, &call_site);
+    }
+
+    fn process_call(
+        &mut self,
+        caller: &'l Method,
+        context: &Context,
+        kind: InvocationKind,
+        method_ref: &MethodRef,
+        call_site: &CallSite<'_>,
+    ) {
+        let resolved = match self
+            .builder
+            .resolver
+            .dispatch(kind, method_ref, &caller.owner, None)
+        {
+            Ok(it) => it,
+            Err(source) => {
+                self.call_graph.add_error(Error::Resolution {
+                    caller: caller.as_ref(),
+                    pc: call_site.pc,
+                    source,
+                });
+                return;
+            }
+        };
+        let this = match call_site.this {
+            Some(this) if !resolved.access_flags.contains(method::AccessFlags::STATIC) => this,
+            _ => {
+                let callee_context = self.callee_context(caller, context, call_site.pc, None);
+                self.add_call(caller, context, call_site, resolved, &callee_context, None);
+                return;
+            }
+        };
+        let dispatch = if kind == InvocationKind::Special
+            || resolved.is_signature_polymorphic()
+            || resolved.access_flags.contains(method::AccessFlags::PRIVATE)
+        {
+            Dispatch::Fixed(resolved)
+        } else {
+            Dispatch::Virtual(resolved)
+        };
+        let call = self.receiver_calls.len();
+        self.receiver_calls.push(ReceiverCall {
+            caller,
+            context: context.clone(),
+            pc: call_site.pc,
+            declared_type: method_ref.owner.clone(),
+            dispatch,
+            args: call_site.args.to_vec(),
+            result: call_site.result,
+        });
+        for id in this {
+            let receiver = self.variable(caller, context, *id);
+            self.add_receiver_call(receiver, call);
+        }
+    }
+
+    fn dispatch(&mut self, call: usize, object: usize) {
+        let receiver_class = match &self.objects[object].kind {
+            ObjectKind::Instance(class) => class.clone(),
+            ObjectKind::Array(_) => ClassRef::new("java/lang/Object"),
+            // Calls on closures are handled at their creation sites.
+            ObjectKind::Closure(_) => return,
+        };
+        let ReceiverCall {
+            caller,
+            ref context,
+            pc,
+            ref declared_type,
+            dispatch,
+            ..
+        } = self.receiver_calls[call];
+        let method_resolver = &self.builder.resolver;
+        let is_array = matches!(self.objects[object].kind, ObjectKind::Array(_));
+        // Objects that cannot be the receiver due to the imprecision of the analysis are ignored.
+        let is_compatible = is_array
+            || method_resolver
+                .load(&receiver_class)
+                .and_then(|it| method_resolver.is_subtype_of(it, declared_type))
+                .unwrap_or(false);
+        if !is_compatible {
+            return;
+        }
+        let callee = match dispatch {
+            Dispatch::Fixed(callee) => callee,
+            Dispatch::Virtual(resolved) => {
+                match method_resolver.select(resolved, &receiver_class) {
+                    Ok(callee) => callee,
+                    Err(source) => {
+                        self.call_graph.add_error(Error::Resolution {
+                            caller: caller.as_ref(),
+                            pc,
+                            source,
+                        });
+                        return;
+                    }
+                }
+            }
+        };
+        let context = context.clone();
+        let callee_context = self.callee_context(caller, &context, pc, Some(object));
+        let call = &self.receiver_calls[call];
+        let args = call.args.clone();
+        let call_site = CallSite {
+            pc,
+            this: None,
+            args: &args,
+            result: call.result,
+        };
+        self.add_call(
+            caller,
+            &context,
+            &call_site,
+            callee,
+            &callee_context,
+            Some(object),
+        );
+    }
+
+    fn callee_context(
+        &self,
+        caller: &Method,
+        context: &Context,
+        pc: ProgramCounter,
+        receiver: Option<usize>,
+    ) -> Context {
+        match (self.builder.context_sensitivity, receiver) {
+            (ContextSensitivity::Insensitive, _) => Context::empty(),
+            (ContextSensitivity::CallSite(depth), _) => {
+                let call_site = Location {
+                    method: caller.as_ref(),
+                    pc,
+                };
+                context.push(call_site, depth)
+            }
+            (ContextSensitivity::Object(depth), Some(receiver)) => {
+                let receiver = &self.objects[receiver];
+                receiver.context.push(receiver.site.clone(), depth)
+            }
+            (ContextSensitivity::Object(_), None) => context.clone(),
+        }
+    }
+
+    fn add_call(
+        &mut self,
+        caller: &'l Method,
+        context: &Context,
+        call_site: &CallSite<'_>,
+        target: &'l Method,
+        target_context: &Context,
+        receiver: Option<usize>,
+    ) {
+        self.call_graph
+            .add_edge(caller.as_ref(), call_site.pc, target.as_ref());
+        if target.body.is_none() {
+            return;
+        }
+        self.add_reachable(target, target_context.clone());
+        if let Some(receiver) = receiver {
+            let this = self.variable(target, target_context, Identifier::This);
+            self.worklist.push_back((this, BTreeSet::from([receiver])));
+        }
+        for (index, arg) in call_site.args.iter().enumerate() {
+            let Ok(index) = u16::try_from(index) else {
+                break;
+            };
+            let parameter = self.variable(target, target_context, Identifier::Arg(index));
+            for id in arg {
+                let source = self.variable(caller, context, *id);
+                self.add_edge(source, parameter);
+            }
+        }
+        let returns_reference = matches!(
+            target.descriptor.return_type,
+            ReturnType::Some(FieldType::Object(_) | FieldType::Array(_))
+        );
+        if let (Some(result), true) = (call_site.result, returns_reference) {
+            let return_node = self.node(Pointer::Return {
+                method: target.as_ref(),
+                context: target_context.clone(),
+            });
+            let destination = self.variable(caller, context, result.into());
+            self.add_edge(return_node, destination);
+        }
+    }
+}
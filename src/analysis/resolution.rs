@@ -0,0 +1,572 @@
+//! Method and field resolution, and method selection.
+//!
+//! Resolution finds the method or field a symbolic [`MethodRef`] or [`FieldRef`] refers to, while
+//! selection finds the method that actually runs when the resolved method is invoked on a
+//! receiver.
+//! See the [JVM Specification §5.4.3](https://docs.oracle.com/javase/specs/jvms/se21/html/jvms-5.html#jvms-5.4.3)
+//! for more information.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    jvm::{
+        class::{self, Class},
+        class_loader::{self, CachingClassLoader, ClassPath},
+        code::Instruction,
+        field::Field,
+        method::{self, Method},
+        references::{ClassRef, FieldRef, MethodRef},
+    },
+    macros::see_jvm_spec,
+    types::method_descriptor::MethodDescriptor,
+};
+
+/// An error that occurs when resolving or selecting a method, or resolving a field.
+#[derive(Debug, thiserror::Error)]
+pub enum ResolutionError {
+    /// A class involved in the resolution could not be loaded.
+    #[error("Failed to load class {0}: {1}")]
+    ClassLoading(ClassRef, #[source] class_loader::Error),
+    /// The kind of the referenced class does not match the invocation (e.g., an interface
+    /// method reference pointing to a class).
+    #[error("IncompatibleClassChangeError: {0} {1}")]
+    IncompatibleClassChange(ClassRef, &'static str),
+    /// No method matches the reference.
+    #[error("NoSuchMethodError: {0}")]
+    NotFound(MethodRef),
+    /// No field matches the reference.
+    #[error("NoSuchFieldError: {0}")]
+    FieldNotFound(FieldRef),
+    /// The method is found but not accessible from the caller.
+    #[error("IllegalAccessError: {method} is not accessible from {accessor}")]
+    IllegalAccess {
+        /// The class trying to access the method.
+        accessor: ClassRef,
+        /// The method being accessed.
+        method: MethodRef,
+    },
+    /// The selected method is `abstract`, or no implementation can be selected.
+    #[error("AbstractMethodError: {0}")]
+    AbstractMethod(MethodRef),
+    /// More than one maximally-specific default method can be selected.
+    #[error("IncompatibleClassChangeError: Conflicting default methods for {method}")]
+    AmbiguousDefault {
+        /// The method being selected.
+        method: MethodRef,
+        /// The conflicting default methods.
+        candidates: Vec<MethodRef>,
+    },
+}
+
+/// The kind of a method invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvocationKind {
+    /// Invocation with `invokestatic`.
+    Static,
+    /// Invocation with `invokespecial`.
+    Special,
+    /// Invocation with `invokevirtual`.
+    Virtual,
+    /// Invocation with `invokeinterface`.
+    Interface,
+}
+
+impl InvocationKind {
+    /// Gets the invocation kind and the method reference of an invoke instruction.
+    /// Returns [`None`] if the instruction is not an invocation with a symbolic method reference.
+    #[must_use]
+    pub fn of(instruction: &Instruction) -> Option<(Self, &MethodRef)> {
+        match instruction {
+            Instruction::InvokeStatic(it) => Some((Self::Static, it)),
+            Instruction::InvokeSpecial(it) => Some((Self::Special, it)),
+            Instruction::InvokeVirtual(it) => Some((Self::Virtual, it)),
+            Instruction::InvokeInterface(it, _) => Some((Self::Interface, it)),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves and selects methods with classes loaded by a [`CachingClassLoader`].
+#[derive(Debug)]
+pub struct MethodResolver<'l, P> {
+    class_loader: &'l CachingClassLoader<P>,
+}
+
+type Result<T> = std::result::Result<T, ResolutionError>;
+
+impl<'l, P> MethodResolver<'l, P>
+where
+    P: ClassPath,
+{
+    /// Creates a new resolver that loads classes with the given class loader.
+    #[must_use]
+    pub const fn new(class_loader: &'l CachingClassLoader<P>) -> Self {
+        Self { class_loader }
+    }
+
+    /// Loads a class.
+    /// # Errors
+    /// - [`ResolutionError::ClassLoading`] if the class cannot be loaded.
+    pub fn load(&self, class_ref: &ClassRef) -> Result<&'l Class> {
+        self.class_loader
+            .load_class(class_ref.binary_name.as_str())
+            .map_err(|e| ResolutionError::ClassLoading(class_ref.clone(), e))
+    }
+
+    /// Resolves a method reference with either method resolution or interface method resolution
+    /// depending on whether the owner of the reference is an interface.
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn resolve(&self, method_ref: &MethodRef) -> Result<&'l Method> {
+        let owner = self.load(&method_ref.owner)?;
+        if owner.access_flags.contains(class::AccessFlags::INTERFACE) {
+            self.resolve_interface_method(method_ref)
+        } else {
+            self.resolve_method(method_ref)
+        }
+    }
+
+    /// Resolves a reference to a method declared in a class.
+    #[doc = see_jvm_spec!(5, 4, 3, 3)]
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn resolve_method(&self, method_ref: &MethodRef) -> Result<&'l Method> {
+        let MethodRef {
+            owner,
+            name,
+            descriptor,
+        } = method_ref;
+        let class = self.load(owner)?;
+        if class.access_flags.contains(class::AccessFlags::INTERFACE) {
+            return Err(ResolutionError::IncompatibleClassChange(
+                owner.clone(),
+                "is an interface but a class is expected",
+            ));
+        }
+        // Look up in the class and its superclasses.
+        let mut current = Some(class);
+        while let Some(class) = current {
+            let mut same_name = class.methods.iter().filter(|m| &m.name == name);
+            if let (Some(only), None) = (same_name.next(), same_name.next()) {
+                if only.is_signature_polymorphic() {
+                    return Ok(only);
+                }
+            }
+            if let Some(method) = class.get_method(name, descriptor) {
+                return Ok(method);
+            }
+            current = class
+                .super_class
+                .as_ref()
+                .map(|it| self.load(it))
+                .transpose()?;
+        }
+        // Look up in the superinterfaces.
+        self.resolve_in_superinterfaces(class, name, descriptor)?
+            .ok_or_else(|| ResolutionError::NotFound(method_ref.clone()))
+    }
+
+    /// Resolves a reference to a method declared in an interface.
+    #[doc = see_jvm_spec!(5, 4, 3, 4)]
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn resolve_interface_method(&self, method_ref: &MethodRef) -> Result<&'l Method> {
+        let MethodRef {
+            owner,
+            name,
+            descriptor,
+        } = method_ref;
+        let interface = self.load(owner)?;
+        if !interface
+            .access_flags
+            .contains(class::AccessFlags::INTERFACE)
+        {
+            return Err(ResolutionError::IncompatibleClassChange(
+                owner.clone(),
+                "is a class but an interface is expected",
+            ));
+        }
+        if let Some(method) = interface.get_method(name, descriptor) {
+            return Ok(method);
+        }
+        let object = self.load(&ClassRef::new(JAVA_LANG_OBJECT))?;
+        if let Some(method) = object.get_method(name, descriptor).filter(|m| {
+            m.access_flags.contains(method::AccessFlags::PUBLIC)
+                && !m.access_flags.contains(method::AccessFlags::STATIC)
+        }) {
+            return Ok(method);
+        }
+        self.resolve_in_superinterfaces(interface, name, descriptor)?
+            .ok_or_else(|| ResolutionError::NotFound(method_ref.clone()))
+    }
+
+    /// The common tail of method resolution and interface method resolution, which looks up the
+    /// method in the superinterfaces of `class`.
+    fn resolve_in_superinterfaces(
+        &self,
+        class: &'l Class,
+        name: &str,
+        descriptor: &MethodDescriptor,
+    ) -> Result<Option<&'l Method>> {
+        let maximally_specific =
+            self.maximally_specific_superinterface_methods(class, name, descriptor)?;
+        let mut non_abstract = maximally_specific
+            .iter()
+            .filter(|m| !m.access_flags.contains(method::AccessFlags::ABSTRACT));
+        if let (Some(&only), None) = (non_abstract.next(), non_abstract.next()) {
+            return Ok(Some(only));
+        }
+        // Otherwise an arbitrary one of the non-private, non-static superinterface methods is chosen.
+        let any_candidate = self
+            .superinterfaces(class)?
+            .into_iter()
+            .filter_map(|it| it.get_method(name, descriptor))
+            .find(|m| is_inheritable_interface_method(m));
+        Ok(any_candidate)
+    }
+
+    /// Resolves a reference to a field.
+    /// The field is looked up in the referenced class, then its direct superinterfaces
+    /// (recursively), and then its superclass (recursively).
+    #[doc = see_jvm_spec!(5, 4, 3, 2)]
+    /// # Errors
+    /// - [`ResolutionError::ClassLoading`] if a class in the hierarchy cannot be loaded.
+    /// - [`ResolutionError::FieldNotFound`] if no field matches the reference.
+    pub fn resolve_field(&self, field_ref: &FieldRef) -> Result<&'l Field> {
+        let class = self.load(&field_ref.owner)?;
+        self.lookup_field(class, field_ref)?
+            .ok_or_else(|| ResolutionError::FieldNotFound(field_ref.clone()))
+    }
+
+    fn lookup_field(&self, class: &'l Class, field_ref: &FieldRef) -> Result<Option<&'l Field>> {
+        if let Some(field) = class.get_field(&field_ref.name, &field_ref.field_type) {
+            return Ok(Some(field));
+        }
+        for interface in &class.interfaces {
+            if let Some(field) = self.lookup_field(self.load(interface)?, field_ref)? {
+                return Ok(Some(field));
+            }
+        }
+        match &class.super_class {
+            Some(super_class) => self.lookup_field(self.load(super_class)?, field_ref),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the maximally-specific superinterface methods of `class` with the given name and
+    /// descriptor.
+    /// A maximally-specific superinterface method is a non-private, non-static method declared in
+    /// a superinterface of `class` that is not overridden by another such method declared in one of
+    /// its subinterfaces.
+    #[doc = see_jvm_spec!(5, 4, 3, 3)]
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn maximally_specific_superinterface_methods(
+        &self,
+        class: &'l Class,
+        name: &str,
+        descriptor: &MethodDescriptor,
+    ) -> Result<Vec<&'l Method>> {
+        let superinterfaces = self.superinterfaces(class)?;
+        let candidates: Vec<(&Class, &Method)> = superinterfaces
+            .iter()
+            .filter_map(|it| it.get_method(name, descriptor).map(|m| (*it, m)))
+            .filter(|(_, m)| is_inheritable_interface_method(m))
+            .collect();
+        let mut result = Vec::new();
+        for (declaring, method) in &candidates {
+            let mut overridden = false;
+            for (other, _) in &candidates {
+                if other.binary_name != declaring.binary_name
+                    && self.is_subtype_of(other, &declaring.as_ref())?
+                {
+                    overridden = true;
+                    break;
+                }
+            }
+            if !overridden {
+                result.push(*method);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Checks whether `method` is accessible from the class `accessor`.
+    #[doc = see_jvm_spec!(5, 4, 4)]
+    /// # Errors
+    /// - [`ResolutionError::IllegalAccess`] if the method is not accessible.
+    /// - [`ResolutionError::ClassLoading`] if a class needed for the check cannot be loaded.
+    pub fn check_access(&self, accessor: &ClassRef, method: &Method) -> Result<()> {
+        let flags = method.access_flags;
+        let accessible = if flags.contains(method::AccessFlags::PUBLIC) {
+            true
+        } else if flags.contains(method::AccessFlags::PRIVATE) {
+            accessor == &method.owner
+                || self.nest_host_of(accessor)? == self.nest_host_of(&method.owner)?
+        } else if flags.contains(method::AccessFlags::PROTECTED) {
+            same_package(accessor, &method.owner)
+                || self.is_subtype_of(self.load(accessor)?, &method.owner)?
+        } else {
+            same_package(accessor, &method.owner)
+        };
+        if accessible {
+            Ok(())
+        } else {
+            Err(ResolutionError::IllegalAccess {
+                accessor: accessor.clone(),
+                method: method.as_ref(),
+            })
+        }
+    }
+
+    /// Selects the method to be invoked by `invokevirtual` or `invokeinterface`, given the
+    /// resolved method and the class of the receiver object.
+    #[doc = see_jvm_spec!(5, 4, 6)]
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn select(&self, resolved: &'l Method, receiver: &ClassRef) -> Result<&'l Method> {
+        if resolved.access_flags.contains(method::AccessFlags::PRIVATE) {
+            return Ok(resolved);
+        }
+        let receiver_class = self.load(receiver)?;
+        let mut current = Some(receiver_class);
+        while let Some(class) = current {
+            if let Some(method) = class
+                .get_method(&resolved.name, &resolved.descriptor)
+                .filter(|m| can_override(m, resolved))
+            {
+                return if method.access_flags.contains(method::AccessFlags::ABSTRACT) {
+                    Err(ResolutionError::AbstractMethod(method.as_ref()))
+                } else {
+                    Ok(method)
+                };
+            }
+            current = class
+                .super_class
+                .as_ref()
+                .map(|it| self.load(it))
+                .transpose()?;
+        }
+        self.select_default(receiver_class, resolved)
+    }
+
+    /// Selects the method to be invoked by `invokespecial`.
+    /// The method reference is resolved and checked for accessibility from `caller` first.
+    #[doc = see_jvm_spec!(6, 5)]
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn select_special(&self, caller: &ClassRef, method_ref: &MethodRef) -> Result<&'l Method> {
+        let resolved = self.resolve(method_ref)?;
+        self.check_access(caller, resolved)?;
+        let caller_class = self.load(caller)?;
+        let symbolic = self.load(&method_ref.owner)?;
+        let is_super_call = !resolved.is_constructor()
+            && !symbolic
+                .access_flags
+                .contains(class::AccessFlags::INTERFACE)
+            && symbolic.binary_name != caller_class.binary_name
+            && self.is_subtype_of(caller_class, &symbolic.as_ref())?;
+        let start = match &caller_class.super_class {
+            Some(super_class) if is_super_call => self.load(super_class)?,
+            _ => symbolic,
+        };
+        let mut current = Some(start);
+        while let Some(class) = current {
+            if let Some(method) = class
+                .get_method(&resolved.name, &resolved.descriptor)
+                .filter(|m| !m.access_flags.contains(method::AccessFlags::STATIC))
+            {
+                return Ok(method);
+            }
+            if class.access_flags.contains(class::AccessFlags::INTERFACE) {
+                break;
+            }
+            current = class
+                .super_class
+                .as_ref()
+                .map(|it| self.load(it))
+                .transpose()?;
+        }
+        if start.access_flags.contains(class::AccessFlags::INTERFACE) {
+            let object = self.load(&ClassRef::new(JAVA_LANG_OBJECT))?;
+            if let Some(method) = object
+                .get_method(&resolved.name, &resolved.descriptor)
+                .filter(|m| {
+                    m.access_flags.contains(method::AccessFlags::PUBLIC)
+                        && !m.access_flags.contains(method::AccessFlags::STATIC)
+                })
+            {
+                return Ok(method);
+            }
+        }
+        self.select_default(start, resolved)
+    }
+
+    /// Finds the method that runs for an invocation.
+    ///
+    /// The method reference is resolved and checked for accessibility from `caller`.
+    /// For `invokevirtual` and `invokeinterface`, the method is then selected based on `receiver`,
+    /// the runtime class of the receiver object. The resolved method is returned if `receiver`
+    /// is [`None`].
+    /// # Errors
+    /// See [`ResolutionError`].
+    pub fn dispatch(
+        &self,
+        kind: InvocationKind,
+        method_ref: &MethodRef,
+        caller: &ClassRef,
+        receiver: Option<&ClassRef>,
+    ) -> Result<&'l Method> {
+        match kind {
+            InvocationKind::Static => {
+                let resolved = self.resolve(method_ref)?;
+                self.check_access(caller, resolved)?;
+                if resolved.access_flags.contains(method::AccessFlags::STATIC) {
+                    Ok(resolved)
+                } else {
+                    Err(ResolutionError::IncompatibleClassChange(
+                        resolved.owner.clone(),
+                        "declares an instance method invoked by invokestatic",
+                    ))
+                }
+            }
+            InvocationKind::Special => self.select_special(caller, method_ref),
+            InvocationKind::Virtual | InvocationKind::Interface => {
+                let resolved = if kind == InvocationKind::Virtual {
+                    self.resolve_method(method_ref)?
+                } else {
+                    self.resolve_interface_method(method_ref)?
+                };
+                self.check_access(caller, resolved)?;
+                if resolved.access_flags.contains(method::AccessFlags::STATIC) {
+                    return Err(ResolutionError::IncompatibleClassChange(
+                        resolved.owner.clone(),
+                        "declares a static method invoked as an instance method",
+                    ));
+                }
+                match receiver {
+                    Some(receiver) if !resolved.is_signature_polymorphic() => {
+                        self.select(resolved, receiver)
+                    }
+                    _ => Ok(resolved),
+                }
+            }
+        }
+    }
+
+    /// Checks whether `class` is `super_type` or one of its subclasses or subinterfaces.
+    /// # Errors
+    /// - [`ResolutionError::ClassLoading`] if a class in the hierarchy cannot be loaded.
+    pub fn is_subtype_of(&self, class: &Class, super_type: &ClassRef) -> Result<bool> {
+        if class.binary_name == super_type.binary_name {
+            return Ok(true);
+        }
+        for parent in class.super_class.iter().chain(class.interfaces.iter()) {
+            if self.is_subtype_of(self.load(parent)?, super_type)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Collects the direct and indirect superinterfaces of `class`, including the ones of its
+    /// superclasses.
+    fn superinterfaces(&self, class: &'l Class) -> Result<Vec<&'l Class>> {
+        let mut visited = BTreeSet::new();
+        let mut result = Vec::new();
+        let mut stack: Vec<&ClassRef> = class.interfaces.iter().collect();
+        let mut super_class = class.super_class.as_ref();
+        while let Some(it) = super_class {
+            let loaded = self.load(it)?;
+            stack.extend(loaded.interfaces.iter());
+            super_class = loaded.super_class.as_ref();
+        }
+        while let Some(interface_ref) = stack.pop() {
+            if !visited.insert(interface_ref.binary_name.as_str()) {
+                continue;
+            }
+            let interface = self.load(interface_ref)?;
+            stack.extend(interface.interfaces.iter());
+            result.push(interface);
+        }
+        Ok(result)
+    }
+
+    fn select_default(&self, class: &'l Class, resolved: &Method) -> Result<&'l Method> {
+        let candidates = self.maximally_specific_superinterface_methods(
+            class,
+            &resolved.name,
+            &resolved.descriptor,
+        )?;
+        let non_abstract: Vec<_> = candidates
+            .into_iter()
+            .filter(|m| !m.access_flags.contains(method::AccessFlags::ABSTRACT))
+            .collect();
+        match non_abstract.as_slice() {
+            [only] => Ok(only),
+            [] => Err(ResolutionError::AbstractMethod(resolved.as_ref())),
+            multiple => Err(ResolutionError::AmbiguousDefault {
+                method: resolved.as_ref(),
+                candidates: multiple.iter().map(|it| it.as_ref()).collect(),
+            }),
+        }
+    }
+
+    fn nest_host_of(&self, class_ref: &ClassRef) -> Result<ClassRef> {
+        let class = self.load(class_ref)?;
+        Ok(class.nest_host.clone().unwrap_or_else(|| class.as_ref()))
+    }
+}
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+
+fn is_inheritable_interface_method(method: &Method) -> bool {
+    !method
+        .access_flags
+        .intersects(method::AccessFlags::PRIVATE | method::AccessFlags::STATIC)
+}
+
+/// Checks whether `overrider` can override `overridden`.
+#[doc = see_jvm_spec!(5, 4, 5)]
+fn can_override(overrider: &Method, overridden: &Method) -> bool {
+    if overrider
+        .access_flags
+        .intersects(method::AccessFlags::PRIVATE | method::AccessFlags::STATIC)
+    {
+        return false;
+    }
+    overridden
+        .access_flags
+        .intersects(method::AccessFlags::PUBLIC | method::AccessFlags::PROTECTED)
+        || same_package(&overrider.owner, &overridden.owner)
+}
+
+fn package_of(class_ref: &ClassRef) -> &str {
+    class_ref
+        .binary_name
+        .rsplit_once('/')
+        .map_or("", |(package, _)| package)
+}
+
+fn same_package(lhs: &ClassRef, rhs: &ClassRef) -> bool {
+    package_of(lhs) == package_of(rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_names() {
+        assert_eq!(package_of(&ClassRef::new("java/lang/Object")), "java/lang");
+        assert_eq!(package_of(&ClassRef::new("Main")), "");
+        assert!(same_package(
+            &ClassRef::new("java/util/List"),
+            &ClassRef::new("java/util/Map")
+        ));
+        assert!(!same_package(
+            &ClassRef::new("java/util/List"),
+            &ClassRef::new("java/util/concurrent/Future")
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+//! Taint analysis over Moka IR.
+//!
+//! The analysis tracks values returned by the sources in a [`TaintSpec`] and reports the calls to
+//! sinks that receive them.
+//! It is formulated as an [`IfdsProblem`](crate::analysis::ifds::IfdsProblem) on an
+//! [`InterproceduralCfg`], so taint flows through the arguments and return values of the
+//! analyzed methods context-sensitively.
+//! Calls to sources, sanitizers, and propagators are modeled by the specification instead of being
+//! analyzed, and so are calls to methods not in the call graph.
+//!
+//! Taint also flows through string concatenations and other closures (whose results are tainted
+//! when any captured value is), static fields, instance fields (distinguished by their declaring
+//! class and name, but not by the object they belong to), and array elements (merged with the
+//! array itself).
+//! Field references are resolved with [`InterproceduralCfg::resolve_field`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    analysis::ifds::IfdsProblem,
+    ir::{expression::Expression, Identifier, MokaInstruction},
+    jvm::references::MethodRef,
+};
+
+use self::problem::{TaintFact, TaintProblem, TaintedValue};
+
+use super::{ifds::InterproceduralCfg, Location};
+
+mod problem;
+mod spec;
+
+pub use spec::{MethodPattern, Propagator, Sink, SpecError, TaintPosition, TaintSpec};
+
+/// A location in the source code.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TracePoint {
+    /// The location in the bytecode.
+    pub location: Location,
+    /// The source line of the location, if the method has a line number table.
+    pub line_number: Option<u16>,
+}
+
+impl Display for TracePoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.line_number {
+            Some(line_number) => write!(f, "{} (line {line_number})", self.location),
+            None => self.location.fmt(f),
+        }
+    }
+}
+
+/// A tainted value reaching a sink.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Finding {
+    /// The call to the source.
+    pub source: TracePoint,
+    /// The call to the sink.
+    pub sink: TracePoint,
+    /// The sink method being called.
+    pub sink_method: MethodRef,
+    /// The value passed to the sink.
+    pub position: TaintPosition,
+    /// The call sites from an entry point to the sink, ending with the call to the sink.
+    pub call_chain: Vec<TracePoint>,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tainted value from {} reaches {} of {} at {}",
+            self.source, self.position, self.sink_method, self.sink
+        )
+    }
+}
+
+/// A taint analysis on an [`InterproceduralCfg`].
+#[derive(Debug)]
+pub struct TaintAnalysis<'a> {
+    spec: &'a TaintSpec,
+    icfg: &'a InterproceduralCfg,
+}
+
+impl<'a> TaintAnalysis<'a> {
+    /// Creates a taint analysis with a specification.
+    #[must_use]
+    pub fn new(spec: &'a TaintSpec, icfg: &'a InterproceduralCfg) -> Self {
+        Self { spec, icfg }
+    }
+
+    /// Runs the analysis from the entry points of the call graph and returns the findings.
+    #[must_use]
+    pub fn findings(&self) -> Vec<Finding> {
+        let problem = TaintProblem {
+            spec: self.spec,
+            propagators: self.spec.effective_propagators(),
+            icfg: self.icfg,
+        };
+        let results = problem.solve(self.icfg);
+        let mut findings = BTreeSet::new();
+        for method in self.icfg.methods() {
+            let method_ref = MethodRef {
+                owner: method.owner.clone(),
+                name: method.name.clone(),
+                descriptor: method.descriptor.clone(),
+            };
+            for (pc, instruction) in &method.instructions {
+                let MokaInstruction::Definition {
+                    expr: Expression::Call { method, this, args },
+                    ..
+                } = instruction
+                else {
+                    continue;
+                };
+                let location = Location {
+                    method: method_ref.clone(),
+                    pc: *pc,
+                };
+                let sinks: Vec<_> = self
+                    .spec
+                    .sinks
+                    .iter()
+                    .filter(|sink| {
+                        problem
+                            .targets(&location, method)
+                            .any(|it| sink.method.matches(it))
+                    })
+                    .collect();
+                if sinks.is_empty() {
+                    continue;
+                }
+                let positions = this
+                    .iter()
+                    .map(|it| (TaintPosition::Receiver, it))
+                    .chain((0..).map(TaintPosition::Argument).zip(args));
+                for (position, arg) in positions {
+                    if !sinks.iter().any(|it| it.is_sensitive(position)) {
+                        continue;
+                    }
+                    for fact in results.facts_at(&location) {
+                        let TaintFact::Tainted {
+                            value: TaintedValue::Local(id),
+                            source,
+                        } = fact
+                        else {
+                            continue;
+                        };
+                        if arg.iter().any(|it: &Identifier| it == id) {
+                            findings.insert(Finding {
+                                source: self.trace_point(source.clone()),
+                                sink: self.trace_point(location.clone()),
+                                sink_method: method.clone(),
+                                position,
+                                call_chain: self.call_chain(&location),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        findings.into_iter().collect()
+    }
+
+    fn trace_point(&self, location: Location) -> TracePoint {
+        TracePoint {
+            line_number: self.icfg.line_number_at(&location),
+            location,
+        }
+    }
+
+    /// Finds a shortest chain of calls from an entry point to `call_site`.
+    fn call_chain(&self, call_site: &Location) -> Vec<TracePoint> {
+        let call_graph = self.icfg.call_graph();
+        let entry_points: BTreeSet<_> = call_graph.entry_points().collect();
+        // Maps each caller to the call site leading towards `call_site` and its callee.
+        let mut next_calls: BTreeMap<&MethodRef, (Location, &MethodRef)> = BTreeMap::new();
+        let mut visited = BTreeSet::from([&call_site.method]);
+        let mut queue = VecDeque::from([&call_site.method]);
+        while let Some(method) = queue.pop_front() {
+            if entry_points.contains(method) {
+                let mut chain = Vec::new();
+                let mut current = method;
+                while let Some((location, callee)) = next_calls.get(current) {
+                    chain.push(self.trace_point(location.clone()));
+                    current = callee;
+                }
+                chain.push(self.trace_point(call_site.clone()));
+                return chain;
+            }
+            for (caller, pc) in call_graph.callers(method) {
+                if visited.insert(caller) {
+                    let location = Location {
+                        method: caller.clone(),
+                        pc,
+                    };
+                    next_calls.insert(caller, (location, method));
+                    queue.push_back(caller);
+                }
+            }
+        }
+        vec![self.trace_point(call_site.clone())]
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    analysis::{
+        ifds::{IfdsProblem, InterproceduralCfg},
+        Location,
+    },
+    ir::{
+        expression::{ArrayOperation, Expression, FieldAccess},
+        Argument, Identifier, MokaInstruction,
+    },
+    jvm::{method, references::FieldRef, references::MethodRef},
+};
+
+use super::spec::{MethodPattern, Propagator, TaintPosition, TaintSpec};
+
+/// A dataflow fact of the taint analysis.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum TaintFact {
+    /// The zero fact.
+    Zero,
+    /// A value is tainted by the source called at `source`.
+    Tainted {
+        value: TaintedValue,
+        source: Location,
+    },
+}
+
+/// A value that may be tainted.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum TaintedValue {
+    /// A value in the current method.
+    Local(Identifier),
+    /// A static field, identified by the reference to its declaring class.
+    StaticField(FieldRef),
+    /// An instance field of any object, identified by the reference to its declaring class.
+    InstanceField(FieldRef),
+}
+
+pub(super) struct TaintProblem<'a> {
+    pub(super) spec: &'a TaintSpec,
+    pub(super) propagators: Vec<Propagator>,
+    pub(super) icfg: &'a InterproceduralCfg,
+}
+
+impl TaintProblem<'_> {
+    /// Returns the expression evaluated at a location and the value it defines.
+    fn definition_at(&self, location: &Location) -> Option<(Identifier, &Expression)> {
+        match self.icfg.instruction_at(location)? {
+            MokaInstruction::Definition { value, expr } => Some(((*value).into(), expr)),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbolic target of a call and the methods it may be dispatched to.
+    pub(super) fn targets<'m>(
+        &'m self,
+        call_site: &'m Location,
+        method: &'m MethodRef,
+    ) -> impl Iterator<Item = &'m MethodRef> {
+        std::iter::once(method).chain(
+            self.icfg
+                .call_graph()
+                .callees(&call_site.method, call_site.pc),
+        )
+    }
+
+    fn matches_any<'p>(
+        &self,
+        patterns: impl IntoIterator<Item = &'p MethodPattern>,
+        call_site: &Location,
+        method: &MethodRef,
+    ) -> bool {
+        patterns.into_iter().any(|pattern| {
+            self.targets(call_site, method)
+                .any(|target| pattern.matches(target))
+        })
+    }
+
+    /// Checks whether a call is modeled by the specification rather than analyzed.
+    fn is_modeled(&self, call_site: &Location, method: &MethodRef) -> bool {
+        self.matches_any(&self.spec.sources, call_site, method)
+            || self.matches_any(&self.spec.sanitizers, call_site, method)
+            || self.matches_any(
+                self.propagators.iter().map(|it| &it.method),
+                call_site,
+                method,
+            )
+    }
+
+    /// Computes the facts after a call from a fact before it without analyzing the callees.
+    fn call_site_flow(
+        &self,
+        call_site: &Location,
+        result: Identifier,
+        expr: &Expression,
+        fact: &TaintFact,
+    ) -> BTreeSet<TaintFact> {
+        let mut facts = BTreeSet::new();
+        match (expr, fact) {
+            (Expression::Call { method, .. }, TaintFact::Zero) => {
+                if self.matches_any(&self.spec.sources, call_site, method) {
+                    facts.insert(TaintFact::Tainted {
+                        value: TaintedValue::Local(result),
+                        source: call_site.clone(),
+                    });
+                }
+            }
+            (
+                Expression::Call { method, this, args },
+                TaintFact::Tainted {
+                    value: TaintedValue::Local(id),
+                    source,
+                },
+            ) => {
+                let call = CallValues {
+                    result,
+                    this: this.as_ref(),
+                    args,
+                };
+                let propagators = self.propagators.iter().filter(|it| {
+                    self.targets(call_site, method)
+                        .any(|target| it.method.matches(target))
+                });
+                for propagator in propagators {
+                    if propagator.from.iter().any(|it| call.contains(*it, *id)) {
+                        let tainted = propagator.to.iter().flat_map(|it| call.values(*it));
+                        facts.extend(tainted.map(|value| TaintFact::Tainted {
+                            value: TaintedValue::Local(value),
+                            source: source.clone(),
+                        }));
+                    }
+                }
+                if self.matches_any(&self.spec.sanitizers, call_site, method) {
+                    let sanitized = TaintedValue::Local(result);
+                    facts.retain(
+                        |it| !matches!(it, TaintFact::Tainted { value, .. } if *value == sanitized),
+                    );
+                }
+                facts.insert(fact.clone());
+            }
+            (
+                Expression::Closure { captures, .. },
+                TaintFact::Tainted {
+                    value: TaintedValue::Local(id),
+                    source,
+                },
+            ) => {
+                if captures.iter().flatten().any(|it| it == id) {
+                    facts.insert(TaintFact::Tainted {
+                        value: TaintedValue::Local(result),
+                        source: source.clone(),
+                    });
+                }
+                facts.insert(fact.clone());
+            }
+            (_, TaintFact::Zero) => {}
+            (_, TaintFact::Tainted { .. }) => {
+                facts.insert(fact.clone());
+            }
+        }
+        facts
+    }
+}
+
+/// The values involved in a call.
+struct CallValues<'a> {
+    result: Identifier,
+    this: Option<&'a Argument>,
+    args: &'a [Argument],
+}
+
+impl CallValues<'_> {
+    fn values(&self, position: TaintPosition) -> Vec<Identifier> {
+        match position {
+            TaintPosition::Return => vec![self.result],
+            TaintPosition::Receiver => self.this.into_iter().flatten().copied().collect(),
+            TaintPosition::Argument(_) | TaintPosition::AllArguments => self
+                .args
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| position.covers_argument(*index))
+                .flat_map(|(_, arg)| arg.iter().copied())
+                .collect(),
+        }
+    }
+
+    fn contains(&self, position: TaintPosition, id: Identifier) -> bool {
+        self.values(position).contains(&id)
+    }
+}
+
+impl IfdsProblem for TaintProblem<'_> {
+    type Fact = TaintFact;
+
+    fn zero(&self) -> Self::Fact {
+        TaintFact::Zero
+    }
+
+    fn initial_seeds(&self) -> Vec<(MethodRef, Self::Fact)> {
+        self.icfg
+            .call_graph()
+            .entry_points()
+            .map(|it| (it.clone(), TaintFact::Zero))
+            .collect()
+    }
+
+    fn normal_flow(
+        &self,
+        current: &Location,
+        _successor: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact> {
+        let Some((result, expr)) = self.definition_at(current) else {
+            return BTreeSet::from([fact.clone()]);
+        };
+        if matches!(expr, Expression::Call { .. } | Expression::Closure { .. }) {
+            // Calls to methods that are not in the call graph.
+            return self.call_site_flow(current, result, expr, fact);
+        }
+        let TaintFact::Tainted { value, source } = fact else {
+            return BTreeSet::new();
+        };
+        let mut facts = BTreeSet::from([fact.clone()]);
+        let mut taint = |value| {
+            facts.insert(TaintFact::Tainted {
+                value,
+                source: source.clone(),
+            });
+        };
+        let used = |arg: &Argument| matches!(value, TaintedValue::Local(id) if arg.iter().any(|it| it == id));
+        match expr {
+            Expression::Field(FieldAccess::ReadStatic { field }) => {
+                if matches!(value, TaintedValue::StaticField(it) if it == self.icfg.resolve_field(field))
+                {
+                    taint(TaintedValue::Local(result));
+                }
+            }
+            Expression::Field(FieldAccess::ReadInstance { field, .. }) => {
+                if matches!(value, TaintedValue::InstanceField(it) if it == self.icfg.resolve_field(field))
+                {
+                    taint(TaintedValue::Local(result));
+                }
+            }
+            Expression::Field(FieldAccess::WriteStatic { field, value }) if used(value) => {
+                taint(TaintedValue::StaticField(
+                    self.icfg.resolve_field(field).clone(),
+                ));
+            }
+            Expression::Field(FieldAccess::WriteInstance { field, value, .. }) if used(value) => {
+                taint(TaintedValue::InstanceField(
+                    self.icfg.resolve_field(field).clone(),
+                ));
+            }
+            Expression::Array(ArrayOperation::Write {
+                array_ref, value, ..
+            }) if used(value) => {
+                array_ref
+                    .iter()
+                    .for_each(|it| taint(TaintedValue::Local(*it)));
+            }
+            Expression::Array(ArrayOperation::Read { array_ref, .. }) if used(array_ref) => {
+                taint(TaintedValue::Local(result));
+            }
+            Expression::Math(_) | Expression::Conversion(_) => {
+                if matches!(value, TaintedValue::Local(id) if expr.uses().contains(id)) {
+                    taint(TaintedValue::Local(result));
+                }
+            }
+            _ => {}
+        }
+        facts
+    }
+
+    fn call_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact> {
+        let Some((_, expr)) = self.definition_at(call_site) else {
+            return BTreeSet::new();
+        };
+        let TaintFact::Tainted { value, source } = fact else {
+            return BTreeSet::new();
+        };
+        let TaintedValue::Local(id) = value else {
+            return BTreeSet::from([fact.clone()]);
+        };
+        // Maps the values at the call site to the receiver and the arguments of the callee.
+        let mut params: Vec<(Identifier, &Argument)> = Vec::new();
+        match expr {
+            Expression::Call { method, this, args } => {
+                if self.is_modeled(call_site, method) {
+                    return BTreeSet::new();
+                }
+                params.extend(this.iter().map(|it| (Identifier::This, it)));
+                params.extend((0..).map(Identifier::Arg).zip(args));
+            }
+            Expression::Closure { captures, .. } => {
+                let is_static = self
+                    .icfg
+                    .method(callee)
+                    .is_some_and(|it| it.access_flags.contains(method::AccessFlags::STATIC));
+                let mut captures = captures.iter();
+                if !is_static {
+                    params.extend(captures.next().map(|it| (Identifier::This, it)));
+                }
+                params.extend((0..).map(Identifier::Arg).zip(captures));
+            }
+            _ => {}
+        }
+        params
+            .into_iter()
+            .filter(|(_, arg)| arg.iter().any(|it| it == id))
+            .map(|(param, _)| TaintFact::Tainted {
+                value: TaintedValue::Local(param),
+                source: source.clone(),
+            })
+            .collect()
+    }
+
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        _callee: &MethodRef,
+        exit: &Location,
+        _return_site: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact> {
+        let TaintFact::Tainted { value, source } = fact else {
+            return BTreeSet::new();
+        };
+        let TaintedValue::Local(id) = value else {
+            return BTreeSet::from([fact.clone()]);
+        };
+        let Some((result, expr)) = self.definition_at(call_site) else {
+            return BTreeSet::new();
+        };
+        let mut tainted = Vec::new();
+        if let Some(MokaInstruction::Return(Some(returned))) = self.icfg.instruction_at(exit) {
+            if returned.iter().any(|it| it == id) {
+                tainted.push(result);
+            }
+        }
+        // Values reachable from the receiver or the arguments may be tainted by the callee.
+        if let Expression::Call { this, args, .. } = expr {
+            let caller_values = match id {
+                Identifier::This => this.as_ref(),
+                Identifier::Arg(index) => args.get(usize::from(*index)),
+                _ => None,
+            };
+            tainted.extend(caller_values.into_iter().flatten());
+        }
+        tainted
+            .into_iter()
+            .map(|it| TaintFact::Tainted {
+                value: TaintedValue::Local(it),
+                source: source.clone(),
+            })
+            .collect()
+    }
+
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        _return_site: &Location,
+        fact: &Self::Fact,
+    ) -> BTreeSet<Self::Fact> {
+        match self.definition_at(call_site) {
+            Some((result, expr)) => self.call_site_flow(call_site, result, expr, fact),
+            None => BTreeSet::from([fact.clone()]),
+        }
+    }
+}
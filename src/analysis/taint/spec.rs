@@ -0,0 +1,499 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{jvm::references::MethodRef, types::method_descriptor::MethodDescriptor};
+
+/// An error that occurs when parsing a [`TaintSpec`].
+#[derive(Debug, thiserror::Error)]
+pub enum SpecError {
+    /// A method pattern is malformed.
+    #[error("Invalid method pattern: {0}")]
+    InvalidPattern(String),
+    /// A taint position is malformed.
+    #[error("Invalid taint position: {0}")]
+    InvalidPosition(String),
+    /// The format of a specification file cannot be determined from its extension.
+    #[error("Unknown specification format: {0}")]
+    UnknownFormat(std::path::PathBuf),
+    /// The specification file cannot be read.
+    #[error("Failed to read the specification: {0}")]
+    IO(#[from] std::io::Error),
+    /// The TOML specification is malformed.
+    #[cfg(feature = "taint-spec")]
+    #[error("Malformed TOML specification: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The JSON specification is malformed.
+    #[cfg(feature = "taint-spec")]
+    #[error("Malformed JSON specification: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A pattern matching methods, written as `owner::name` or `owner::name(descriptor)`.
+///
+/// The owner and the name may contain `*`, which matches any sequence of characters.
+/// For example, `java/util/*::add` matches `add` in any class in `java/util` and its subpackages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "taint-spec",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub struct MethodPattern {
+    /// The pattern of the binary name of the class declaring the method.
+    pub owner: String,
+    /// The pattern of the method name.
+    pub name: String,
+    /// The descriptor of the method, or [`None`] to match any descriptor.
+    pub descriptor: Option<MethodDescriptor>,
+}
+
+impl MethodPattern {
+    /// Checks whether the pattern matches a method.
+    #[must_use]
+    pub fn matches(&self, method: &MethodRef) -> bool {
+        glob_matches(&self.owner, &method.owner.binary_name)
+            && glob_matches(&self.name, &method.name)
+            && self
+                .descriptor
+                .as_ref()
+                .is_none_or(|it| *it == method.descriptor)
+    }
+}
+
+impl FromStr for MethodPattern {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SpecError::InvalidPattern(s.to_owned());
+        let (owner, rest) = s.split_once("::").ok_or_else(invalid)?;
+        let (name, descriptor) = match rest.find('(') {
+            Some(index) => {
+                let descriptor = rest[index..].parse().map_err(|_| invalid())?;
+                (&rest[..index], Some(descriptor))
+            }
+            None => (rest, None),
+        };
+        if owner.is_empty() || name.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            descriptor,
+        })
+    }
+}
+
+impl TryFrom<String> for MethodPattern {
+    type Error = SpecError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for MethodPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.owner, self.name)?;
+        if let Some(descriptor) = &self.descriptor {
+            write!(f, "{}", descriptor.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` in the pattern matches any sequence of characters.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|it| text.is_char_boundary(*it))
+                .any(|it| glob_matches(rest, &text[it..]))
+        }
+    }
+}
+
+/// A value involved in a method call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "taint-spec",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub enum TaintPosition {
+    /// The receiver of an instance method, written as `receiver`.
+    Receiver,
+    /// The return value, written as `return`.
+    Return,
+    /// The argument at the given index, written as `arg0`, `arg1`, etc.
+    Argument(u16),
+    /// All the arguments, written as `arguments`.
+    AllArguments,
+}
+
+impl TaintPosition {
+    /// Checks whether this position covers the argument at `index`.
+    #[must_use]
+    pub fn covers_argument(self, index: usize) -> bool {
+        match self {
+            Self::Argument(it) => usize::from(it) == index,
+            Self::AllArguments => true,
+            Self::Receiver | Self::Return => false,
+        }
+    }
+}
+
+impl FromStr for TaintPosition {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "receiver" => Ok(Self::Receiver),
+            "return" => Ok(Self::Return),
+            "arguments" => Ok(Self::AllArguments),
+            _ => s
+                .strip_prefix("arg")
+                .and_then(|it| it.parse().ok())
+                .map(Self::Argument)
+                .ok_or_else(|| SpecError::InvalidPosition(s.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<String> for TaintPosition {
+    type Error = SpecError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for TaintPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Receiver => write!(f, "receiver"),
+            Self::Return => write!(f, "return"),
+            Self::Argument(index) => write!(f, "arg{index}"),
+            Self::AllArguments => write!(f, "arguments"),
+        }
+    }
+}
+
+/// A method whose arguments must not be tainted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "taint-spec",
+    derive(serde::Deserialize),
+    serde(from = "serde_impl::SinkDef")
+)]
+pub struct Sink {
+    /// The methods that are sinks.
+    pub method: MethodPattern,
+    /// The sensitive values, or [`None`] if all the arguments and the receiver are sensitive.
+    pub positions: Option<Vec<TaintPosition>>,
+}
+
+impl Sink {
+    /// Checks whether a value at `position` passed to the sink is sensitive.
+    #[must_use]
+    pub fn is_sensitive(&self, position: TaintPosition) -> bool {
+        self.positions.as_ref().is_none_or(|positions| {
+            positions.iter().any(|it| match position {
+                TaintPosition::Argument(index) => it.covers_argument(usize::from(index)),
+                _ => *it == position,
+            })
+        })
+    }
+}
+
+impl From<MethodPattern> for Sink {
+    fn from(method: MethodPattern) -> Self {
+        Self {
+            method,
+            positions: None,
+        }
+    }
+}
+
+/// A method that transfers taint between the values involved in its calls, usually a library
+/// method whose body is not analyzed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "taint-spec", derive(serde::Deserialize))]
+pub struct Propagator {
+    /// The methods that propagate taint.
+    pub method: MethodPattern,
+    /// The values whose taint is propagated.
+    pub from: Vec<TaintPosition>,
+    /// The values that become tainted.
+    pub to: Vec<TaintPosition>,
+}
+
+/// The specification of a taint analysis.
+///
+/// With the `taint-spec` feature, it can be loaded from a TOML file like the following, or an
+/// equivalent JSON file.
+/// ```toml
+/// sources = ["javax/servlet/ServletRequest::getParameter"]
+/// sanitizers = ["org/owasp/encoder/Encode::forSql"]
+///
+/// [[sinks]]
+/// method = "java/sql/Statement::executeQuery"
+/// positions = ["arg0"]
+///
+/// [[propagators]]
+/// method = "com/example/Wrapper::unwrap"
+/// from = ["receiver"]
+/// to = ["return"]
+/// ```
+/// A sink can also be written as a plain pattern, in which case all its arguments are sensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "taint-spec",
+    derive(serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct TaintSpec {
+    /// The methods whose return values are tainted.
+    pub sources: Vec<MethodPattern>,
+    /// The methods whose arguments must not be tainted.
+    pub sinks: Vec<Sink>,
+    /// The methods whose return values are never tainted.
+    pub sanitizers: Vec<MethodPattern>,
+    /// The methods that propagate taint in addition to the default ones.
+    pub propagators: Vec<Propagator>,
+    /// Whether to use the propagators in [`TaintSpec::default_propagators`], which cover
+    /// `String`, `StringBuilder`, `StringBuffer` and the collections in `java/util`.
+    pub use_default_propagators: bool,
+}
+
+impl Default for TaintSpec {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            sinks: Vec::new(),
+            sanitizers: Vec::new(),
+            propagators: Vec::new(),
+            use_default_propagators: true,
+        }
+    }
+}
+
+/// The default propagators as `(pattern, from, to)`.
+const DEFAULT_PROPAGATORS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "java/lang/String::*",
+        &["receiver", "arguments"],
+        &["return"],
+    ),
+    ("java/lang/StringBuilder::*", &["receiver"], &["return"]),
+    (
+        "java/lang/StringBuilder::append",
+        &["arguments"],
+        &["receiver", "return"],
+    ),
+    (
+        "java/lang/StringBuilder::insert",
+        &["arguments"],
+        &["receiver", "return"],
+    ),
+    (
+        "java/lang/StringBuilder::<init>",
+        &["arguments"],
+        &["receiver"],
+    ),
+    ("java/lang/StringBuffer::*", &["receiver"], &["return"]),
+    (
+        "java/lang/StringBuffer::append",
+        &["arguments"],
+        &["receiver", "return"],
+    ),
+    (
+        "java/lang/StringBuffer::insert",
+        &["arguments"],
+        &["receiver", "return"],
+    ),
+    (
+        "java/lang/StringBuffer::<init>",
+        &["arguments"],
+        &["receiver"],
+    ),
+    ("java/util/*::<init>", &["arguments"], &["receiver"]),
+    ("java/util/*::add*", &["arguments"], &["receiver"]),
+    ("java/util/*::put*", &["arguments"], &["receiver"]),
+    ("java/util/*::set", &["arguments"], &["receiver"]),
+    ("java/util/*::offer*", &["arguments"], &["receiver"]),
+    ("java/util/*::push", &["arguments"], &["receiver"]),
+    ("java/util/*::of", &["arguments"], &["return"]),
+    ("java/util/*::asList", &["arguments"], &["return"]),
+    ("java/util/*::get*", &["receiver"], &["return"]),
+    ("java/util/*::remove*", &["receiver"], &["return"]),
+    ("java/util/*::poll*", &["receiver"], &["return"]),
+    ("java/util/*::peek*", &["receiver"], &["return"]),
+    ("java/util/*::pop", &["receiver"], &["return"]),
+    ("java/util/*::next", &["receiver"], &["return"]),
+    ("java/util/*::iterator", &["receiver"], &["return"]),
+    ("java/util/*::values", &["receiver"], &["return"]),
+    ("java/util/*::entrySet", &["receiver"], &["return"]),
+    ("java/util/*::toArray", &["receiver"], &["return"]),
+];
+
+impl TaintSpec {
+    /// Returns the built-in propagators for strings and collections.
+    #[must_use]
+    // The built-in patterns and positions are always valid.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn default_propagators() -> Vec<Propagator> {
+        let positions = |it: &[&str]| it.iter().map(|p| p.parse().unwrap()).collect();
+        DEFAULT_PROPAGATORS
+            .iter()
+            .map(|(pattern, from, to)| Propagator {
+                method: pattern.parse().unwrap(),
+                from: positions(from),
+                to: positions(to),
+            })
+            .collect()
+    }
+
+    /// Returns the propagators used by the analysis, including the default ones if enabled.
+    #[must_use]
+    pub fn effective_propagators(&self) -> Vec<Propagator> {
+        let mut propagators = self.propagators.clone();
+        if self.use_default_propagators {
+            propagators.extend(Self::default_propagators());
+        }
+        propagators
+    }
+}
+
+#[cfg(feature = "taint-spec")]
+impl TaintSpec {
+    /// Parses a specification in TOML.
+    /// # Errors
+    /// - [`SpecError::Toml`] if the specification is malformed.
+    pub fn from_toml(s: &str) -> Result<Self, SpecError> {
+        toml::from_str(s).map_err(Into::into)
+    }
+
+    /// Parses a specification in JSON.
+    /// # Errors
+    /// - [`SpecError::Json`] if the specification is malformed.
+    pub fn from_json(s: &str) -> Result<Self, SpecError> {
+        serde_json::from_str(s).map_err(Into::into)
+    }
+
+    /// Reads a specification from a `.toml` or `.json` file.
+    /// # Errors
+    /// See [`SpecError`].
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, SpecError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|it| it.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            Some("json") => Self::from_json(&content),
+            _ => Err(SpecError::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+#[cfg(feature = "taint-spec")]
+mod serde_impl {
+    use super::{MethodPattern, Sink, TaintPosition};
+
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum SinkDef {
+        Pattern(MethodPattern),
+        Detailed {
+            method: MethodPattern,
+            positions: Option<Vec<TaintPosition>>,
+        },
+    }
+
+    impl From<SinkDef> for Sink {
+        fn from(value: SinkDef) -> Self {
+            match value {
+                SinkDef::Pattern(method) => method.into(),
+                SinkDef::Detailed { method, positions } => Self { method, positions },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jvm::references::ClassRef;
+
+    use super::*;
+
+    fn method(owner: &str, name: &str, descriptor: &str) -> MethodRef {
+        MethodRef {
+            owner: ClassRef::new(owner),
+            name: name.to_owned(),
+            descriptor: descriptor.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn pattern_matching() {
+        let pattern: MethodPattern = "java/util/*::add*".parse().unwrap();
+        assert!(pattern.matches(&method("java/util/List", "add", "(Ljava/lang/Object;)Z")));
+        assert!(pattern.matches(&method(
+            "java/util/Deque",
+            "addFirst",
+            "(Ljava/lang/Object;)V"
+        )));
+        assert!(!pattern.matches(&method("java/lang/List", "add", "(Ljava/lang/Object;)Z")));
+
+        let pattern: MethodPattern =
+            "java/sql/Statement::executeQuery(Ljava/lang/String;)Ljava/sql/ResultSet;"
+                .parse()
+                .unwrap();
+        assert!(pattern.matches(&method(
+            "java/sql/Statement",
+            "executeQuery",
+            "(Ljava/lang/String;)Ljava/sql/ResultSet;"
+        )));
+        assert!(!pattern.matches(&method("java/sql/Statement", "executeQuery", "()V")));
+        assert_eq!(
+            pattern.to_string(),
+            "java/sql/Statement::executeQuery(Ljava/lang/String;)Ljava/sql/ResultSet;"
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!("java/sql/Statement".parse::<MethodPattern>().is_err());
+        assert!("::executeQuery".parse::<MethodPattern>().is_err());
+        assert!("A::b(".parse::<MethodPattern>().is_err());
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(
+            "arg2".parse::<TaintPosition>().unwrap(),
+            TaintPosition::Argument(2)
+        );
+        assert!("argument".parse::<TaintPosition>().is_err());
+        let sink = Sink {
+            method: "A::b".parse().unwrap(),
+            positions: Some(vec![TaintPosition::Argument(1)]),
+        };
+        assert!(sink.is_sensitive(TaintPosition::Argument(1)));
+        assert!(!sink.is_sensitive(TaintPosition::Argument(0)));
+        assert!(!sink.is_sensitive(TaintPosition::Receiver));
+    }
+
+    #[test]
+    fn default_propagators_are_valid() {
+        assert_eq!(
+            TaintSpec::default_propagators().len(),
+            DEFAULT_PROPAGATORS.len()
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    jvm::{code::VerificationType, references::ClassRef},
+    macros::see_jvm_spec,
+    types::field_type::{FieldType, PrimitiveType},
+};
+
+use super::{HierarchyOracle, VerifyErrorKind};
+
+use VerificationType::{
+    DoubleVariable, FloatVariable, IntegerVariable, LongVariable, NullVariable, ObjectVariable,
+    TopVariable, UninitializedThisVariable, UninitializedVariable,
+};
+
+pub(super) const OBJECT: &str = "java/lang/Object";
+pub(super) const THROWABLE: &str = "java/lang/Throwable";
+
+/// Returns the verification type of values of a field type.
+pub(super) fn of_field_type(field_type: &FieldType) -> VerificationType {
+    match field_type {
+        FieldType::Base(PrimitiveType::Long) => LongVariable,
+        FieldType::Base(PrimitiveType::Double) => DoubleVariable,
+        FieldType::Base(PrimitiveType::Float) => FloatVariable,
+        FieldType::Base(_) => IntegerVariable,
+        FieldType::Object(class) => ObjectVariable(class.clone()),
+        FieldType::Array(_) => ObjectVariable(ClassRef::new(field_type.descriptor())),
+    }
+}
+
+/// Returns the element type of an array class, e.g., `int` for `[I`.
+pub(super) fn component_type(class: &ClassRef) -> Option<FieldType> {
+    if !class.binary_name.starts_with('[') {
+        return None;
+    }
+    match class.binary_name.parse() {
+        Ok(FieldType::Array(component)) => Some(*component),
+        _ => None,
+    }
+}
+
+/// Checks whether values of `from` can be used where `to` is expected.
+#[doc = see_jvm_spec!(4, 10, 1, 2)]
+pub(super) fn is_assignable(
+    hierarchy: &dyn HierarchyOracle,
+    from: &VerificationType,
+    to: &VerificationType,
+) -> bool {
+    match (from, to) {
+        _ if from == to => true,
+        (_, TopVariable) | (NullVariable, ObjectVariable(_)) => true,
+        (ObjectVariable(from), ObjectVariable(to)) => is_class_assignable(hierarchy, from, to),
+        _ => false,
+    }
+}
+
+fn is_class_assignable(hierarchy: &dyn HierarchyOracle, from: &ClassRef, to: &ClassRef) -> bool {
+    if from == to || to.binary_name == OBJECT {
+        return true;
+    }
+    match (component_type(from), component_type(to)) {
+        (Some(from), Some(to)) => match (of_field_type(&from), of_field_type(&to)) {
+            (ObjectVariable(from), ObjectVariable(to)) => {
+                is_class_assignable(hierarchy, &from, &to)
+            }
+            _ => from == to,
+        },
+        (Some(_), None) => matches!(
+            to.binary_name.as_str(),
+            "java/lang/Cloneable" | "java/io/Serializable"
+        ),
+        (None, Some(_)) => false,
+        // Interfaces are treated as `java/lang/Object` by the verifier.
+        (None, None) => hierarchy.is_interface(to) || hierarchy.is_subtype_of(from, to),
+    }
+}
+
+/// Checks whether a type is a reference, including `null` and uninitialized objects.
+pub(super) fn is_reference(ty: &VerificationType) -> bool {
+    matches!(
+        ty,
+        NullVariable | ObjectVariable(_) | UninitializedThisVariable | UninitializedVariable { .. }
+    )
+}
+
+fn is_category2(ty: &VerificationType) -> bool {
+    matches!(ty, LongVariable | DoubleVariable)
+}
+
+/// Expands types to slots, where `long` and `double` take two slots with the second one `top`.
+pub(super) fn expand(types: impl IntoIterator<Item = VerificationType>) -> Vec<VerificationType> {
+    let mut slots = Vec::new();
+    for ty in types {
+        let wide = is_category2(&ty);
+        slots.push(ty);
+        if wide {
+            slots.push(TopVariable);
+        }
+    }
+    slots
+}
+
+/// The types of the local variables and the operand stack at an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Frame {
+    /// The types of the local variables, padded with `top` to `max_locals`.
+    pub locals: Vec<VerificationType>,
+    /// The types of the operand stack, with the top of the stack last.
+    pub stack: Vec<VerificationType>,
+    /// Whether `this` is not yet initialized in a constructor.
+    pub this_uninitialized: bool,
+    pub max_stack: u16,
+}
+
+impl Frame {
+    /// Creates a frame from types in the stack map format.
+    pub fn new(
+        locals: Vec<VerificationType>,
+        stack: Vec<VerificationType>,
+        max_locals: u16,
+        max_stack: u16,
+    ) -> Result<Self, VerifyErrorKind> {
+        let mut locals = expand(locals);
+        if locals.len() > usize::from(max_locals) {
+            return Err(VerifyErrorKind::LocalOutOfBounds {
+                index: u16::try_from(locals.len() - 1).unwrap_or(u16::MAX),
+                max_locals,
+            });
+        }
+        locals.resize(usize::from(max_locals), TopVariable);
+        let stack = expand(stack);
+        if stack.len() > usize::from(max_stack) {
+            return Err(VerifyErrorKind::StackOverflow(max_stack));
+        }
+        let this_uninitialized = locals.contains(&UninitializedThisVariable);
+        Ok(Self {
+            locals,
+            stack,
+            this_uninitialized,
+            max_stack,
+        })
+    }
+
+    /// Checks whether this frame can flow to a location with `target` as its stack map frame.
+    pub fn is_assignable_to(&self, hierarchy: &dyn HierarchyOracle, target: &Self) -> bool {
+        let all_assignable = |from: &[VerificationType], to: &[VerificationType]| {
+            from.len() == to.len()
+                && from
+                    .iter()
+                    .zip(to)
+                    .all(|(from, to)| is_assignable(hierarchy, from, to))
+        };
+        all_assignable(&self.locals, &target.locals)
+            && all_assignable(&self.stack, &target.stack)
+            && (!self.this_uninitialized || target.this_uninitialized)
+    }
+
+    pub fn push(&mut self, ty: VerificationType) -> Result<(), VerifyErrorKind> {
+        self.stack.extend(expand([ty]));
+        if self.stack.len() > usize::from(self.max_stack) {
+            return Err(VerifyErrorKind::StackOverflow(self.max_stack));
+        }
+        Ok(())
+    }
+
+    fn pop_slot(&mut self) -> Result<VerificationType, VerifyErrorKind> {
+        self.stack.pop().ok_or(VerifyErrorKind::StackUnderflow)
+    }
+
+    /// Pops a value of any type and returns its type.
+    pub fn pop_any(&mut self) -> Result<VerificationType, VerifyErrorKind> {
+        let top = self.pop_slot()?;
+        if top != TopVariable {
+            return Ok(top);
+        }
+        match self.pop_slot()? {
+            it if is_category2(&it) => Ok(it),
+            _ => Err(VerifyErrorKind::SplitCategory2),
+        }
+    }
+
+    /// Pops a value assignable to `expected` and returns its actual type.
+    pub fn pop(
+        &mut self,
+        hierarchy: &dyn HierarchyOracle,
+        expected: &VerificationType,
+    ) -> Result<VerificationType, VerifyErrorKind> {
+        let found = self.pop_any()?;
+        if is_assignable(hierarchy, &found, expected) {
+            Ok(found)
+        } else {
+            Err(VerifyErrorKind::TypeMismatch {
+                expected: expected.clone(),
+                found,
+            })
+        }
+    }
+
+    /// Pops a reference, which may be `null` or an uninitialized object.
+    pub fn pop_reference(&mut self) -> Result<VerificationType, VerifyErrorKind> {
+        match self.pop_any()? {
+            it if is_reference(&it) => Ok(it),
+            it => Err(VerifyErrorKind::NotReference(it)),
+        }
+    }
+
+    /// Checks that the top `depth` slots of the operand stack do not split a category 2 value.
+    fn check_boundary(&self, depth: usize) -> Result<(), VerifyErrorKind> {
+        let index = self
+            .stack
+            .len()
+            .checked_sub(depth)
+            .ok_or(VerifyErrorKind::StackUnderflow)?;
+        if self.stack[index] == TopVariable {
+            Err(VerifyErrorKind::SplitCategory2)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pops `count` slots of the operand stack.
+    pub fn pop_slots(&mut self, count: usize) -> Result<(), VerifyErrorKind> {
+        self.check_boundary(count)?;
+        self.stack.truncate(self.stack.len() - count);
+        Ok(())
+    }
+
+    /// Duplicates the top `count` slots and inserts the copies `depth` slots below the top.
+    pub fn dup(&mut self, count: usize, depth: usize) -> Result<(), VerifyErrorKind> {
+        self.check_boundary(count)?;
+        self.check_boundary(depth)?;
+        let len = self.stack.len();
+        let copies: Vec<_> = self.stack[len - count..].to_vec();
+        let index = len - depth;
+        self.stack.splice(index..index, copies);
+        if self.stack.len() > usize::from(self.max_stack) {
+            return Err(VerifyErrorKind::StackOverflow(self.max_stack));
+        }
+        Ok(())
+    }
+
+    /// Swaps the top two slots of the operand stack.
+    pub fn swap(&mut self) -> Result<(), VerifyErrorKind> {
+        self.check_boundary(1)?;
+        self.check_boundary(2)?;
+        let len = self.stack.len();
+        self.stack.swap(len - 1, len - 2);
+        Ok(())
+    }
+
+    fn local(&self, index: u16) -> Result<&VerificationType, VerifyErrorKind> {
+        self.locals
+            .get(usize::from(index))
+            .ok_or(VerifyErrorKind::LocalOutOfBounds {
+                index,
+                max_locals: u16::try_from(self.locals.len()).unwrap_or(u16::MAX),
+            })
+    }
+
+    /// Loads a primitive value of type `expected` from a local variable.
+    pub fn load(&self, index: u16, expected: &VerificationType) -> Result<(), VerifyErrorKind> {
+        let found = self.local(index)?;
+        if found != expected {
+            return Err(VerifyErrorKind::TypeMismatch {
+                expected: expected.clone(),
+                found: found.clone(),
+            });
+        }
+        if is_category2(found) && *self.local(index + 1)? != TopVariable {
+            return Err(VerifyErrorKind::SplitCategory2);
+        }
+        Ok(())
+    }
+
+    /// Loads a reference from a local variable and returns its type.
+    pub fn load_reference(&self, index: u16) -> Result<VerificationType, VerifyErrorKind> {
+        match self.local(index)? {
+            it if is_reference(it) => Ok(it.clone()),
+            it => Err(VerifyErrorKind::NotReference(it.clone())),
+        }
+    }
+
+    /// Stores a value of type `ty` to a local variable.
+    pub fn store(&mut self, index: u16, ty: VerificationType) -> Result<(), VerifyErrorKind> {
+        let wide = is_category2(&ty);
+        self.local(index)?;
+        if wide {
+            self.local(index + 1)?;
+        }
+        let index = usize::from(index);
+        // Overwriting the second half of a category 2 value invalidates the first half.
+        if index > 0 && is_category2(&self.locals[index - 1]) {
+            self.locals[index - 1] = TopVariable;
+        }
+        self.locals[index] = ty;
+        if wide {
+            self.locals[index + 1] = TopVariable;
+        }
+        Ok(())
+    }
+
+    /// Replaces all occurrences of an uninitialized type after its constructor is called.
+    pub fn initialize(&mut self, uninitialized: &VerificationType, class: &ClassRef) {
+        for slot in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+            if slot == uninitialized {
+                *slot = ObjectVariable(class.clone());
+            }
+        }
+        if *uninitialized == UninitializedThisVariable {
+            self.this_uninitialized = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::class_hierarchy::ClassHierarchy;
+
+    use super::*;
+
+    fn object(name: &str) -> VerificationType {
+        ObjectVariable(ClassRef::new(name))
+    }
+
+    #[test]
+    fn assignability() {
+        let hierarchy = ClassHierarchy::default();
+        let is_assignable = |from, to| is_assignable(&hierarchy, &from, &to);
+        assert!(is_assignable(IntegerVariable, TopVariable));
+        assert!(is_assignable(NullVariable, object("java/lang/String")));
+        assert!(is_assignable(object("java/lang/String"), object(OBJECT)));
+        assert!(is_assignable(object("[[I"), object("[Ljava/lang/Object;")));
+        assert!(is_assignable(
+            object("[Ljava/lang/String;"),
+            object("java/lang/Cloneable")
+        ));
+        assert!(!is_assignable(object("[I"), object("[J")));
+        assert!(!is_assignable(object("[I"), object("[Ljava/lang/Object;")));
+        assert!(!is_assignable(object(OBJECT), object("java/lang/String")));
+        assert!(!is_assignable(IntegerVariable, FloatVariable));
+        assert!(!is_assignable(UninitializedThisVariable, object(OBJECT)));
+    }
+
+    #[test]
+    fn category2_values() {
+        let mut frame = Frame::new(vec![LongVariable], vec![], 3, 4).unwrap();
+        assert_eq!(frame.locals, vec![LongVariable, TopVariable, TopVariable]);
+        frame.push(IntegerVariable).unwrap();
+        frame.push(DoubleVariable).unwrap();
+        assert_eq!(frame.dup(1, 1), Err(VerifyErrorKind::SplitCategory2));
+        frame.push(IntegerVariable).unwrap();
+        assert_eq!(
+            frame.push(IntegerVariable),
+            Err(VerifyErrorKind::StackOverflow(4))
+        );
+
+        let mut frame = Frame::new(vec![LongVariable], vec![], 3, 4).unwrap();
+        frame.store(1, IntegerVariable).unwrap();
+        assert_eq!(
+            frame.locals,
+            vec![TopVariable, IntegerVariable, TopVariable]
+        );
+    }
+}
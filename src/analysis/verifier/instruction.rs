@@ -0,0 +1,922 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    jvm::{
+        class::Class,
+        code::{
+            Instruction, MethodBody, ProgramCounter, StackMapFrame, VerificationType,
+            WideInstruction,
+        },
+        field::ConstantValue,
+        method::{self, Method},
+        references::{ClassRef, FieldRef, MethodRef},
+    },
+    types::{
+        field_type::{FieldType, PrimitiveType},
+        method_descriptor::ReturnType,
+    },
+};
+
+use super::{
+    frame::{component_type, is_assignable, of_field_type, Frame, OBJECT, THROWABLE},
+    HierarchyOracle, VerifyError, VerifyErrorKind,
+};
+
+use VerificationType::{
+    DoubleVariable, FloatVariable, IntegerVariable, LongVariable, NullVariable, ObjectVariable,
+    UninitializedThisVariable, UninitializedVariable,
+};
+
+fn object(name: &str) -> VerificationType {
+    ObjectVariable(ClassRef::new(name))
+}
+
+/// The effect of an instruction on the control flow.
+struct Step {
+    /// The frame after the instruction.
+    frame: Frame,
+    /// Whether the execution may continue to the next instruction.
+    falls_through: bool,
+    /// The branch targets of the instruction.
+    targets: Vec<ProgramCounter>,
+}
+
+impl Step {
+    fn next(frame: Frame) -> Self {
+        Self {
+            frame,
+            falls_through: true,
+            targets: Vec::new(),
+        }
+    }
+
+    fn branch(frame: Frame, target: ProgramCounter) -> Self {
+        Self {
+            frame,
+            falls_through: true,
+            targets: vec![target],
+        }
+    }
+
+    fn jump(frame: Frame, targets: Vec<ProgramCounter>) -> Self {
+        Self {
+            frame,
+            falls_through: false,
+            targets,
+        }
+    }
+}
+
+pub(super) struct MethodVerifier<'a> {
+    pub class: &'a Class,
+    pub hierarchy: &'a dyn HierarchyOracle,
+    pub method: &'a Method,
+    pub body: &'a MethodBody,
+}
+
+impl MethodVerifier<'_> {
+    pub fn verify(&self) -> Vec<VerifyError> {
+        let mut errors = Vec::new();
+        let mut report = |pc, kind| errors.push(VerifyError { pc, kind });
+        let initial_frame = match self.initial_frame() {
+            Ok(it) => it,
+            Err(kind) => {
+                report(ProgramCounter::ZERO, kind);
+                return errors;
+            }
+        };
+        let frames = match self.stack_map_frames(&initial_frame) {
+            Ok(it) => it,
+            Err((pc, kind)) => {
+                report(pc, kind);
+                return errors;
+            }
+        };
+        let mut current = Some(initial_frame);
+        // Whether the instructions without stack map frames are already reported or follow an error.
+        let mut skipping = false;
+        let mut last_pc = ProgramCounter::ZERO;
+        for (pc, instruction) in &self.body.instructions {
+            let pc = *pc;
+            last_pc = pc;
+            if let Some(frame) = frames.get(&pc) {
+                if let Some(current) = &current {
+                    if !current.is_assignable_to(self.hierarchy, frame) {
+                        report(pc, VerifyErrorKind::IncompatibleStackMapFrame(pc));
+                    }
+                }
+                current = Some(frame.clone());
+                skipping = false;
+            }
+            let Some(frame) = current.take() else {
+                if !skipping {
+                    report(pc, VerifyErrorKind::UnreachableWithoutFrame);
+                    skipping = true;
+                }
+                continue;
+            };
+            self.check_handlers(pc, &frame, &frames)
+                .into_iter()
+                .for_each(|kind| report(pc, kind));
+            match self.execute(pc, instruction, frame) {
+                Ok(step) => {
+                    for target in &step.targets {
+                        if let Err(kind) = self.check_target(*target, &step.frame, &frames) {
+                            report(pc, kind);
+                        }
+                    }
+                    current = step.falls_through.then_some(step.frame);
+                }
+                Err(kind) => {
+                    report(pc, kind);
+                    skipping = true;
+                }
+            }
+        }
+        if current.is_some() {
+            report(last_pc, VerifyErrorKind::FallOffEnd);
+        }
+        errors
+    }
+
+    fn initial_frame(&self) -> Result<Frame, VerifyErrorKind> {
+        let this_class = self.class.as_ref();
+        let mut locals = Vec::new();
+        if !self
+            .method
+            .access_flags
+            .contains(method::AccessFlags::STATIC)
+        {
+            let is_object = this_class.binary_name == OBJECT;
+            locals.push(if self.method.name == "<init>" && !is_object {
+                UninitializedThisVariable
+            } else {
+                ObjectVariable(this_class)
+            });
+        }
+        locals.extend(
+            self.method
+                .descriptor
+                .parameters_types
+                .iter()
+                .map(of_field_type),
+        );
+        Frame::new(
+            locals,
+            Vec::new(),
+            self.body.max_locals,
+            self.body.max_stack,
+        )
+    }
+
+    /// Decodes the stack map table into full frames indexed by their locations.
+    fn stack_map_frames(
+        &self,
+        initial_frame: &Frame,
+    ) -> Result<BTreeMap<ProgramCounter, Frame>, (ProgramCounter, VerifyErrorKind)> {
+        let mut frames = BTreeMap::new();
+        // The locals in the stack map format, where `long` and `double` take one entry.
+        let mut locals = compact(&initial_frame.locals);
+        let mut offset: Option<u16> = None;
+        for stack_map_frame in self.body.stack_map_table.iter().flatten() {
+            let offset_delta = match stack_map_frame {
+                StackMapFrame::SameFrame { offset_delta }
+                | StackMapFrame::SameLocals1StackItemFrame { offset_delta, .. }
+                | StackMapFrame::ChopFrame { offset_delta, .. }
+                | StackMapFrame::AppendFrame { offset_delta, .. }
+                | StackMapFrame::FullFrame { offset_delta, .. } => *offset_delta,
+            };
+            // Each frame after the first one applies at `offset_delta + 1` after the previous one.
+            let next_offset = match offset {
+                None => Some(offset_delta),
+                Some(it) => it
+                    .checked_add(offset_delta)
+                    .and_then(|it| it.checked_add(1)),
+            };
+            let Some(next_offset) = next_offset else {
+                return Err((
+                    ProgramCounter::from(u16::MAX),
+                    VerifyErrorKind::InvalidStackMapFrame("offset overflows"),
+                ));
+            };
+            offset = Some(next_offset);
+            let pc = ProgramCounter::from(next_offset);
+            let stack = match stack_map_frame {
+                StackMapFrame::SameFrame { .. } => Vec::new(),
+                StackMapFrame::SameLocals1StackItemFrame { stack, .. } => vec![stack.clone()],
+                StackMapFrame::ChopFrame { chop_count, .. } => {
+                    let Some(remaining) = locals.len().checked_sub(usize::from(*chop_count)) else {
+                        return Err((
+                            pc,
+                            VerifyErrorKind::InvalidStackMapFrame("chops too many locals"),
+                        ));
+                    };
+                    locals.truncate(remaining);
+                    Vec::new()
+                }
+                StackMapFrame::AppendFrame {
+                    locals: appended, ..
+                } => {
+                    locals.extend(appended.iter().cloned());
+                    Vec::new()
+                }
+                StackMapFrame::FullFrame {
+                    locals: full_locals,
+                    stack,
+                    ..
+                } => {
+                    locals.clone_from(full_locals);
+                    stack.clone()
+                }
+            };
+            if self.body.instruction_at(pc).is_none() {
+                return Err((
+                    pc,
+                    VerifyErrorKind::InvalidStackMapFrame("no instruction at the offset"),
+                ));
+            }
+            let frame = Frame::new(
+                locals.clone(),
+                stack,
+                self.body.max_locals,
+                self.body.max_stack,
+            )
+            .map_err(|kind| (pc, kind))?;
+            frames.insert(pc, frame);
+        }
+        Ok(frames)
+    }
+
+    fn check_target(
+        &self,
+        target: ProgramCounter,
+        frame: &Frame,
+        frames: &BTreeMap<ProgramCounter, Frame>,
+    ) -> Result<(), VerifyErrorKind> {
+        if self.body.instruction_at(target).is_none() {
+            return Err(VerifyErrorKind::InvalidBranchTarget(target));
+        }
+        let target_frame = frames
+            .get(&target)
+            .ok_or(VerifyErrorKind::MissingStackMapFrame(target))?;
+        if frame.is_assignable_to(self.hierarchy, target_frame) {
+            Ok(())
+        } else {
+            Err(VerifyErrorKind::IncompatibleStackMapFrame(target))
+        }
+    }
+
+    /// Checks the exception handlers covering `pc` with the frame before the instruction.
+    fn check_handlers(
+        &self,
+        pc: ProgramCounter,
+        frame: &Frame,
+        frames: &BTreeMap<ProgramCounter, Frame>,
+    ) -> Vec<VerifyErrorKind> {
+        let mut errors = Vec::new();
+        for handler in self.body.exception_table.iter().filter(|it| it.covers(pc)) {
+            let exception = handler
+                .catch_type
+                .clone()
+                .unwrap_or_else(|| ClassRef::new(THROWABLE));
+            let exception = ObjectVariable(exception);
+            let throwable = object(THROWABLE);
+            if !is_assignable(self.hierarchy, &exception, &throwable) {
+                errors.push(VerifyErrorKind::TypeMismatch {
+                    expected: throwable,
+                    found: exception,
+                });
+                continue;
+            }
+            let handler_frame = Frame {
+                locals: frame.locals.clone(),
+                stack: vec![exception],
+                this_uninitialized: frame.this_uninitialized,
+                max_stack: frame.max_stack,
+            };
+            if let Err(kind) = self.check_target(handler.handler_pc, &handler_frame, frames) {
+                errors.push(kind);
+            }
+        }
+        errors
+    }
+
+    fn pop(
+        &self,
+        frame: &mut Frame,
+        ty: &VerificationType,
+    ) -> Result<VerificationType, VerifyErrorKind> {
+        frame.pop(self.hierarchy, ty)
+    }
+
+    fn pop_n(
+        &self,
+        frame: &mut Frame,
+        ty: &VerificationType,
+        count: usize,
+    ) -> Result<(), VerifyErrorKind> {
+        for _ in 0..count {
+            self.pop(frame, ty)?;
+        }
+        Ok(())
+    }
+
+    /// Pops `operands` values of type `operand` and pushes a value of type `result`.
+    fn operation(
+        &self,
+        mut frame: Frame,
+        operand: &VerificationType,
+        operands: usize,
+        result: Option<VerificationType>,
+    ) -> Result<Step, VerifyErrorKind> {
+        self.pop_n(&mut frame, operand, operands)?;
+        if let Some(result) = result {
+            frame.push(result)?;
+        }
+        Ok(Step::next(frame))
+    }
+
+    fn load(mut frame: Frame, index: u16, ty: VerificationType) -> Result<Step, VerifyErrorKind> {
+        frame.load(index, &ty)?;
+        frame.push(ty)?;
+        Ok(Step::next(frame))
+    }
+
+    fn load_reference(mut frame: Frame, index: u16) -> Result<Step, VerifyErrorKind> {
+        let ty = frame.load_reference(index)?;
+        frame.push(ty)?;
+        Ok(Step::next(frame))
+    }
+
+    fn store(
+        &self,
+        mut frame: Frame,
+        index: u16,
+        ty: &VerificationType,
+    ) -> Result<Step, VerifyErrorKind> {
+        let ty = self.pop(&mut frame, ty)?;
+        frame.store(index, ty)?;
+        Ok(Step::next(frame))
+    }
+
+    fn store_reference(mut frame: Frame, index: u16) -> Result<Step, VerifyErrorKind> {
+        let ty = frame.pop_reference()?;
+        frame.store(index, ty)?;
+        Ok(Step::next(frame))
+    }
+
+    fn increment(frame: Frame, index: u16) -> Result<Step, VerifyErrorKind> {
+        frame.load(index, &IntegerVariable)?;
+        Ok(Step::next(frame))
+    }
+
+    /// Pops the index and the array for an array load or store, and returns the array type.
+    fn pop_array(&self, frame: &mut Frame) -> Result<VerificationType, VerifyErrorKind> {
+        self.pop(frame, &IntegerVariable)?;
+        let array = frame.pop_reference()?;
+        match &array {
+            NullVariable => Ok(array),
+            ObjectVariable(class) if component_type(class).is_some() => Ok(array),
+            _ => Err(VerifyErrorKind::NotArray(array)),
+        }
+    }
+
+    /// Checks that an array holds elements of one of `element_types`.
+    fn check_element(
+        array: &VerificationType,
+        element_types: &[PrimitiveType],
+    ) -> Result<(), VerifyErrorKind> {
+        let ObjectVariable(class) = array else {
+            return Ok(());
+        };
+        match component_type(class) {
+            Some(FieldType::Base(it)) if element_types.contains(&it) => Ok(()),
+            _ => Err(VerifyErrorKind::NotArray(array.clone())),
+        }
+    }
+
+    fn array_load(
+        &self,
+        mut frame: Frame,
+        element_types: &[PrimitiveType],
+    ) -> Result<Step, VerifyErrorKind> {
+        let array = self.pop_array(&mut frame)?;
+        Self::check_element(&array, element_types)?;
+        frame.push(of_field_type(&FieldType::Base(element_types[0])))?;
+        Ok(Step::next(frame))
+    }
+
+    fn array_store(
+        &self,
+        mut frame: Frame,
+        element_types: &[PrimitiveType],
+    ) -> Result<Step, VerifyErrorKind> {
+        let value = of_field_type(&FieldType::Base(element_types[0]));
+        self.pop(&mut frame, &value)?;
+        let array = self.pop_array(&mut frame)?;
+        Self::check_element(&array, element_types)?;
+        Ok(Step::next(frame))
+    }
+
+    fn reference_array_load(&self, mut frame: Frame) -> Result<Step, VerifyErrorKind> {
+        let array = self.pop_array(&mut frame)?;
+        let element = match &array {
+            ObjectVariable(class) => match component_type(class) {
+                Some(it @ (FieldType::Object(_) | FieldType::Array(_))) => of_field_type(&it),
+                _ => return Err(VerifyErrorKind::NotArray(array)),
+            },
+            _ => NullVariable,
+        };
+        frame.push(element)?;
+        Ok(Step::next(frame))
+    }
+
+    fn reference_array_store(&self, mut frame: Frame) -> Result<Step, VerifyErrorKind> {
+        self.pop(&mut frame, &object(OBJECT))?;
+        let array = self.pop_array(&mut frame)?;
+        if let ObjectVariable(class) = &array {
+            if !matches!(
+                component_type(class),
+                Some(FieldType::Object(_) | FieldType::Array(_))
+            ) {
+                return Err(VerifyErrorKind::NotArray(array));
+            }
+        }
+        Ok(Step::next(frame))
+    }
+
+    fn conditional(
+        &self,
+        mut frame: Frame,
+        operand: &VerificationType,
+        operands: usize,
+        target: ProgramCounter,
+    ) -> Result<Step, VerifyErrorKind> {
+        self.pop_n(&mut frame, operand, operands)?;
+        Ok(Step::branch(frame, target))
+    }
+
+    fn reference_conditional(
+        mut frame: Frame,
+        operands: usize,
+        target: ProgramCounter,
+    ) -> Result<Step, VerifyErrorKind> {
+        for _ in 0..operands {
+            frame.pop_reference()?;
+        }
+        Ok(Step::branch(frame, target))
+    }
+
+    fn return_value(
+        &self,
+        mut frame: Frame,
+        ty: Option<&VerificationType>,
+    ) -> Result<Step, VerifyErrorKind> {
+        let expected = match &self.method.descriptor.return_type {
+            ReturnType::Void => None,
+            ReturnType::Some(it) => Some(of_field_type(it)),
+        };
+        match (ty, expected) {
+            (None, None) => {
+                if self.method.name == "<init>" && frame.this_uninitialized {
+                    return Err(VerifyErrorKind::ReturnBeforeInitialization);
+                }
+            }
+            (Some(ObjectVariable(_)), Some(expected @ ObjectVariable(_))) => {
+                self.pop(&mut frame, &expected)?;
+            }
+            (Some(ty), Some(expected)) if *ty == expected => {
+                self.pop(&mut frame, &expected)?;
+            }
+            _ => return Err(VerifyErrorKind::InvalidReturn),
+        }
+        Ok(Step::jump(frame, Vec::new()))
+    }
+
+    fn field_access(
+        &self,
+        mut frame: Frame,
+        field: &FieldRef,
+        is_static: bool,
+        is_put: bool,
+    ) -> Result<Step, VerifyErrorKind> {
+        let field_type = of_field_type(&field.field_type);
+        if is_put {
+            self.pop(&mut frame, &field_type)?;
+        }
+        if !is_static {
+            let receiver = frame.pop_reference()?;
+            // A constructor may assign the fields declared in its class before calling `super()`.
+            let is_early_assignment = is_put
+                && receiver == UninitializedThisVariable
+                && field.owner == self.class.as_ref();
+            let owner = ObjectVariable(field.owner.clone());
+            if !is_early_assignment && !is_assignable(self.hierarchy, &receiver, &owner) {
+                return Err(VerifyErrorKind::TypeMismatch {
+                    expected: owner,
+                    found: receiver,
+                });
+            }
+        }
+        if !is_put {
+            frame.push(field_type)?;
+        }
+        Ok(Step::next(frame))
+    }
+
+    fn pop_arguments(&self, frame: &mut Frame, method: &MethodRef) -> Result<(), VerifyErrorKind> {
+        for parameter in method.descriptor.parameters_types.iter().rev() {
+            self.pop(frame, &of_field_type(parameter))?;
+        }
+        Ok(())
+    }
+
+    fn push_return_value(frame: &mut Frame, method: &MethodRef) -> Result<(), VerifyErrorKind> {
+        match &method.descriptor.return_type {
+            ReturnType::Some(it) => frame.push(of_field_type(it)),
+            ReturnType::Void => Ok(()),
+        }
+    }
+
+    fn invoke(
+        &self,
+        mut frame: Frame,
+        method: &MethodRef,
+        has_receiver: bool,
+    ) -> Result<Step, VerifyErrorKind> {
+        self.pop_arguments(&mut frame, method)?;
+        if has_receiver {
+            self.pop(&mut frame, &ObjectVariable(method.owner.clone()))?;
+        }
+        Self::push_return_value(&mut frame, method)?;
+        Ok(Step::next(frame))
+    }
+
+    fn invoke_special(
+        &self,
+        mut frame: Frame,
+        method: &MethodRef,
+    ) -> Result<Step, VerifyErrorKind> {
+        if method.name != "<init>" {
+            self.pop_arguments(&mut frame, method)?;
+            self.pop(&mut frame, &ObjectVariable(self.class.as_ref()))?;
+            Self::push_return_value(&mut frame, method)?;
+            return Ok(Step::next(frame));
+        }
+        self.pop_arguments(&mut frame, method)?;
+        let receiver = frame.pop_reference()?;
+        let initialized = match &receiver {
+            UninitializedThisVariable => {
+                let this_class = self.class.as_ref();
+                let is_valid = method.owner == this_class
+                    || self.class.super_class.as_ref() == Some(&method.owner);
+                is_valid.then_some(this_class)
+            }
+            UninitializedVariable { offset } => match self.body.instruction_at(*offset) {
+                Some(Instruction::New(class)) if *class == method.owner => Some(class.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(initialized) = initialized else {
+            return Err(VerifyErrorKind::InvalidInitialization(receiver));
+        };
+        frame.initialize(&receiver, &initialized);
+        Ok(Step::next(frame))
+    }
+
+    fn constant(
+        frame: Frame,
+        constant: &ConstantValue,
+        is_wide: bool,
+    ) -> Result<Step, VerifyErrorKind> {
+        let ty = match constant {
+            ConstantValue::Integer(_) if !is_wide => IntegerVariable,
+            ConstantValue::Float(_) if !is_wide => FloatVariable,
+            ConstantValue::Long(_) if is_wide => LongVariable,
+            ConstantValue::Double(_) if is_wide => DoubleVariable,
+            ConstantValue::String(_) if !is_wide => object("java/lang/String"),
+            ConstantValue::Class(_) if !is_wide => object("java/lang/Class"),
+            ConstantValue::Handle(_) if !is_wide => object("java/lang/invoke/MethodHandle"),
+            ConstantValue::MethodType(_) if !is_wide => object("java/lang/invoke/MethodType"),
+            ConstantValue::Dynamic(_, _, field_type) => {
+                let ty = of_field_type(field_type);
+                if matches!(ty, LongVariable | DoubleVariable) != is_wide {
+                    return Err(VerifyErrorKind::IllegalInstruction);
+                }
+                ty
+            }
+            _ => return Err(VerifyErrorKind::IllegalInstruction),
+        };
+        Self::push(frame, ty)
+    }
+
+    fn push(mut frame: Frame, ty: VerificationType) -> Result<Step, VerifyErrorKind> {
+        frame.push(ty)?;
+        Ok(Step::next(frame))
+    }
+
+    fn new_array(
+        &self,
+        mut frame: Frame,
+        array_type: &FieldType,
+        dimensions: u8,
+    ) -> Result<Step, VerifyErrorKind> {
+        self.pop_n(&mut frame, &IntegerVariable, usize::from(dimensions))?;
+        frame.push(of_field_type(array_type))?;
+        Ok(Step::next(frame))
+    }
+
+    fn execute_wide(
+        &self,
+        frame: Frame,
+        instruction: &WideInstruction,
+    ) -> Result<Step, VerifyErrorKind> {
+        use WideInstruction as W;
+        match instruction {
+            W::ILoad(index) => Self::load(frame, *index, IntegerVariable),
+            W::LLoad(index) => Self::load(frame, *index, LongVariable),
+            W::FLoad(index) => Self::load(frame, *index, FloatVariable),
+            W::DLoad(index) => Self::load(frame, *index, DoubleVariable),
+            W::ALoad(index) => Self::load_reference(frame, *index),
+            W::IStore(index) => self.store(frame, *index, &IntegerVariable),
+            W::LStore(index) => self.store(frame, *index, &LongVariable),
+            W::FStore(index) => self.store(frame, *index, &FloatVariable),
+            W::DStore(index) => self.store(frame, *index, &DoubleVariable),
+            W::AStore(index) => Self::store_reference(frame, *index),
+            W::IInc(index, _) => Self::increment(frame, *index),
+            W::Ret(_) => Err(VerifyErrorKind::IllegalInstruction),
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn execute(
+        &self,
+        pc: ProgramCounter,
+        instruction: &Instruction,
+        mut frame: Frame,
+    ) -> Result<Step, VerifyErrorKind> {
+        #[allow(clippy::enum_glob_use)]
+        use Instruction::*;
+        use PrimitiveType::{Boolean, Byte, Char, Double, Float, Int, Long, Short};
+        let (int, long, float, double) = (
+            &IntegerVariable,
+            &LongVariable,
+            &FloatVariable,
+            &DoubleVariable,
+        );
+        match instruction {
+            Nop => Ok(Step::next(frame)),
+            AConstNull => Self::push(frame, NullVariable),
+            IConstM1 | IConst0 | IConst1 | IConst2 | IConst3 | IConst4 | IConst5 | BiPush(_)
+            | SiPush(_) => Self::push(frame, IntegerVariable),
+            LConst0 | LConst1 => Self::push(frame, LongVariable),
+            FConst0 | FConst1 | FConst2 => Self::push(frame, FloatVariable),
+            DConst0 | DConst1 => Self::push(frame, DoubleVariable),
+            Ldc(constant) | LdcW(constant) => Self::constant(frame, constant, false),
+            Ldc2W(constant) => Self::constant(frame, constant, true),
+
+            ILoad(index) => Self::load(frame, u16::from(*index), IntegerVariable),
+            LLoad(index) => Self::load(frame, u16::from(*index), LongVariable),
+            FLoad(index) => Self::load(frame, u16::from(*index), FloatVariable),
+            DLoad(index) => Self::load(frame, u16::from(*index), DoubleVariable),
+            ALoad(index) => Self::load_reference(frame, u16::from(*index)),
+            ILoad0 => Self::load(frame, 0, IntegerVariable),
+            ILoad1 => Self::load(frame, 1, IntegerVariable),
+            ILoad2 => Self::load(frame, 2, IntegerVariable),
+            ILoad3 => Self::load(frame, 3, IntegerVariable),
+            LLoad0 => Self::load(frame, 0, LongVariable),
+            LLoad1 => Self::load(frame, 1, LongVariable),
+            LLoad2 => Self::load(frame, 2, LongVariable),
+            LLoad3 => Self::load(frame, 3, LongVariable),
+            FLoad0 => Self::load(frame, 0, FloatVariable),
+            FLoad1 => Self::load(frame, 1, FloatVariable),
+            FLoad2 => Self::load(frame, 2, FloatVariable),
+            FLoad3 => Self::load(frame, 3, FloatVariable),
+            DLoad0 => Self::load(frame, 0, DoubleVariable),
+            DLoad1 => Self::load(frame, 1, DoubleVariable),
+            DLoad2 => Self::load(frame, 2, DoubleVariable),
+            DLoad3 => Self::load(frame, 3, DoubleVariable),
+            ALoad0 => Self::load_reference(frame, 0),
+            ALoad1 => Self::load_reference(frame, 1),
+            ALoad2 => Self::load_reference(frame, 2),
+            ALoad3 => Self::load_reference(frame, 3),
+            IALoad => self.array_load(frame, &[Int]),
+            LALoad => self.array_load(frame, &[Long]),
+            FALoad => self.array_load(frame, &[Float]),
+            DALoad => self.array_load(frame, &[Double]),
+            AALoad => self.reference_array_load(frame),
+            BALoad => self.array_load(frame, &[Byte, Boolean]),
+            CALoad => self.array_load(frame, &[Char]),
+            SALoad => self.array_load(frame, &[Short]),
+
+            IStore(index) => self.store(frame, u16::from(*index), int),
+            LStore(index) => self.store(frame, u16::from(*index), long),
+            FStore(index) => self.store(frame, u16::from(*index), float),
+            DStore(index) => self.store(frame, u16::from(*index), double),
+            AStore(index) => Self::store_reference(frame, u16::from(*index)),
+            IStore0 => self.store(frame, 0, int),
+            IStore1 => self.store(frame, 1, int),
+            IStore2 => self.store(frame, 2, int),
+            IStore3 => self.store(frame, 3, int),
+            LStore0 => self.store(frame, 0, long),
+            LStore1 => self.store(frame, 1, long),
+            LStore2 => self.store(frame, 2, long),
+            LStore3 => self.store(frame, 3, long),
+            FStore0 => self.store(frame, 0, float),
+            FStore1 => self.store(frame, 1, float),
+            FStore2 => self.store(frame, 2, float),
+            FStore3 => self.store(frame, 3, float),
+            DStore0 => self.store(frame, 0, double),
+            DStore1 => self.store(frame, 1, double),
+            DStore2 => self.store(frame, 2, double),
+            DStore3 => self.store(frame, 3, double),
+            AStore0 => Self::store_reference(frame, 0),
+            AStore1 => Self::store_reference(frame, 1),
+            AStore2 => Self::store_reference(frame, 2),
+            AStore3 => Self::store_reference(frame, 3),
+            IAStore => self.array_store(frame, &[Int]),
+            LAStore => self.array_store(frame, &[Long]),
+            FAStore => self.array_store(frame, &[Float]),
+            DAStore => self.array_store(frame, &[Double]),
+            AAStore => self.reference_array_store(frame),
+            BAStore => self.array_store(frame, &[Byte, Boolean]),
+            CAStore => self.array_store(frame, &[Char]),
+            SAStore => self.array_store(frame, &[Short]),
+
+            Pop => frame.pop_slots(1).map(|()| Step::next(frame)),
+            Pop2 => frame.pop_slots(2).map(|()| Step::next(frame)),
+            Dup => frame.dup(1, 1).map(|()| Step::next(frame)),
+            DupX1 => frame.dup(1, 2).map(|()| Step::next(frame)),
+            DupX2 => frame.dup(1, 3).map(|()| Step::next(frame)),
+            Dup2 => frame.dup(2, 2).map(|()| Step::next(frame)),
+            Dup2X1 => frame.dup(2, 3).map(|()| Step::next(frame)),
+            Dup2X2 => frame.dup(2, 4).map(|()| Step::next(frame)),
+            Swap => frame.swap().map(|()| Step::next(frame)),
+
+            IAdd | ISub | IMul | IDiv | IRem | IShl | IShr | IUShr | IAnd | IOr | IXor => {
+                self.operation(frame, int, 2, Some(IntegerVariable))
+            }
+            LAdd | LSub | LMul | LDiv | LRem | LAnd | LOr | LXor => {
+                self.operation(frame, long, 2, Some(LongVariable))
+            }
+            FAdd | FSub | FMul | FDiv | FRem => {
+                self.operation(frame, float, 2, Some(FloatVariable))
+            }
+            DAdd | DSub | DMul | DDiv | DRem => {
+                self.operation(frame, double, 2, Some(DoubleVariable))
+            }
+            INeg | I2B | I2C | I2S => self.operation(frame, int, 1, Some(IntegerVariable)),
+            LNeg => self.operation(frame, long, 1, Some(LongVariable)),
+            FNeg => self.operation(frame, float, 1, Some(FloatVariable)),
+            DNeg => self.operation(frame, double, 1, Some(DoubleVariable)),
+            LShl | LShr | LUShr => {
+                self.pop(&mut frame, int)?;
+                self.operation(frame, long, 1, Some(LongVariable))
+            }
+            IInc(index, _) => Self::increment(frame, u16::from(*index)),
+
+            I2L => self.operation(frame, int, 1, Some(LongVariable)),
+            I2F => self.operation(frame, int, 1, Some(FloatVariable)),
+            I2D => self.operation(frame, int, 1, Some(DoubleVariable)),
+            L2I => self.operation(frame, long, 1, Some(IntegerVariable)),
+            L2F => self.operation(frame, long, 1, Some(FloatVariable)),
+            L2D => self.operation(frame, long, 1, Some(DoubleVariable)),
+            F2I => self.operation(frame, float, 1, Some(IntegerVariable)),
+            F2L => self.operation(frame, float, 1, Some(LongVariable)),
+            F2D => self.operation(frame, float, 1, Some(DoubleVariable)),
+            D2I => self.operation(frame, double, 1, Some(IntegerVariable)),
+            D2L => self.operation(frame, double, 1, Some(LongVariable)),
+            D2F => self.operation(frame, double, 1, Some(FloatVariable)),
+
+            LCmp => self.operation(frame, long, 2, Some(IntegerVariable)),
+            FCmpL | FCmpG => self.operation(frame, float, 2, Some(IntegerVariable)),
+            DCmpL | DCmpG => self.operation(frame, double, 2, Some(IntegerVariable)),
+            IfEq(target) | IfNe(target) | IfLt(target) | IfGe(target) | IfGt(target)
+            | IfLe(target) => self.conditional(frame, int, 1, *target),
+            IfICmpEq(target) | IfICmpNe(target) | IfICmpLt(target) | IfICmpGe(target)
+            | IfICmpGt(target) | IfICmpLe(target) => self.conditional(frame, int, 2, *target),
+            IfACmpEq(target) | IfACmpNe(target) => Self::reference_conditional(frame, 2, *target),
+            IfNull(target) | IfNonNull(target) => Self::reference_conditional(frame, 1, *target),
+
+            Goto(target) | GotoW(target) => Ok(Step::jump(frame, vec![*target])),
+            TableSwitch {
+                jump_targets,
+                default,
+                ..
+            } => {
+                self.pop(&mut frame, int)?;
+                let mut targets = jump_targets.clone();
+                targets.push(*default);
+                Ok(Step::jump(frame, targets))
+            }
+            LookupSwitch {
+                default,
+                match_targets,
+            } => {
+                self.pop(&mut frame, int)?;
+                let mut targets: Vec<_> = match_targets.values().copied().collect();
+                targets.push(*default);
+                Ok(Step::jump(frame, targets))
+            }
+            IReturn => self.return_value(frame, Some(int)),
+            LReturn => self.return_value(frame, Some(long)),
+            FReturn => self.return_value(frame, Some(float)),
+            DReturn => self.return_value(frame, Some(double)),
+            AReturn => self.return_value(frame, Some(&object(OBJECT))),
+            Return => self.return_value(frame, None),
+
+            GetStatic(field) => self.field_access(frame, field, true, false),
+            PutStatic(field) => self.field_access(frame, field, true, true),
+            GetField(field) => self.field_access(frame, field, false, false),
+            PutField(field) => self.field_access(frame, field, false, true),
+            InvokeVirtual(method) | InvokeInterface(method, _) => self.invoke(frame, method, true),
+            InvokeStatic(method) => self.invoke(frame, method, false),
+            InvokeSpecial(method) => self.invoke_special(frame, method),
+            InvokeDynamic { descriptor, .. } => {
+                for parameter in descriptor.parameters_types.iter().rev() {
+                    self.pop(&mut frame, &of_field_type(parameter))?;
+                }
+                if let ReturnType::Some(it) = &descriptor.return_type {
+                    frame.push(of_field_type(it))?;
+                }
+                Ok(Step::next(frame))
+            }
+            New(_) => {
+                let uninitialized = UninitializedVariable { offset: pc };
+                if frame.stack.contains(&uninitialized) {
+                    return Err(VerifyErrorKind::InvalidInitialization(uninitialized));
+                }
+                for local in &mut frame.locals {
+                    if *local == uninitialized {
+                        *local = VerificationType::TopVariable;
+                    }
+                }
+                Self::push(frame, uninitialized)
+            }
+            NewArray(element_type) => {
+                self.new_array(frame, &FieldType::Base(*element_type).into_array_type(), 1)
+            }
+            ANewArray(element) => {
+                let element_type = if element.binary_name.starts_with('[') {
+                    element
+                        .binary_name
+                        .parse()
+                        .map_err(|_| VerifyErrorKind::IllegalInstruction)?
+                } else {
+                    FieldType::Object(element.clone())
+                };
+                self.new_array(frame, &element_type.into_array_type(), 1)
+            }
+            ArrayLength => {
+                let array = frame.pop_reference()?;
+                match &array {
+                    NullVariable => {}
+                    ObjectVariable(class) if component_type(class).is_some() => {}
+                    _ => return Err(VerifyErrorKind::NotArray(array)),
+                }
+                Self::push(frame, IntegerVariable)
+            }
+            AThrow => {
+                self.pop(&mut frame, &object(THROWABLE))?;
+                Ok(Step::jump(frame, Vec::new()))
+            }
+            CheckCast(target_type) => {
+                self.pop(&mut frame, &object(OBJECT))?;
+                Self::push(frame, of_field_type(target_type))
+            }
+            InstanceOf(_) => {
+                self.pop(&mut frame, &object(OBJECT))?;
+                Self::push(frame, IntegerVariable)
+            }
+            MonitorEnter | MonitorExit => {
+                self.pop(&mut frame, &object(OBJECT))?;
+                Ok(Step::next(frame))
+            }
+            Wide(instruction) => self.execute_wide(frame, instruction),
+            MultiANewArray(array_type, dimensions) => {
+                self.new_array(frame, array_type, *dimensions)
+            }
+            Jsr(_) | JsrW(_) | Ret(_) | Breakpoint | ImpDep1 | ImpDep2 => {
+                Err(VerifyErrorKind::IllegalInstruction)
+            }
+        }
+    }
+}
+
+/// Converts slots back to the stack map format, where `long` and `double` take one entry.
+fn compact(slots: &[VerificationType]) -> Vec<VerificationType> {
+    let mut types = Vec::new();
+    let mut slots = slots.iter();
+    while let Some(slot) = slots.next() {
+        if matches!(slot, LongVariable | DoubleVariable) {
+            slots.next();
+        }
+        types.push(slot.clone());
+    }
+    // Trailing `top`s are padding up to `max_locals`.
+    while types.last() == Some(&VerificationType::TopVariable) {
+        types.pop();
+    }
+    types
+}
@@ -0,0 +1,215 @@
+//! Verification of method bodies by type checking.
+//!
+//! The [`Verifier`] checks each instruction of a method against the types in its stack map table
+//! following the rules of the type-checking verifier in JVMS §4.10.1.
+//! Unlike the IR generator, which stops at the first malformed instruction, the verifier reports
+//! every error it finds together with its location.
+//! After an error, it resumes at the next instruction with a stack map frame.
+//!
+//! Subtyping between classes is answered by a [`HierarchyOracle`], which is implemented for
+//! [`ClassHierarchy`].
+//! Class files older than version 50 must be verified by type inference, which is not supported.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    jvm::{
+        class::Class,
+        code::{ProgramCounter, VerificationType},
+        method::Method,
+        references::{ClassRef, MethodRef},
+    },
+    macros::see_jvm_spec,
+};
+
+use super::class_hierarchy::ClassHierarchy;
+
+mod frame;
+mod instruction;
+
+/// The first major version of class files verified by type checking.
+const TYPE_CHECKING_MAJOR_VERSION: u16 = 50;
+
+/// Answers questions about the class hierarchy needed for checking assignability.
+pub trait HierarchyOracle {
+    /// Checks whether `class` is an interface.
+    fn is_interface(&self, class: &ClassRef) -> bool;
+
+    /// Checks whether `class` is `super_type` or one of its subtypes.
+    fn is_subtype_of(&self, class: &ClassRef, super_type: &ClassRef) -> bool;
+}
+
+impl HierarchyOracle for ClassHierarchy {
+    fn is_interface(&self, class: &ClassRef) -> bool {
+        ClassHierarchy::is_interface(self, class)
+    }
+
+    fn is_subtype_of(&self, class: &ClassRef, super_type: &ClassRef) -> bool {
+        ClassHierarchy::is_subtype_of(self, class, super_type)
+    }
+}
+
+/// The reason why an instruction fails verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VerifyErrorKind {
+    /// The class file must be verified by type inference.
+    #[error("Class files of version {0} are not verified by type checking")]
+    UnsupportedVersion(u16),
+    /// The instruction pops more values than the operand stack has.
+    #[error("The operand stack underflows")]
+    StackUnderflow,
+    /// The operand stack grows beyond `max_stack`.
+    #[error("The operand stack exceeds max_stack ({0})")]
+    StackOverflow(u16),
+    /// A local variable beyond `max_locals` is accessed.
+    #[error("The local variable {index} exceeds max_locals ({max_locals})")]
+    LocalOutOfBounds {
+        /// The index of the local variable.
+        index: u16,
+        /// The maximum number of local variables.
+        max_locals: u16,
+    },
+    /// A value is not assignable to the expected type.
+    #[error("Expected {expected:?}, found {found:?}")]
+    TypeMismatch {
+        /// The type expected by the instruction.
+        expected: VerificationType,
+        /// The type of the value.
+        found: VerificationType,
+    },
+    /// A value is not a reference.
+    #[error("Expected a reference, found {0:?}")]
+    NotReference(VerificationType),
+    /// A value is not an array.
+    #[error("Expected an array, found {0:?}")]
+    NotArray(VerificationType),
+    /// The instruction splits a `long` or `double` on the operand stack.
+    #[error("The instruction splits a category 2 value")]
+    SplitCategory2,
+    /// The instruction jumps to the middle of an instruction or outside the method.
+    #[error("Invalid branch target {0}")]
+    InvalidBranchTarget(ProgramCounter),
+    /// A branch target or an exception handler has no stack map frame.
+    #[error("No stack map frame at {0}")]
+    MissingStackMapFrame(ProgramCounter),
+    /// The current frame is not assignable to the stack map frame at a branch target.
+    #[error("The frame is not assignable to the stack map frame at {0}")]
+    IncompatibleStackMapFrame(ProgramCounter),
+    /// An instruction after an unconditional control transfer has no stack map frame.
+    #[error("The instruction is unreachable without a stack map frame")]
+    UnreachableWithoutFrame,
+    /// A stack map frame is malformed or does not start an instruction.
+    #[error("Invalid stack map frame: {0}")]
+    InvalidStackMapFrame(&'static str),
+    /// The execution falls off the end of the code.
+    #[error("The execution falls off the end of the code")]
+    FallOffEnd,
+    /// An object is used before it is initialized.
+    #[error("Invalid constructor call on {0:?}")]
+    InvalidInitialization(VerificationType),
+    /// A constructor returns before calling a constructor of its class or its superclass.
+    #[error("The constructor returns before `this` is initialized")]
+    ReturnBeforeInitialization,
+    /// The returned value does not match the return type of the method.
+    #[error("The return instruction does not match the return type")]
+    InvalidReturn,
+    /// The instruction is not allowed in methods verified by type checking.
+    #[error("The instruction is not allowed")]
+    IllegalInstruction,
+}
+
+/// An error found by the verifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    /// The location of the instruction or stack map frame.
+    pub pc: ProgramCounter,
+    /// The reason of the error.
+    pub kind: VerifyErrorKind,
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pc, self.kind)
+    }
+}
+
+/// The errors found in the methods of a class.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    errors: BTreeMap<MethodRef, Vec<VerifyError>>,
+}
+
+impl VerificationReport {
+    /// Checks whether all the methods pass verification.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the errors in a method.
+    #[must_use]
+    pub fn errors_in(&self, method: &MethodRef) -> &[VerifyError] {
+        self.errors.get(method).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the methods that fail verification and their errors.
+    pub fn iter(&self) -> impl Iterator<Item = (&MethodRef, &[VerifyError])> {
+        self.errors.iter().map(|(k, v)| (k, v.as_slice()))
+    }
+}
+
+/// A type-checking verifier for the methods of a class.
+#[doc = see_jvm_spec!(4, 10, 1)]
+#[derive(Debug)]
+pub struct Verifier<'a, H> {
+    class: &'a Class,
+    hierarchy: &'a H,
+}
+
+impl<'a, H: HierarchyOracle> Verifier<'a, H> {
+    /// Creates a verifier for the methods in `class`.
+    #[must_use]
+    pub fn new(class: &'a Class, hierarchy: &'a H) -> Self {
+        Self { class, hierarchy }
+    }
+
+    /// Verifies all the methods with bodies in the class.
+    #[must_use]
+    pub fn verify(&self) -> VerificationReport {
+        let errors = self
+            .class
+            .methods
+            .iter()
+            .map(|it| (it.as_ref(), self.verify_method(it)))
+            .filter(|(_, errors)| !errors.is_empty())
+            .collect();
+        VerificationReport { errors }
+    }
+
+    /// Verifies a method of the class and returns the errors sorted by their locations.
+    #[must_use]
+    pub fn verify_method(&self, method: &Method) -> Vec<VerifyError> {
+        let Some(body) = &method.body else {
+            return Vec::new();
+        };
+        let major_version = self.class.version.major();
+        if major_version < TYPE_CHECKING_MAJOR_VERSION {
+            return vec![VerifyError {
+                pc: ProgramCounter::ZERO,
+                kind: VerifyErrorKind::UnsupportedVersion(major_version),
+            }];
+        }
+        let mut errors = instruction::MethodVerifier {
+            class: self.class,
+            hierarchy: self.hierarchy,
+            method,
+            body,
+        }
+        .verify();
+        errors.sort_by_key(|it| it.pc);
+        errors
+    }
+}
@@ -55,6 +55,17 @@ impl Access {
             Self::ReadStatic { .. } => BTreeSet::default(),
         }
     }
+
+    /// Returns the field being accessed.
+    #[must_use]
+    pub const fn field(&self) -> &FieldRef {
+        match self {
+            Self::ReadStatic { field }
+            | Self::WriteStatic { field, .. }
+            | Self::ReadInstance { field, .. }
+            | Self::WriteInstance { field, .. } => field,
+        }
+    }
 }
 
 #[cfg(test)]
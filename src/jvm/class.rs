@@ -1,6 +1,6 @@
 //! JVM classes and interfaces
 
-use std::borrow::Borrow;
+use std::{borrow::Borrow, sync::Arc};
 
 use bitflags::bitflags;
 
@@ -15,6 +15,8 @@ use crate::{
 
 use super::{
     annotation::{Annotation, TypeAnnotation},
+    constant_pool::ConstantPool,
+    custom_attribute::CustomAttributes,
     field::{ConstantValue, Field},
     method::Method,
     module::Module,
@@ -81,6 +83,13 @@ pub struct Class {
     pub record: Option<Vec<RecordComponent>>,
     /// Unrecognized JVM attributes.
     pub free_attributes: Vec<(String, Vec<u8>)>,
+    /// Attributes decoded by custom [`AttributeDecoder`](crate::jvm::parsing::AttributeDecoder)s.
+    pub custom_attributes: CustomAttributes,
+    /// The constant pool of the class file.
+    /// It is only kept if
+    /// [`ParseOptions::with_retained_constant_pool`](crate::jvm::parsing::ParseOptions::with_retained_constant_pool)
+    /// is set.
+    pub constant_pool: Option<Arc<ConstantPool>>,
 }
 
 impl Class {
@@ -116,167 +125,154 @@ impl Class {
 }
 
 /// The maximum supported major version of a class file.
-pub const MAX_MAJOR_VERSION: u16 = 65;
+pub const MAX_MAJOR_VERSION: u16 = Version::JDK_27.major;
 
 /// The version of a class file.
-#[derive(Debug, PartialOrd, PartialEq, Eq, Copy, Clone)]
-#[non_exhaustive]
-pub enum Version {
-    /// JDK 1.1
-    Jdk1_1(u16),
+///
+/// Versions are ordered by their major versions first and then their minor versions.
+/// The versions of the Java SE releases are available as constants, e.g., [`Version::JDK_21`].
+#[doc = see_jvm_spec!(4, 1)]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Version {
+    major: u16,
+    minor: u16,
+}
+
+impl Version {
+    /// The minor version of the class files compiled with `--enable-preview`.
+    pub const PREVIEW_MINOR_VERSION: u16 = u16::MAX;
+
+    /// JDK 1.1, as emitted by `javac` of JDK 1.1.
+    pub const JDK_1_1: Self = Self::release(45).with_minor(3);
     /// JDK 1.2
-    Jdk1_2,
+    pub const JDK_1_2: Self = Self::release(46);
     /// JDK 1.3
-    Jdk1_3,
+    pub const JDK_1_3: Self = Self::release(47);
     /// JDK 1.4
-    Jdk1_4,
+    pub const JDK_1_4: Self = Self::release(48);
     /// JDK 5
-    Jdk5,
+    pub const JDK_5: Self = Self::release(49);
     /// JDK 6
-    Jdk6,
+    pub const JDK_6: Self = Self::release(50);
     /// JDK 7
-    Jdk7,
+    pub const JDK_7: Self = Self::release(51);
     /// JDK 8
-    Jdk8,
+    pub const JDK_8: Self = Self::release(52);
     /// JDK 9
-    Jdk9,
+    pub const JDK_9: Self = Self::release(53);
     /// JDK 10
-    Jdk10,
+    pub const JDK_10: Self = Self::release(54);
     /// JDK 11
-    Jdk11,
+    pub const JDK_11: Self = Self::release(55);
     /// JDK 12
-    Jdk12(bool),
+    pub const JDK_12: Self = Self::release(56);
     /// JDK 13
-    Jdk13(bool),
+    pub const JDK_13: Self = Self::release(57);
     /// JDK 14
-    Jdk14(bool),
+    pub const JDK_14: Self = Self::release(58);
     /// JDK 15
-    Jdk15(bool),
+    pub const JDK_15: Self = Self::release(59);
     /// JDK 16
-    Jdk16(bool),
+    pub const JDK_16: Self = Self::release(60);
     /// JDK 17
-    Jdk17(bool),
+    pub const JDK_17: Self = Self::release(61);
     /// JDK 18
-    Jdk18(bool),
+    pub const JDK_18: Self = Self::release(62);
     /// JDK 19
-    Jdk19(bool),
+    pub const JDK_19: Self = Self::release(63);
     /// JDK 20
-    Jdk20(bool),
+    pub const JDK_20: Self = Self::release(64);
     /// JDK 21
-    Jdk21(bool),
-}
-impl Version {
+    pub const JDK_21: Self = Self::release(65);
+    /// JDK 22
+    pub const JDK_22: Self = Self::release(66);
+    /// JDK 23
+    pub const JDK_23: Self = Self::release(67);
+    /// JDK 24
+    pub const JDK_24: Self = Self::release(68);
+    /// JDK 25
+    pub const JDK_25: Self = Self::release(69);
+    /// JDK 26
+    pub const JDK_26: Self = Self::release(70);
+    /// JDK 27
+    pub const JDK_27: Self = Self::release(71);
+
+    const fn release(major: u16) -> Self {
+        Self { major, minor: 0 }
+    }
+
+    const fn with_minor(self, minor: u16) -> Self {
+        Self {
+            major: self.major,
+            minor,
+        }
+    }
+
+    /// Creates a version if it is well-formed according to the JVM specification, including the
+    /// versions newer than [`MAX_MAJOR_VERSION`].
+    /// Returns `None` if the major version is lower than `45`, or if the minor version is neither
+    /// `0` nor [`Self::PREVIEW_MINOR_VERSION`] for JDK 1.2 and later.
+    #[must_use]
+    pub const fn new(major: u16, minor: u16) -> Option<Self> {
+        let is_valid = match major {
+            0..=44 => false,
+            45 => true,
+            46..=55 => minor == 0,
+            _ => minor == 0 || minor == Self::PREVIEW_MINOR_VERSION,
+        };
+        if is_valid {
+            Some(Self { major, minor })
+        } else {
+            None
+        }
+    }
+
     pub(crate) const fn from_versions(major: u16, minor: u16) -> Result<Self, Error> {
-        match (major, minor) {
-            (45, minor) => Ok(Self::Jdk1_1(minor)),
-            (46, 0x0000) => Ok(Self::Jdk1_2),
-            (47, 0x0000) => Ok(Self::Jdk1_3),
-            (48, 0x0000) => Ok(Self::Jdk1_4),
-            (49, 0x0000) => Ok(Self::Jdk5),
-            (50, 0x0000) => Ok(Self::Jdk6),
-            (51, 0x0000) => Ok(Self::Jdk7),
-            (52, 0x0000) => Ok(Self::Jdk8),
-            (53, 0x0000) => Ok(Self::Jdk9),
-            (54, 0x0000) => Ok(Self::Jdk10),
-            (55, 0x0000) => Ok(Self::Jdk11),
-            (56, 0x0000) => Ok(Self::Jdk12(false)),
-            (56, 0xFFFF) => Ok(Self::Jdk12(true)),
-            (57, 0x0000) => Ok(Self::Jdk13(false)),
-            (57, 0xFFFF) => Ok(Self::Jdk13(true)),
-            (58, 0x0000) => Ok(Self::Jdk14(false)),
-            (58, 0xFFFF) => Ok(Self::Jdk14(true)),
-            (59, 0x0000) => Ok(Self::Jdk15(false)),
-            (59, 0xFFFF) => Ok(Self::Jdk15(true)),
-            (60, 0x0000) => Ok(Self::Jdk16(false)),
-            (60, 0xFFFF) => Ok(Self::Jdk16(true)),
-            (61, 0x0000) => Ok(Self::Jdk17(false)),
-            (61, 0xFFFF) => Ok(Self::Jdk17(true)),
-            (62, 0x0000) => Ok(Self::Jdk18(false)),
-            (62, 0xFFFF) => Ok(Self::Jdk18(true)),
-            (63, 0x0000) => Ok(Self::Jdk19(false)),
-            (63, 0xFFFF) => Ok(Self::Jdk19(true)),
-            (64, 0x0000) => Ok(Self::Jdk20(false)),
-            (64, 0xFFFF) => Ok(Self::Jdk20(true)),
-            (65, 0x0000) => Ok(Self::Jdk21(false)),
-            (65, 0xFFFF) => Ok(Self::Jdk21(true)),
-            (major, _) if major > MAX_MAJOR_VERSION => {
-                Err(Error::Other("Unsupportted class version"))
-            }
-            _ => Err(Error::Other("Invalid class version")),
+        match Self::new(major, minor) {
+            Some(version) if version.major <= MAX_MAJOR_VERSION => Ok(version),
+            Some(_) => Err(Error::Other("Unsupported class version")),
+            None => Err(Error::Other("Invalid class version")),
         }
     }
 
+    /// Returns the version of the class files compiled with `--enable-preview` on the same
+    /// release, or `None` if the release does not support preview features (i.e., before JDK 12).
+    #[must_use]
+    pub const fn preview(self) -> Option<Self> {
+        Self::new(self.major, Self::PREVIEW_MINOR_VERSION)
+    }
+
     /// Returns `true` if this class file is compiled with `--enable-preview`.
     #[must_use]
     pub const fn is_preview_enabled(&self) -> bool {
-        matches!(
-            self,
-            Self::Jdk12(true)
-                | Self::Jdk13(true)
-                | Self::Jdk14(true)
-                | Self::Jdk15(true)
-                | Self::Jdk16(true)
-                | Self::Jdk17(true)
-                | Self::Jdk18(true)
-                | Self::Jdk19(true)
-                | Self::Jdk20(true)
-                | Self::Jdk21(true)
-        )
+        self.major >= Self::JDK_12.major && self.minor == Self::PREVIEW_MINOR_VERSION
     }
 
     /// Returns the major version of the class file.
     #[must_use]
     pub const fn major(&self) -> u16 {
-        match self {
-            Self::Jdk1_1(_) => 45,
-            Self::Jdk1_2 => 46,
-            Self::Jdk1_3 => 47,
-            Self::Jdk1_4 => 48,
-            Self::Jdk5 => 49,
-            Self::Jdk6 => 50,
-            Self::Jdk7 => 51,
-            Self::Jdk8 => 52,
-            Self::Jdk9 => 53,
-            Self::Jdk10 => 54,
-            Self::Jdk11 => 55,
-            Self::Jdk12(_) => 56,
-            Self::Jdk13(_) => 57,
-            Self::Jdk14(_) => 58,
-            Self::Jdk15(_) => 59,
-            Self::Jdk16(_) => 60,
-            Self::Jdk17(_) => 61,
-            Self::Jdk18(_) => 62,
-            Self::Jdk19(_) => 63,
-            Self::Jdk20(_) => 64,
-            Self::Jdk21(_) => 65,
-        }
+        self.major
     }
 
     /// Returns the minor version of the class file.
     #[must_use]
     pub const fn minor(&self) -> u16 {
-        #[allow(clippy::enum_glob_use)]
-        use Version::*;
-        match self {
-            Jdk1_1(minor) => *minor,
-            Jdk1_2 | Jdk1_3 | Jdk1_4 | Jdk5 | Jdk6 | Jdk7 | Jdk8 | Jdk9 | Jdk10 | Jdk11 => 0,
-            Jdk12(enable_preview)
-            | Jdk13(enable_preview)
-            | Jdk14(enable_preview)
-            | Jdk15(enable_preview)
-            | Jdk16(enable_preview)
-            | Jdk17(enable_preview)
-            | Jdk18(enable_preview)
-            | Jdk19(enable_preview)
-            | Jdk20(enable_preview)
-            | Jdk21(enable_preview) => {
-                if *enable_preview {
-                    u16::MAX
-                } else {
-                    0
-                }
-            }
-        }
+        self.minor
+    }
+
+    /// Returns the number of the Java SE release that introduces the class file version,
+    /// e.g., `8` for JDK 8 and `2` for JDK 1.2.
+    #[must_use]
+    pub const fn java_release(&self) -> u16 {
+        self.major - 44
+    }
+
+    /// Returns `true` if every method of the class is implicitly `strictfp`, i.e., the
+    /// `ACC_STRICT` flag has no effect and may be combined with `ACC_ABSTRACT`.
+    /// This is the case since JDK 17 (JEP 306).
+    #[must_use]
+    pub const fn is_strict_implied(&self) -> bool {
+        self.major >= Self::JDK_17.major
     }
 }
 
@@ -311,6 +307,25 @@ pub struct BootstrapMethod {
     pub arguments: Vec<ConstantValue>,
 }
 
+impl BootstrapMethod {
+    /// Returns the implementation method of a lambda expression or method reference if this
+    /// bootstrap method is `LambdaMetafactory.metafactory` or `LambdaMetafactory.altMetafactory`.
+    #[must_use]
+    pub fn lambda_implementation(&self) -> Option<&MethodHandle> {
+        let is_lambda_metafactory = matches!(
+            &self.method,
+            MethodHandle::RefInvokeStatic(it)
+                if it.owner.binary_name == "java/lang/invoke/LambdaMetafactory"
+                    && matches!(it.name.as_str(), "metafactory" | "altMetafactory")
+        );
+        // The second static argument of the metafactory is the implementation method.
+        match self.arguments.get(1) {
+            Some(ConstantValue::Handle(handle)) if is_lambda_metafactory => Some(handle),
+            _ => None,
+        }
+    }
+}
+
 /// A method handle.
 #[doc = see_jvm_spec!(4, 4, 8)]
 #[derive(Debug, PartialEq, Clone)]
@@ -354,6 +369,8 @@ pub struct RecordComponent {
     pub runtime_invisible_type_annotations: Vec<TypeAnnotation>,
     /// Unrecognized JVM attributes.
     pub free_attributes: Vec<(String, Vec<u8>)>,
+    /// Attributes decoded by custom [`AttributeDecoder`](crate::jvm::parsing::AttributeDecoder)s.
+    pub custom_attributes: CustomAttributes,
 }
 
 bitflags! {
@@ -471,6 +488,38 @@ mod tests {
         fn invalid_class_version(major in 46..=MAX_MAJOR_VERSION, minor in 1..=u16::MAX) {
             assert!(Version::from_versions(major, minor).is_err());
         }
+
+        #[test]
+        fn future_class_versions(
+            major in (MAX_MAJOR_VERSION+1)..=u16::MAX,
+            minor in prop_oneof![Just(0u16), Just(u16::MAX)]
+        ) {
+            let class_version = Version::new(major, minor).unwrap();
+            assert_eq!(major, class_version.major());
+            assert_eq!(class_version.is_preview_enabled(), minor == u16::MAX);
+            assert!(class_version > Version::from_versions(MAX_MAJOR_VERSION, 0).unwrap());
+        }
+    }
+
+    #[test]
+    fn named_versions() {
+        assert_eq!(Version::JDK_1_1.java_release(), 1);
+        assert_eq!(Version::JDK_1_2.java_release(), 2);
+        assert_eq!(Version::JDK_8.major(), 52);
+        assert_eq!(Version::JDK_8.java_release(), 8);
+        assert_eq!(Version::JDK_21.major(), 65);
+        assert_eq!(Version::JDK_25.java_release(), 25);
+        assert_eq!(Version::JDK_27.major(), 71);
+        assert_eq!(Version::JDK_27.java_release(), 27);
+        assert!(Version::JDK_11 < Version::JDK_17);
+
+        assert_eq!(Version::JDK_11.preview(), None);
+        let preview = Version::JDK_21.preview().unwrap();
+        assert!(preview.is_preview_enabled());
+        assert!(preview > Version::JDK_21);
+
+        assert!(!Version::JDK_16.is_strict_implied());
+        assert!(Version::JDK_17.is_strict_implied());
     }
 
     fn arb_access_flag() -> impl Strategy<Value = AccessFlags> {
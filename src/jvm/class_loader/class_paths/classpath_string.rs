@@ -0,0 +1,147 @@
+use std::{
+    convert::Infallible,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use super::{
+    super::{ClassPath, Error},
+    DirectoryClassPath,
+};
+
+/// An entry in a [`ClasspathString`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClasspathEntry {
+    /// A directory containing class files, e.g., `build/classes/`.
+    Directory(PathBuf),
+    /// A JAR or ZIP file, e.g., `lib/a.jar`.
+    Archive(PathBuf),
+    /// All the JAR files in a directory, e.g., `lib/*`.
+    Wildcard(PathBuf),
+}
+
+impl ClasspathEntry {
+    fn parse(entry: PathBuf) -> Self {
+        if entry.file_name() == Some(OsStr::new("*")) {
+            let directory = entry.parent().map(Path::to_path_buf).unwrap_or_default();
+            Self::Wildcard(directory)
+        } else if is_archive(&entry) {
+            Self::Archive(entry)
+        } else {
+            Self::Directory(entry)
+        }
+    }
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jar") || ext.eq_ignore_ascii_case("zip"))
+}
+
+/// A class path in the format of the `-classpath` option of `java`, e.g., `a.jar:b/:lib/*`.
+///
+/// The entries are separated by the platform-specific separator, i.e., `:` on Unix and `;` on
+/// Windows.
+/// Entries ending with `.jar` or `.zip` are archives, entries whose last component is `*` are
+/// expanded to the JAR files in the directory, and the others are directories.
+/// Empty entries are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClasspathString {
+    entries: Vec<ClasspathEntry>,
+}
+
+impl ClasspathString {
+    /// Returns the entries in the order they are searched.
+    #[must_use]
+    pub fn entries(&self) -> &[ClasspathEntry] {
+        &self.entries
+    }
+
+    /// Creates the class paths for the entries.
+    /// Wildcards are expanded to the JAR files in the directory, sorted by name.
+    ///
+    /// # Errors
+    /// - [`Error::IO`] if the directory of a wildcard cannot be read.
+    /// - [`Error::Unsupported`] if there are archives but the `jar` feature is not enabled.
+    pub fn class_paths(&self) -> Result<Vec<Box<dyn ClassPath + Send + Sync>>, Error> {
+        let mut class_paths: Vec<Box<dyn ClassPath + Send + Sync>> = Vec::new();
+        for entry in &self.entries {
+            match entry {
+                ClasspathEntry::Directory(directory) => {
+                    class_paths.push(Box::new(DirectoryClassPath::new(directory)));
+                }
+                ClasspathEntry::Archive(archive) => class_paths.push(archive_class_path(archive)?),
+                ClasspathEntry::Wildcard(directory) => {
+                    let directory = if directory.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        directory.as_path()
+                    };
+                    let mut archives = Vec::new();
+                    for dir_entry in fs::read_dir(directory)? {
+                        let path = dir_entry?.path();
+                        let is_jar = path
+                            .extension()
+                            .and_then(OsStr::to_str)
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"));
+                        if is_jar && path.is_file() {
+                            archives.push(path);
+                        }
+                    }
+                    archives.sort_unstable();
+                    for archive in archives {
+                        class_paths.push(archive_class_path(&archive)?);
+                    }
+                }
+            }
+        }
+        Ok(class_paths)
+    }
+}
+
+#[cfg(feature = "jar")]
+#[allow(clippy::unnecessary_wraps)]
+fn archive_class_path(archive: &Path) -> Result<Box<dyn ClassPath + Send + Sync>, Error> {
+    Ok(Box::new(super::JarClassPath::new(archive)))
+}
+
+#[cfg(not(feature = "jar"))]
+fn archive_class_path(_archive: &Path) -> Result<Box<dyn ClassPath + Send + Sync>, Error> {
+    Err(Error::Unsupported)
+}
+
+impl FromStr for ClasspathString {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let entries = std::env::split_paths(s)
+            .filter(|it| !it.as_os_str().is_empty())
+            .map(ClasspathEntry::parse)
+            .collect();
+        Ok(Self { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_classpath_string() {
+        let classpath: ClasspathString = "a.jar:b/::lib/*:c.ZIP:*".parse().unwrap();
+        assert_eq!(
+            classpath.entries(),
+            [
+                ClasspathEntry::Archive("a.jar".into()),
+                ClasspathEntry::Directory("b/".into()),
+                ClasspathEntry::Wildcard("lib".into()),
+                ClasspathEntry::Archive("c.ZIP".into()),
+                ClasspathEntry::Wildcard(PathBuf::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use zip::{result::ZipError, ZipArchive};
+
+use crate::jvm::{
+    class::Class,
+    parsing::{ParseOptions, ParseWarning},
+};
+
+use super::{
+    super::{ClassPath, Error},
+    parse_class, Manifest,
+};
+
+/// The first Java release that supports multi-release JAR files.
+const FIRST_VERSIONED_RELEASE: u16 = 9;
+
+fn zip_error(error: ZipError) -> Error {
+    match error {
+        ZipError::FileNotFound => Error::NotFound,
+        ZipError::Io(io_err) => Error::IO(io_err),
+        e => Error::Other(Box::new(e)),
+    }
+}
+
+fn open_zip<R: Read + Seek>(reader: R) -> Result<ZipArchive<R>, Error> {
+    ZipArchive::new(reader).map_err(|e| match e {
+        ZipError::FileNotFound => Error::Other(Box::new(e)),
+        e => zip_error(e),
+    })
+}
+
+fn read_class<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    entry: &str,
+    options: &ParseOptions,
+) -> Result<(Class, Vec<ParseWarning>), Error> {
+    let class_file = archive.by_name(entry).map_err(zip_error)?;
+    parse_class(class_file, options)
+}
+
+fn read_manifest<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Option<Manifest>, Error> {
+    let mut manifest_file = match archive.by_name(Manifest::PATH) {
+        Ok(it) => it,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(zip_error(e)),
+    };
+    let mut content = String::new();
+    manifest_file.read_to_string(&mut content)?;
+    content
+        .parse()
+        .map(Some)
+        .map_err(|e| Error::Other(Box::new(e)))
+}
+
+/// The content of an archive shared among threads.
+#[derive(Debug, Clone)]
+struct SharedBytes(Arc<[u8]>);
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A zip archive read into memory with its entries indexed by name.
+///
+/// Cloning the archive is cheap since the content and the index are shared, so each lookup
+/// works on its own clone without locking.
+#[derive(Debug, Clone)]
+pub(super) struct IndexedArchive {
+    archive: ZipArchive<Cursor<SharedBytes>>,
+    manifest: Option<Manifest>,
+}
+
+impl IndexedArchive {
+    /// Reads an archive, which may be preceded by a header of `header.len()` bytes.
+    pub(super) fn open(path: &Path, header: &[u8]) -> Result<Self, Error> {
+        let content = fs::read(path)?;
+        if !content.starts_with(header) {
+            return Err(Error::Other("Unexpected file header".into()));
+        }
+        Self::from_bytes(content)
+    }
+
+    /// Reads an archive from its content, e.g., an archive nested in another one.
+    pub(super) fn from_bytes(content: Vec<u8>) -> Result<Self, Error> {
+        let mut archive = open_zip(Cursor::new(SharedBytes(content.into())))?;
+        let manifest = read_manifest(&mut archive)?;
+        Ok(Self { archive, manifest })
+    }
+
+    /// Returns the archive opened at `cell`, or opens it if it is not yet.
+    pub(super) fn get_or_open<'c>(
+        cell: &'c OnceLock<Self>,
+        path: &Path,
+        header: &[u8],
+    ) -> Result<&'c Self, Error> {
+        if let Some(archive) = cell.get() {
+            return Ok(archive);
+        }
+        let archive = Self::open(path, header)?;
+        // Another thread may have opened the archive in the meantime, in which case it is kept.
+        Ok(cell.get_or_init(|| archive))
+    }
+
+    /// Returns the names of the entries in the archive.
+    pub(super) fn entry_names(&self) -> impl Iterator<Item = &str> {
+        self.archive.file_names()
+    }
+
+    /// Reads the content of an entry.
+    pub(super) fn read_entry(&self, entry: &str) -> Result<Vec<u8>, Error> {
+        let mut archive = self.archive.clone();
+        let mut file = archive.by_name(entry).map_err(zip_error)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        Ok(content)
+    }
+
+    fn read_class(
+        &self,
+        entry: &str,
+        options: &ParseOptions,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        read_class(&mut self.archive.clone(), entry, options)
+    }
+
+    /// Lists the classes in `directory` (e.g., `BOOT-INF/classes/`, or empty for the root),
+    /// including the versioned classes up to `release` in multi-release JAR files.
+    pub(super) fn class_names(&self, directory: &str, release: Option<u16>) -> BTreeSet<String> {
+        let is_multi_release = self
+            .manifest
+            .as_ref()
+            .is_some_and(Manifest::is_multi_release);
+        let release = release.filter(|_| is_multi_release);
+        self.entry_names()
+            .filter_map(|entry| entry.strip_prefix(directory)?.strip_suffix(".class"))
+            .filter_map(|name| match name.strip_prefix("META-INF/versions/") {
+                Some(versioned) => {
+                    let (version, name) = versioned.split_once('/')?;
+                    let version: u16 = version.parse().ok()?;
+                    (version >= FIRST_VERSIONED_RELEASE && release? >= version).then_some(name)
+                }
+                // Other files in `META-INF/` are not classes.
+                None if name.starts_with("META-INF/") => None,
+                None => Some(name),
+            })
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Finds a class in `directory` (e.g., `BOOT-INF/classes/`, or empty for the root).
+    /// If `release` is set and the archive is a multi-release JAR file, the versioned classes
+    /// for that release take precedence.
+    pub(super) fn find_class(
+        &self,
+        directory: &str,
+        binary_name: &str,
+        release: Option<u16>,
+        options: &ParseOptions,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        let is_multi_release = self
+            .manifest
+            .as_ref()
+            .is_some_and(Manifest::is_multi_release);
+        if let Some(release) = release.filter(|_| is_multi_release) {
+            for version in (FIRST_VERSIONED_RELEASE..=release).rev() {
+                let entry = format!("{directory}META-INF/versions/{version}/{binary_name}.class");
+                match self.read_class(&entry, options) {
+                    Err(Error::NotFound) => {}
+                    result => return result,
+                }
+            }
+        }
+        self.read_class(&format!("{directory}{binary_name}.class"), options)
+    }
+}
+
+/// A class path that searches for classes in a JAR file.
+///
+/// For multi-release JAR files, the classes in `META-INF/versions/{N}/` take precedence over the
+/// ones at the root when a target release not lower than `N` is set with
+/// [`JarClassPath::with_release`].
+///
+/// The JAR file is read and indexed when the first class is looked up, and the index is shared by
+/// all the subsequent lookups, which can run concurrently.
+/// The `jar_class_path` benchmark compares this with reopening the JAR file for each lookup.
+#[derive(Debug)]
+pub struct JarClassPath {
+    jar_file: PathBuf,
+    release: Option<u16>,
+    options: ParseOptions,
+    archive: OnceLock<IndexedArchive>,
+}
+
+impl JarClassPath {
+    /// Create a new JAR class path.
+    pub fn new(jar_file: impl Into<PathBuf>) -> Self {
+        Self {
+            jar_file: jar_file.into(),
+            release: None,
+            options: ParseOptions::default(),
+            archive: OnceLock::new(),
+        }
+    }
+
+    /// Sets the target Java release (e.g., `17`) for looking up classes in multi-release JAR files.
+    /// Without a target release, only the classes at the root of the JAR file are found.
+    #[must_use]
+    pub fn with_release(mut self, release: u16) -> Self {
+        self.release = Some(release);
+        self
+    }
+
+    /// Sets the options for parsing the classes found in the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn archive(&self) -> Result<&IndexedArchive, Error> {
+        IndexedArchive::get_or_open(&self.archive, &self.jar_file, &[])
+    }
+
+    /// Reads the manifest of the JAR file.
+    /// Returns `None` if the JAR file does not have a manifest.
+    ///
+    /// # Errors
+    /// See [`Error`].
+    pub fn manifest(&self) -> Result<Option<Manifest>, Error> {
+        self.archive().map(|it| it.manifest.clone())
+    }
+}
+
+impl ClassPath for JarClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        self.archive()?
+            .find_class("", binary_name, self.release, &self.options)
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        Ok(self.archive()?.class_names("", self.release))
+    }
+}
+
+/// A class path that searches for classes in a JMOD file (e.g., `$JAVA_HOME/jmods/java.base.jmod`).
+///
+/// A JMOD file consists of a 4-byte header followed by a zip archive, where the class files are
+/// stored in the `classes/` directory.
+#[derive(Debug)]
+pub struct JmodClassPath {
+    jmod_file: PathBuf,
+    options: ParseOptions,
+    archive: OnceLock<IndexedArchive>,
+}
+
+impl JmodClassPath {
+    /// The magic number at the beginning of a JMOD file, i.e., `JM` followed by the version 1.0.
+    const MAGIC: [u8; 4] = [b'J', b'M', 0x01, 0x00];
+
+    /// Create a new JMOD class path.
+    pub fn new(jmod_file: impl Into<PathBuf>) -> Self {
+        Self {
+            jmod_file: jmod_file.into(),
+            options: ParseOptions::default(),
+            archive: OnceLock::new(),
+        }
+    }
+
+    /// Sets the options for parsing the classes found in the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl ClassPath for JmodClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        IndexedArchive::get_or_open(&self.archive, &self.jmod_file, &Self::MAGIC)?
+            .read_class(&format!("classes/{binary_name}.class"), &self.options)
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        Ok(
+            IndexedArchive::get_or_open(&self.archive, &self.jmod_file, &Self::MAGIC)?
+                .class_names("classes/", None),
+        )
+    }
+}
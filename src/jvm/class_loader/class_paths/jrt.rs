@@ -0,0 +1,647 @@
+//! Reading classes from the Java runtime image (`$JAVA_HOME/lib/modules`).
+//!
+//! The runtime image uses the `jimage` format.
+//! It starts with a header followed by an index consisting of a redirect table, an offset table,
+//! the attributes of the locations, and a string table.
+//! The content of the resources follows the index.
+//! All the integers in the header and the index are in the native byte order of the platform
+//! that created the image, which is detected from the magic number.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::jvm::{
+    class::Class,
+    parsing::{ParseOptions, ParseWarning},
+};
+
+use super::{
+    super::{ClassPath, Error},
+    parse_class,
+};
+
+const IMAGE_MAGIC: u32 = 0xCAFE_DADA;
+const MAJOR_VERSION: u16 = 1;
+const HEADER_SIZE: usize = 7 * 4;
+const HASH_MULTIPLIER: u32 = 0x0100_0193;
+
+const ATTRIBUTE_END: u8 = 0;
+const ATTRIBUTE_MODULE: u8 = 1;
+const ATTRIBUTE_PARENT: u8 = 2;
+const ATTRIBUTE_BASE: u8 = 3;
+const ATTRIBUTE_EXTENSION: u8 = 4;
+const ATTRIBUTE_OFFSET: u8 = 5;
+const ATTRIBUTE_COMPRESSED: u8 = 6;
+const ATTRIBUTE_UNCOMPRESSED: u8 = 7;
+
+const COMPRESSED_MAGIC: u32 = 0xCAFE_FAFA;
+const COMPRESSED_HEADER_SIZE: usize = 29;
+
+fn malformed(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// The byte order of a runtime image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    fn i32(self, bytes: &[u8]) -> i32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            Self::Little => i32::from_le_bytes(bytes),
+            Self::Big => i32::from_be_bytes(bytes),
+        }
+    }
+
+    fn u64(self, bytes: &[u8]) -> u64 {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(&bytes[..8]);
+        match self {
+            Self::Little => u64::from_le_bytes(buf),
+            Self::Big => u64::from_be_bytes(buf),
+        }
+    }
+}
+
+/// Computes the hash of a name used for looking up the redirect table.
+fn hash_code(name: &[u8], seed: u32) -> u32 {
+    name.iter().fold(seed, |hash, byte| {
+        hash.wrapping_mul(HASH_MULTIPLIER) ^ u32::from(*byte)
+    }) & 0x7FFF_FFFF
+}
+
+/// The attributes of a resource in the image.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImageLocation {
+    module: u32,
+    parent: u32,
+    base: u32,
+    extension: u32,
+    offset: u64,
+    compressed_size: u64,
+    uncompressed_size: u64,
+}
+
+/// The index of a runtime image.
+#[derive(Debug)]
+struct ImageIndex {
+    byte_order: ByteOrder,
+    redirect: Vec<i32>,
+    offsets: Vec<u32>,
+    locations: Vec<u8>,
+    strings: Vec<u8>,
+    resources_start: u64,
+}
+
+impl ImageIndex {
+    /// Reads the index from the start of an image of `image_size` bytes.
+    fn read(reader: &mut impl Read, image_size: u64) -> io::Result<Self> {
+        let mut header = [0; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        let byte_order = if u32::from_le_bytes([header[0], header[1], header[2], header[3]])
+            == IMAGE_MAGIC
+        {
+            ByteOrder::Little
+        } else if u32::from_be_bytes([header[0], header[1], header[2], header[3]]) == IMAGE_MAGIC {
+            ByteOrder::Big
+        } else {
+            return Err(malformed("Not a jimage file"));
+        };
+        let field = |index: usize| byte_order.u32(&header[index * 4..]);
+        let [version, _flags, _resource_count, table_length, locations_size, strings_size] =
+            [1, 2, 3, 4, 5, 6].map(field);
+        if version >> 16 != u32::from(MAJOR_VERSION) {
+            return Err(malformed("Unsupported jimage version"));
+        }
+        let table_size = u64::from(table_length) * 4;
+        let resources_start = HEADER_SIZE as u64
+            + 2 * table_size
+            + u64::from(locations_size)
+            + u64::from(strings_size);
+        // The sizes are checked before allocating the buffers for the tables.
+        if resources_start > image_size {
+            return Err(malformed("The index exceeds the image"));
+        }
+        let read_bytes = |reader: &mut dyn Read, size: u64| -> io::Result<Vec<u8>> {
+            let size = usize::try_from(size).map_err(|_| malformed("The index is too large"))?;
+            let mut bytes = vec![0; size];
+            reader.read_exact(&mut bytes)?;
+            Ok(bytes)
+        };
+        let redirect = read_bytes(reader, table_size)?
+            .chunks_exact(4)
+            .map(|it| byte_order.i32(it))
+            .collect();
+        let offsets = read_bytes(reader, table_size)?
+            .chunks_exact(4)
+            .map(|it| byte_order.u32(it))
+            .collect();
+        let locations = read_bytes(reader, locations_size.into())?;
+        let strings = read_bytes(reader, strings_size.into())?;
+        Ok(Self {
+            byte_order,
+            redirect,
+            offsets,
+            locations,
+            strings,
+            resources_start,
+        })
+    }
+
+    /// Returns the null-terminated string at `offset` in the string table.
+    fn string(&self, offset: u32) -> Option<&[u8]> {
+        let bytes = self.strings.get(offset as usize..)?;
+        let end = bytes.iter().position(|it| *it == 0)?;
+        Some(&bytes[..end])
+    }
+
+    fn location_at(&self, offset: u32) -> Option<ImageLocation> {
+        let mut bytes = self.locations.get(offset as usize..)?.iter();
+        let mut location = ImageLocation::default();
+        loop {
+            let header = *bytes.next()?;
+            let kind = header >> 3;
+            if kind == ATTRIBUTE_END {
+                return Some(location);
+            }
+            let length = usize::from(header & 0x07) + 1;
+            let mut value = 0u64;
+            for _ in 0..length {
+                value = (value << 8) | u64::from(*bytes.next()?);
+            }
+            let index = u32::try_from(value).ok();
+            match kind {
+                ATTRIBUTE_MODULE => location.module = index?,
+                ATTRIBUTE_PARENT => location.parent = index?,
+                ATTRIBUTE_BASE => location.base = index?,
+                ATTRIBUTE_EXTENSION => location.extension = index?,
+                ATTRIBUTE_OFFSET => location.offset = value,
+                ATTRIBUTE_COMPRESSED => location.compressed_size = value,
+                ATTRIBUTE_UNCOMPRESSED => location.uncompressed_size = value,
+                _ => return None,
+            }
+        }
+    }
+
+    /// Returns the full name of a location, i.e., `/module/parent/base.extension`.
+    fn full_name(&self, location: &ImageLocation) -> Option<Vec<u8>> {
+        let mut name = Vec::new();
+        let module = self.string(location.module)?;
+        if !module.is_empty() {
+            name.push(b'/');
+            name.extend_from_slice(module);
+            name.push(b'/');
+        }
+        let parent = self.string(location.parent)?;
+        if !parent.is_empty() {
+            name.extend_from_slice(parent);
+            name.push(b'/');
+        }
+        name.extend_from_slice(self.string(location.base)?);
+        let extension = self.string(location.extension)?;
+        if !extension.is_empty() {
+            name.push(b'.');
+            name.extend_from_slice(extension);
+        }
+        Some(name)
+    }
+
+    /// Finds the location of a resource by its full name.
+    fn find(&self, name: &str) -> Option<ImageLocation> {
+        let name = name.as_bytes();
+        let length = u32::try_from(self.redirect.len())
+            .ok()
+            .filter(|it| *it > 0)?;
+        let index = hash_code(name, HASH_MULTIPLIER) % length;
+        let index = match self.redirect[index as usize] {
+            0 => return None,
+            // A negative value `v` is the direct index `-1 - v`, which is never negative.
+            value if value < 0 => u32::try_from(-1 - value).ok()?,
+            seed => hash_code(name, u32::try_from(seed).ok()?) % length,
+        };
+        let offset = *self.offsets.get(index as usize)?;
+        let location = self.location_at(offset)?;
+        // Names not in the image may collide with the names in the image.
+        (self.full_name(&location)? == name).then_some(location)
+    }
+}
+
+/// A runtime image opened for reading.
+#[derive(Debug)]
+struct JImage<R> {
+    index: ImageIndex,
+    size: u64,
+    reader: Mutex<R>,
+}
+
+impl<R: Read + Seek> JImage<R> {
+    fn new(mut reader: R) -> io::Result<Self> {
+        let size = reader.seek(SeekFrom::End(0))?;
+        reader.rewind()?;
+        let index = ImageIndex::read(&mut reader, size)?;
+        Ok(Self {
+            index,
+            size,
+            reader: Mutex::new(reader),
+        })
+    }
+
+    /// Reads the content of a resource and decompresses it if needed.
+    fn read(&self, location: &ImageLocation) -> io::Result<Vec<u8>> {
+        let size = if location.compressed_size == 0 {
+            location.uncompressed_size
+        } else {
+            location.compressed_size
+        };
+        let start = self.index.resources_start.saturating_add(location.offset);
+        if start.checked_add(size).is_none_or(|end| end > self.size) {
+            return Err(malformed("The resource exceeds the image"));
+        }
+        let mut content = vec![0; usize::try_from(size).map_err(|_| malformed("Too large"))?];
+        {
+            let mut reader = match self.reader.lock() {
+                Ok(it) => it,
+                // The reader is always seeked before reading.
+                Err(poison_err) => poison_err.into_inner(),
+            };
+            reader.seek(SeekFrom::Start(start))?;
+            reader.read_exact(&mut content)?;
+        }
+        if location.compressed_size != 0 {
+            content = self.decompress(content)?;
+        }
+        Ok(content)
+    }
+
+    /// Removes the layers of compression applied to a resource.
+    fn decompress(&self, mut content: Vec<u8>) -> io::Result<Vec<u8>> {
+        let byte_order = self.index.byte_order;
+        while content.len() >= COMPRESSED_HEADER_SIZE
+            && byte_order.u32(&content) == COMPRESSED_MAGIC
+        {
+            let uncompressed_size = byte_order.u64(&content[12..]);
+            let decompressor = self
+                .index
+                .string(byte_order.u32(&content[20..]))
+                .ok_or_else(|| malformed("Invalid decompressor name"))?;
+            let payload = &content[COMPRESSED_HEADER_SIZE..];
+            content = match decompressor {
+                b"zip" => {
+                    // The buffer grows with the actual output instead of the declared size.
+                    let mut decompressed = Vec::new();
+                    flate2::read::ZlibDecoder::new(payload)
+                        .take(uncompressed_size)
+                        .read_to_end(&mut decompressed)?;
+                    decompressed
+                }
+                b"compact-cp" => expand_shared_strings(&self.index, payload)?,
+                _ => return Err(malformed("Unknown decompressor")),
+            };
+        }
+        Ok(content)
+    }
+}
+
+/// Reads an index compressed by `jdk.internal.jimage.decompressor.CompressIndexes`.
+fn read_compressed_index(bytes: &mut &[u8]) -> io::Result<u32> {
+    let (&header, rest) = bytes
+        .split_first()
+        .ok_or_else(|| malformed("Unexpected end of compressed index"))?;
+    let (length, value) = if header & 0x80 == 0 {
+        (4, u32::from(header))
+    } else {
+        (usize::from((header >> 5) & 0x03), u32::from(header & 0x1F))
+    };
+    if length == 0 || rest.len() < length - 1 {
+        return Err(malformed("Invalid compressed index"));
+    }
+    let (tail, rest) = rest.split_at(length - 1);
+    *bytes = rest;
+    Ok(tail
+        .iter()
+        .fold(value, |acc, byte| (acc << 8) | u32::from(*byte)))
+}
+
+fn split_bytes<'b>(bytes: &mut &'b [u8], length: usize) -> io::Result<&'b [u8]> {
+    if bytes.len() < length {
+        return Err(malformed("Unexpected end of compressed class file"));
+    }
+    let (head, rest) = bytes.split_at(length);
+    *bytes = rest;
+    Ok(head)
+}
+
+/// Restores the strings moved to the string table of the image by the string sharing plugin.
+fn expand_shared_strings(index: &ImageIndex, mut content: &[u8]) -> io::Result<Vec<u8>> {
+    const CONSTANT_UTF8: u8 = 1;
+    const EXTERNALIZED_STRING: u8 = 23;
+    const EXTERNALIZED_STRING_DESCRIPTOR: u8 = 25;
+
+    let string = |offset| {
+        index
+            .string(offset)
+            .ok_or_else(|| malformed("Invalid shared string"))
+    };
+    let push_utf8 = |output: &mut Vec<u8>, value: &[u8]| -> io::Result<()> {
+        let length = u16::try_from(value.len()).map_err(|_| malformed("String too long"))?;
+        output.push(CONSTANT_UTF8);
+        output.extend_from_slice(&length.to_be_bytes());
+        output.extend_from_slice(value);
+        Ok(())
+    };
+    let mut output = Vec::with_capacity(content.len() * 2);
+    // Magic, minor version, and major version
+    output.extend_from_slice(split_bytes(&mut content, 8)?);
+    let count_bytes = split_bytes(&mut content, 2)?;
+    output.extend_from_slice(count_bytes);
+    let count = u16::from_be_bytes([count_bytes[0], count_bytes[1]]);
+    let mut index_in_pool = 1;
+    while index_in_pool < count {
+        let tag = split_bytes(&mut content, 1)?[0];
+        match tag {
+            CONSTANT_UTF8 => {
+                let length = split_bytes(&mut content, 2)?;
+                let value = split_bytes(
+                    &mut content,
+                    usize::from(u16::from_be_bytes([length[0], length[1]])),
+                )?;
+                push_utf8(&mut output, value)?;
+            }
+            EXTERNALIZED_STRING => {
+                let offset = read_compressed_index(&mut content)?;
+                push_utf8(&mut output, string(offset)?)?;
+            }
+            EXTERNALIZED_STRING_DESCRIPTOR => {
+                let descriptor = string(read_compressed_index(&mut content)?)?;
+                let indices_length = read_compressed_index(&mut content)?;
+                let mut indices = split_bytes(&mut content, indices_length as usize)?;
+                let mut value = Vec::with_capacity(descriptor.len() * 2);
+                for &byte in descriptor {
+                    value.push(byte);
+                    if byte == b'L' {
+                        let package = string(read_compressed_index(&mut indices)?)?;
+                        if !package.is_empty() {
+                            value.extend_from_slice(package);
+                            value.push(b'/');
+                        }
+                        value.extend_from_slice(string(read_compressed_index(&mut indices)?)?);
+                    }
+                }
+                push_utf8(&mut output, &value)?;
+            }
+            _ => {
+                let size = match tag {
+                    // Class, String, MethodType, Module, Package
+                    7 | 8 | 16 | 19 | 20 => 2,
+                    // MethodHandle
+                    15 => 3,
+                    // Integer, Float, Fieldref, Methodref, InterfaceMethodref, NameAndType,
+                    // Dynamic, InvokeDynamic
+                    3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 4,
+                    // Long, Double
+                    5 | 6 => 8,
+                    _ => return Err(malformed("Invalid constant pool tag")),
+                };
+                if matches!(tag, 5 | 6) {
+                    index_in_pool += 1;
+                }
+                output.push(tag);
+                output.extend_from_slice(split_bytes(&mut content, size)?);
+            }
+        }
+        index_in_pool += 1;
+    }
+    output.extend_from_slice(content);
+    Ok(output)
+}
+
+/// A class path that searches for classes in a Java runtime image, which is located at
+/// `$JAVA_HOME/lib/modules` since Java 9.
+#[derive(Debug)]
+pub struct JrtClassPath {
+    image: JImage<BufReader<File>>,
+    options: ParseOptions,
+}
+
+impl JrtClassPath {
+    /// Opens a runtime image.
+    ///
+    /// # Errors
+    /// - [`Error::IO`] if the image cannot be read or is malformed.
+    pub fn new(image_file: impl AsRef<Path>) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(image_file)?);
+        let image = JImage::new(reader)?;
+        Ok(Self {
+            image,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Opens the runtime image of the JDK or JRE installed at `java_home`.
+    ///
+    /// # Errors
+    /// See [`JrtClassPath::new`].
+    pub fn from_java_home(java_home: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::new(java_home.into().join("lib").join("modules"))
+    }
+
+    /// Sets the options for parsing the classes found in the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the name of the module containing the package of a class.
+    #[must_use]
+    pub fn module_of(&self, binary_name: &str) -> Option<String> {
+        let (package, _) = binary_name.rsplit_once('/')?;
+        let location = self
+            .image
+            .index
+            .find(&format!("/packages/{}", package.replace('/', ".")))?;
+        let content = self.image.read(&location).ok()?;
+        // Each entry consists of a flag indicating whether the package is empty in the module and
+        // the offset of the module name in the string table.
+        let byte_order = self.image.index.byte_order;
+        let mut modules = content.chunks_exact(8).map(|it| {
+            let is_empty = byte_order.u32(it) != 0;
+            (is_empty, byte_order.u32(&it[4..]))
+        });
+        let (_, module) = modules
+            .clone()
+            .find(|(is_empty, _)| !is_empty)
+            .or_else(|| modules.next())?;
+        let module = self.image.index.string(module)?;
+        Some(String::from_utf8_lossy(module).into_owned())
+    }
+}
+
+impl ClassPath for JrtClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        let module = self.module_of(binary_name).ok_or(Error::NotFound)?;
+        let location = self
+            .image
+            .index
+            .find(&format!("/{module}/{binary_name}.class"))
+            .ok_or(Error::NotFound)?;
+        let bytes = self.image.read(&location)?;
+        parse_class(bytes.as_slice(), &self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Builds a little-endian image containing `resources`, which are full names and contents.
+    fn build_image(resources: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut strings = vec![0u8];
+        let mut intern = |value: &str| {
+            if value.is_empty() {
+                return 0;
+            }
+            let offset = u32::try_from(strings.len()).unwrap();
+            strings.extend_from_slice(value.as_bytes());
+            strings.push(0);
+            offset
+        };
+        let table_length = 16;
+        let mut redirect = [0i32; 16];
+        // Uses a direct index for each resource, which is valid as long as no two names collide.
+        for (i, (name, _)) in resources.iter().enumerate() {
+            let bucket = (hash_code(name.as_bytes(), HASH_MULTIPLIER) % table_length) as usize;
+            assert_eq!(redirect[bucket], 0, "Hash collision in the test image");
+            redirect[bucket] = -1 - i32::try_from(i).unwrap();
+        }
+        let mut locations = vec![];
+        let mut offsets = vec![];
+        let mut content = vec![];
+        for (name, bytes) in resources {
+            let (module, path) = name[1..].split_once('/').unwrap();
+            let (parent, file) = path.rsplit_once('/').unwrap_or(("", path));
+            let (base, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+            offsets.push(u32::try_from(locations.len()).unwrap());
+            let attributes = [
+                (ATTRIBUTE_MODULE, u64::from(intern(module))),
+                (ATTRIBUTE_PARENT, u64::from(intern(parent))),
+                (ATTRIBUTE_BASE, u64::from(intern(base))),
+                (ATTRIBUTE_EXTENSION, u64::from(intern(extension))),
+                (ATTRIBUTE_OFFSET, content.len() as u64),
+                (ATTRIBUTE_UNCOMPRESSED, bytes.len() as u64),
+            ];
+            for (kind, value) in attributes {
+                locations.push((kind << 3) | 7);
+                locations.extend_from_slice(&value.to_be_bytes());
+            }
+            locations.push(ATTRIBUTE_END);
+            content.extend_from_slice(bytes);
+        }
+        offsets.resize(16, 0);
+        let mut image = vec![];
+        let header = [
+            IMAGE_MAGIC,
+            u32::from(MAJOR_VERSION) << 16,
+            0,
+            table_length,
+            table_length,
+            u32::try_from(locations.len()).unwrap(),
+            u32::try_from(strings.len()).unwrap(),
+        ];
+        for it in header {
+            image.extend_from_slice(&it.to_le_bytes());
+        }
+        for it in redirect {
+            image.extend_from_slice(&it.to_le_bytes());
+        }
+        for it in offsets {
+            image.extend_from_slice(&it.to_le_bytes());
+        }
+        image.extend_from_slice(&locations);
+        image.extend_from_slice(&strings);
+        image.extend_from_slice(&content);
+        image
+    }
+
+    #[test]
+    fn find_resources() {
+        let image = build_image(&[
+            ("/java.base/java/lang/Object.class", b"object"),
+            ("/java.base/module-info.class", b"module"),
+        ]);
+        let image = JImage::new(Cursor::new(image)).unwrap();
+        let location = image
+            .index
+            .find("/java.base/java/lang/Object.class")
+            .unwrap();
+        assert_eq!(image.read(&location).unwrap(), b"object");
+        let location = image.index.find("/java.base/module-info.class").unwrap();
+        assert_eq!(image.read(&location).unwrap(), b"module");
+        assert!(image
+            .index
+            .find("/java.base/java/lang/String.class")
+            .is_none());
+    }
+
+    #[test]
+    fn not_an_image() {
+        let error = JImage::new(Cursor::new(vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 0])).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        let error = JImage::new(Cursor::new(vec![0; HEADER_SIZE])).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn sizes_exceeding_image() {
+        let mut image = build_image(&[("/java.base/java/lang/Object.class", b"object")]);
+        // The size of the string table is the last field in the header.
+        let mut oversized_index = image.clone();
+        oversized_index[HEADER_SIZE - 4..HEADER_SIZE].copy_from_slice(&u32::MAX.to_le_bytes());
+        let error = JImage::new(Cursor::new(oversized_index)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        image.truncate(image.len() - 1);
+        let image = JImage::new(Cursor::new(image)).unwrap();
+        let location = image
+            .index
+            .find("/java.base/java/lang/Object.class")
+            .unwrap();
+        let error = image.read(&location).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn compressed_indices() {
+        let mut bytes: &[u8] = &[0xA1, 0xC2, 0x01, 0x00, 0x00, 0x01, 0x02];
+        assert_eq!(read_compressed_index(&mut bytes).unwrap(), 0x01);
+        assert_eq!(read_compressed_index(&mut bytes).unwrap(), 0x0201);
+        assert_eq!(read_compressed_index(&mut bytes).unwrap(), 0x0000_0102);
+        assert!(bytes.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! The manifest of JAR files (`META-INF/MANIFEST.MF`).
+
+use std::{collections::BTreeMap, str::FromStr};
+
+/// An error that can occur while parsing a manifest.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum ManifestError {
+    /// A line is neither an attribute nor a continuation of the previous one.
+    #[error("Invalid line {0} in the manifest")]
+    InvalidLine(usize),
+}
+
+/// The attributes in a section of a manifest.
+/// The names of the attributes are case-insensitive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Attributes(BTreeMap<String, String>);
+
+impl Attributes {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The manifest of a JAR file.
+///
+/// See the [JAR file specification](https://docs.oracle.com/en/java/javase/21/docs/specs/jar/jar.html#jar-manifest).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    main_attributes: Attributes,
+    entries: BTreeMap<String, Attributes>,
+}
+
+impl Manifest {
+    /// The location of the manifest in a JAR file.
+    pub const PATH: &'static str = "META-INF/MANIFEST.MF";
+
+    /// Returns the value of an attribute in the main section.
+    #[must_use]
+    pub fn main_attribute(&self, name: &str) -> Option<&str> {
+        self.main_attributes.get(name)
+    }
+
+    /// Returns the attributes in the main section.
+    pub fn main_attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.main_attributes
+            .0
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the value of an attribute in the section of an entry, e.g., `org/mokapot/`.
+    #[must_use]
+    pub fn entry_attribute(&self, entry: &str, name: &str) -> Option<&str> {
+        self.entries.get(entry)?.get(name)
+    }
+
+    /// Returns the `Main-Class` attribute, i.e., the class to launch the application.
+    #[must_use]
+    pub fn main_class(&self) -> Option<&str> {
+        self.main_attribute("Main-Class")
+    }
+
+    /// Returns the relative URLs in the `Class-Path` attribute.
+    pub fn class_path(&self) -> impl Iterator<Item = &str> {
+        self.main_attribute("Class-Path")
+            .into_iter()
+            .flat_map(str::split_whitespace)
+    }
+
+    /// Returns the `Automatic-Module-Name` attribute, i.e., the module name of the JAR file when
+    /// it is used as an automatic module.
+    #[must_use]
+    pub fn automatic_module_name(&self) -> Option<&str> {
+        self.main_attribute("Automatic-Module-Name")
+    }
+
+    /// Checks whether the `Multi-Release` attribute is `true`.
+    #[must_use]
+    pub fn is_multi_release(&self) -> bool {
+        self.main_attribute("Multi-Release")
+            .is_some_and(|it| it.trim().eq_ignore_ascii_case("true"))
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ManifestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut manifest = Manifest::default();
+        let mut sections = Vec::new();
+        let mut current: Vec<(String, String)> = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            if line.is_empty() {
+                if !current.is_empty() {
+                    sections.push(std::mem::take(&mut current));
+                }
+            } else if let Some(continuation) = line.strip_prefix(' ') {
+                let (_, value) = current
+                    .last_mut()
+                    .ok_or(ManifestError::InvalidLine(index + 1))?;
+                value.push_str(continuation);
+            } else {
+                let (name, value) = line
+                    .split_once(": ")
+                    .or_else(|| line.strip_suffix(':').map(|name| (name, "")))
+                    .ok_or(ManifestError::InvalidLine(index + 1))?;
+                current.push((name.to_owned(), value.to_owned()));
+            }
+        }
+        if !current.is_empty() {
+            sections.push(current);
+        }
+        let mut sections = sections.into_iter();
+        if let Some(main) = sections.next() {
+            manifest.main_attributes = Attributes(main.into_iter().collect());
+        }
+        for section in sections {
+            let attributes = Attributes(section.into_iter().collect());
+            // Sections without a name are ignored.
+            if let Some(name) = attributes.get("Name") {
+                manifest.entries.insert(name.to_owned(), attributes);
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let manifest: Manifest = concat!(
+            "Manifest-Version: 1.0\r\n",
+            "main-class: org.mokapot.Main\r\n",
+            "Class-Path: lib/a.jar lib/b.jar lib/a-very-long-name-that-does-not-fit-into-a-s\r\n",
+            " ingle-line.jar\r\n",
+            "Multi-Release: true\r\n",
+            "Automatic-Module-Name: org.mokapot\r\n",
+            "\r\n",
+            "Name: org/mokapot/\r\n",
+            "Sealed: true\r\n",
+            "\r\n",
+        )
+        .parse()
+        .unwrap();
+        assert_eq!(manifest.main_class(), Some("org.mokapot.Main"));
+        assert_eq!(
+            manifest.class_path().collect::<Vec<_>>(),
+            vec![
+                "lib/a.jar",
+                "lib/b.jar",
+                "lib/a-very-long-name-that-does-not-fit-into-a-single-line.jar"
+            ]
+        );
+        assert!(manifest.is_multi_release());
+        assert_eq!(manifest.automatic_module_name(), Some("org.mokapot"));
+        assert_eq!(
+            manifest.entry_attribute("org/mokapot/", "sealed"),
+            Some("true")
+        );
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        assert_eq!(
+            "Manifest-Version: 1.0\nInvalid".parse::<Manifest>(),
+            Err(ManifestError::InvalidLine(2))
+        );
+        assert_eq!(
+            " continuation".parse::<Manifest>(),
+            Err(ManifestError::InvalidLine(1))
+        );
+        assert!(!Manifest::default().is_multi_release());
+    }
+}
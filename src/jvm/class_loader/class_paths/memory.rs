@@ -0,0 +1,145 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::jvm::{
+    class::Class,
+    parsing::{ParseOptions, ParseWarning},
+};
+
+use super::{
+    super::{ClassPath, Error},
+    parse_class,
+};
+
+#[derive(Debug, Clone)]
+enum ClassEntry {
+    Bytes(Vec<u8>),
+    Parsed(Box<Class>),
+}
+
+/// A class path that searches for classes in memory, e.g., class bytes received from another
+/// process or classes generated in tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClassPath {
+    classes: BTreeMap<String, ClassEntry>,
+    options: ParseOptions,
+}
+
+impl MemoryClassPath {
+    /// Creates an empty memory class path.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the options for parsing the class bytes added to the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Adds the bytes of a class file with the given binary name.
+    /// The bytes are parsed every time the class is looked up.
+    #[must_use]
+    pub fn with_class_bytes(mut self, binary_name: impl Into<String>, bytes: Vec<u8>) -> Self {
+        self.insert_class_bytes(binary_name, bytes);
+        self
+    }
+
+    /// Adds a parsed class, which can be found by its binary name.
+    #[must_use]
+    pub fn with_class(mut self, class: Class) -> Self {
+        self.insert_class(class);
+        self
+    }
+
+    /// Adds the bytes of a class file with the given binary name, replacing the class with the
+    /// same name if it exists.
+    pub fn insert_class_bytes(&mut self, binary_name: impl Into<String>, bytes: Vec<u8>) {
+        self.classes
+            .insert(binary_name.into(), ClassEntry::Bytes(bytes));
+    }
+
+    /// Adds a parsed class, replacing the class with the same binary name if it exists.
+    pub fn insert_class(&mut self, class: Class) {
+        self.classes.insert(
+            class.binary_name.clone(),
+            ClassEntry::Parsed(Box::new(class)),
+        );
+    }
+}
+
+impl FromIterator<(String, Vec<u8>)> for MemoryClassPath {
+    fn from_iter<T: IntoIterator<Item = (String, Vec<u8>)>>(iter: T) -> Self {
+        let classes = iter
+            .into_iter()
+            .map(|(name, bytes)| (name, ClassEntry::Bytes(bytes)))
+            .collect();
+        Self {
+            classes,
+            options: ParseOptions::default(),
+        }
+    }
+}
+
+impl FromIterator<Class> for MemoryClassPath {
+    fn from_iter<T: IntoIterator<Item = Class>>(iter: T) -> Self {
+        let classes = iter
+            .into_iter()
+            .map(|class| {
+                (
+                    class.binary_name.clone(),
+                    ClassEntry::Parsed(Box::new(class)),
+                )
+            })
+            .collect();
+        Self {
+            classes,
+            options: ParseOptions::default(),
+        }
+    }
+}
+
+impl ClassPath for MemoryClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        match self.classes.get(binary_name) {
+            Some(ClassEntry::Bytes(bytes)) => parse_class(bytes.as_slice(), &self.options),
+            Some(ClassEntry::Parsed(class)) => Ok((class.as_ref().clone(), Vec::new())),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        Ok(self.classes.keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_bytes() {
+        let class_path = MemoryClassPath::new().with_class_bytes("Broken", vec![0xCA, 0xFE]);
+        assert!(matches!(
+            class_path.find_class("Broken"),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            class_path.find_class("Missing"),
+            Err(Error::NotFound)
+        ));
+        assert_eq!(
+            class_path.class_names().unwrap(),
+            BTreeSet::from(["Broken".to_owned()])
+        );
+    }
+}
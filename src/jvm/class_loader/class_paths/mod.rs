@@ -0,0 +1,121 @@
+//! Implementations of [`ClassPath`].
+
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use crate::jvm::{
+    class::Class,
+    parsing::{ParseOptions, ParseWarning},
+};
+
+use super::{ClassPath, Error};
+
+mod classpath_string;
+#[cfg(feature = "jar")]
+mod jar;
+#[cfg(feature = "jrt")]
+mod jrt;
+mod manifest;
+mod memory;
+#[cfg(feature = "jar")]
+mod nested;
+
+pub use classpath_string::{ClasspathEntry, ClasspathString};
+#[cfg(feature = "jar")]
+pub use jar::{JarClassPath, JmodClassPath};
+#[cfg(feature = "jrt")]
+pub use jrt::JrtClassPath;
+pub use manifest::{Manifest, ManifestError};
+pub use memory::MemoryClassPath;
+#[cfg(feature = "jar")]
+pub use nested::{ArchiveLayout, NestedJarClassPath};
+
+/// A class path that searches for classes in a directory.
+#[derive(Debug)]
+pub struct DirectoryClassPath {
+    directory: std::path::PathBuf,
+    options: ParseOptions,
+}
+
+impl ClassPath for DirectoryClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        let class_file_path = self.directory.join(binary_name).with_extension("class");
+        if class_file_path.exists() {
+            let class_file = File::open(class_file_path)?;
+            let buf_read = BufReader::new(class_file);
+            parse_class(buf_read, &self.options)
+        } else {
+            Err(Error::NotFound)
+        }
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        let mut class_names = BTreeSet::new();
+        let mut worklist = vec![self.directory.clone()];
+        while let Some(directory) = worklist.pop() {
+            for entry in fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    worklist.push(path);
+                } else if path.extension().is_some_and(|it| it == "class") {
+                    class_names.insert(binary_name_of(&self.directory, &path)?);
+                }
+            }
+        }
+        Ok(class_names)
+    }
+}
+
+/// Parses a class file found in a class path with the options of the class path.
+fn parse_class(
+    reader: impl Read,
+    options: &ParseOptions,
+) -> Result<(Class, Vec<ParseWarning>), Error> {
+    Class::from_reader_with_options(reader, options).map_err(Into::into)
+}
+
+/// Gets the binary name of the class in `class_file`, which is relative to `directory`.
+fn binary_name_of(directory: &Path, class_file: &Path) -> Result<String, Error> {
+    let relative = class_file
+        .strip_prefix(directory)
+        .map_err(|e| Error::Other(Box::new(e)))?
+        .with_extension("");
+    let components = relative
+        .components()
+        .map(|it| {
+            it.as_os_str()
+                .to_str()
+                .ok_or_else(|| Error::Other("Non UTF-8 file name".into()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(components.join("/"))
+}
+
+impl DirectoryClassPath {
+    /// Create a new directory class path.
+    pub fn new(directory: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            options: ParseOptions::default(),
+        }
+    }
+
+    /// Sets the options for parsing the classes found in the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
@@ -0,0 +1,253 @@
+use std::{collections::BTreeSet, path::PathBuf, sync::OnceLock};
+
+use crate::jvm::{
+    class::Class,
+    parsing::{ParseOptions, ParseWarning},
+};
+
+use super::{
+    super::{ClassPath, Error},
+    jar::IndexedArchive,
+};
+
+/// Describes where the classes are located in an archive that contains other archives, e.g., a
+/// Spring Boot fat JAR or a WAR file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ArchiveLayout {
+    class_dirs: Vec<String>,
+    lib_dirs: Vec<String>,
+}
+
+impl ArchiveLayout {
+    /// The layout of a plain JAR file, where the classes are at the root.
+    #[must_use]
+    pub fn jar() -> Self {
+        Self::default().with_class_dir("")
+    }
+
+    /// The layout of a Spring Boot executable JAR file.
+    /// The application classes are in `BOOT-INF/classes/` and the dependencies in
+    /// `BOOT-INF/lib/`, while the classes of the launcher are at the root.
+    #[must_use]
+    pub fn spring_boot() -> Self {
+        Self::default()
+            .with_class_dir("BOOT-INF/classes")
+            .with_class_dir("")
+            .with_lib_dir("BOOT-INF/lib")
+    }
+
+    /// The layout of a WAR file, where the classes are in `WEB-INF/classes/` and the libraries
+    /// in `WEB-INF/lib/`.
+    #[must_use]
+    pub fn war() -> Self {
+        Self::default()
+            .with_class_dir("WEB-INF/classes")
+            .with_lib_dir("WEB-INF/lib")
+    }
+
+    /// The layout of an EAR file, where the modules (JAR and WAR files) are at the root and the
+    /// libraries in `lib/`.
+    #[must_use]
+    pub fn ear() -> Self {
+        Self::default().with_lib_dir("").with_lib_dir("lib")
+    }
+
+    /// Adds a directory (e.g., `BOOT-INF/classes`) containing classes.
+    /// An empty string denotes the root of the archive.
+    /// Directories are searched in the order they are added.
+    #[must_use]
+    pub fn with_class_dir(mut self, directory: impl Into<String>) -> Self {
+        self.class_dirs.push(normalize_dir(directory.into()));
+        self
+    }
+
+    /// Adds a directory (e.g., `BOOT-INF/lib`) containing nested JAR or WAR files.
+    /// Only the archives directly in the directory are included.
+    /// Nested WAR files are searched with the [`ArchiveLayout::war`] layout and other archives
+    /// with the [`ArchiveLayout::jar`] layout.
+    #[must_use]
+    pub fn with_lib_dir(mut self, directory: impl Into<String>) -> Self {
+        self.lib_dirs.push(normalize_dir(directory.into()));
+        self
+    }
+
+    /// Returns the nested archives in `archive` in the order they are searched.
+    fn nested_archives<'a>(&'a self, archive: &'a IndexedArchive) -> Vec<&'a str> {
+        let mut nested = Vec::new();
+        for lib_dir in &self.lib_dirs {
+            let mut entries: Vec<_> = archive
+                .entry_names()
+                .filter(|name| {
+                    name.strip_prefix(lib_dir.as_str()).is_some_and(|file| {
+                        !file.contains('/')
+                            && [".jar", ".war"]
+                                .iter()
+                                .any(|ext| file.to_ascii_lowercase().ends_with(ext))
+                    })
+                })
+                .collect();
+            entries.sort_unstable();
+            nested.extend(entries);
+        }
+        nested
+    }
+}
+
+fn normalize_dir(mut directory: String) -> String {
+    if !directory.is_empty() && !directory.ends_with('/') {
+        directory.push('/');
+    }
+    directory
+}
+
+/// An archive and the directories containing classes in it.
+#[derive(Debug)]
+struct SearchedArchive {
+    archive: IndexedArchive,
+    class_dirs: Vec<String>,
+}
+
+/// A class path that searches for classes in an archive and the archives nested in it, e.g., a
+/// Spring Boot fat JAR, a WAR file, or an EAR file.
+///
+/// The classes in the directories of the outer archive are searched first, followed by the
+/// nested archives in the order of [`ArchiveLayout::with_lib_dir`] and then by name.
+/// The archives are read when the first class is looked up.
+#[derive(Debug)]
+pub struct NestedJarClassPath {
+    archive_file: PathBuf,
+    layout: ArchiveLayout,
+    release: Option<u16>,
+    options: ParseOptions,
+    archives: OnceLock<Vec<SearchedArchive>>,
+}
+
+impl NestedJarClassPath {
+    /// Creates a new class path for the archive with the given layout.
+    pub fn new(archive_file: impl Into<PathBuf>, layout: ArchiveLayout) -> Self {
+        Self {
+            archive_file: archive_file.into(),
+            layout,
+            release: None,
+            options: ParseOptions::default(),
+            archives: OnceLock::new(),
+        }
+    }
+
+    /// Creates a new class path for a Spring Boot executable JAR file.
+    pub fn spring_boot(jar_file: impl Into<PathBuf>) -> Self {
+        Self::new(jar_file, ArchiveLayout::spring_boot())
+    }
+
+    /// Creates a new class path for a WAR file.
+    pub fn war(war_file: impl Into<PathBuf>) -> Self {
+        Self::new(war_file, ArchiveLayout::war())
+    }
+
+    /// Creates a new class path for an EAR file.
+    pub fn ear(ear_file: impl Into<PathBuf>) -> Self {
+        Self::new(ear_file, ArchiveLayout::ear())
+    }
+
+    /// Sets the target Java release for looking up classes in multi-release JAR files.
+    /// See [`super::JarClassPath::with_release`].
+    #[must_use]
+    pub fn with_release(mut self, release: u16) -> Self {
+        self.release = Some(release);
+        self
+    }
+
+    /// Sets the options for parsing the classes found in the class path.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn archives(&self) -> Result<&[SearchedArchive], Error> {
+        if let Some(archives) = self.archives.get() {
+            return Ok(archives);
+        }
+        let outer = IndexedArchive::open(&self.archive_file, &[])?;
+        let mut archives = Vec::new();
+        collect_archives(outer, &self.layout, &mut archives)?;
+        Ok(self.archives.get_or_init(|| archives))
+    }
+}
+
+/// Adds `archive` and the archives nested in it to `archives`.
+fn collect_archives(
+    archive: IndexedArchive,
+    layout: &ArchiveLayout,
+    archives: &mut Vec<SearchedArchive>,
+) -> Result<(), Error> {
+    let mut nested = Vec::new();
+    for name in layout.nested_archives(&archive) {
+        let nested_layout = if name.to_ascii_lowercase().ends_with(".war") {
+            ArchiveLayout::war()
+        } else {
+            ArchiveLayout::jar()
+        };
+        let content = archive.read_entry(name)?;
+        nested.push((IndexedArchive::from_bytes(content)?, nested_layout));
+    }
+    archives.push(SearchedArchive {
+        archive,
+        class_dirs: layout.class_dirs.clone(),
+    });
+    for (archive, layout) in nested {
+        collect_archives(archive, &layout, archives)?;
+    }
+    Ok(())
+}
+
+impl ClassPath for NestedJarClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        for SearchedArchive {
+            archive,
+            class_dirs,
+        } in self.archives()?
+        {
+            for class_dir in class_dirs {
+                match archive.find_class(class_dir, binary_name, self.release, &self.options) {
+                    Err(Error::NotFound) => {}
+                    result => return result,
+                }
+            }
+        }
+        Err(Error::NotFound)
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        let mut class_names = BTreeSet::new();
+        for SearchedArchive {
+            archive,
+            class_dirs,
+        } in self.archives()?
+        {
+            for class_dir in class_dirs {
+                // Exclude the classes in the other directories (e.g., `BOOT-INF/classes/`) when
+                // listing the root of the archive.
+                let names = archive
+                    .class_names(class_dir, self.release)
+                    .into_iter()
+                    .filter(|name| {
+                        class_dirs.iter().all(|other| {
+                            other.len() <= class_dir.len()
+                                || !format!("{class_dir}{name}").starts_with(other.as_str())
+                        })
+                    });
+                class_names.extend(names);
+            }
+        }
+        Ok(class_names)
+    }
+}
@@ -0,0 +1,235 @@
+//! Hierarchies of class loaders with parent delegation.
+//!
+//! A [`ClassLoaderHierarchy`] models the class loaders of an application server or a plugin
+//! system, where each loader has its own class paths and may delegate to a parent loader.
+//! The same binary name can be defined by different loaders, so each loaded class is tagged with
+//! its defining loader in a [`DefinedClass`].
+//!
+//! The analyses in [`crate::analysis`] work on a single namespace given by a
+//! [`CachingClassLoader`].
+//! To analyze an application as seen by one of the loaders, create a [`CachingClassLoader`] from
+//! [`ClassLoaderHierarchy::class_path`], which resolves every class through the delegation chain
+//! of that loader.
+//! Classes defined by different loaders under the same binary name are not distinguished within
+//! such a namespace, since a loader only ever sees one of them.
+
+use crate::jvm::class::Class;
+
+use super::{CachingClassLoader, ClassLoader, ClassPath, Error};
+
+/// An error that occurs when building a [`ClassLoaderHierarchy`].
+#[derive(Debug, thiserror::Error)]
+pub enum HierarchyError {
+    /// The loader is not in the hierarchy.
+    #[error("Unknown class loader: {0:?}")]
+    UnknownLoader(LoaderId),
+}
+
+/// The identifier of a class loader in a [`ClassLoaderHierarchy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoaderId(usize);
+
+/// How a class loader delegates to its parent.
+///
+/// Regardless of the delegation, classes in the `java/` package are always searched in the
+/// parent first, since they can only be defined by the bootstrap class loader.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Delegation {
+    /// Searches the parent before the loader itself, which is the default in the JVM.
+    #[default]
+    ParentFirst,
+    /// Searches the loader itself before the parent, e.g., for web applications.
+    ChildFirst,
+    /// Searches the parent first for classes in the packages with the given prefixes
+    /// (e.g., `javax/servlet/`), and the loader itself first for the other classes.
+    ParentFirstFor(Vec<String>),
+}
+
+impl Delegation {
+    fn is_parent_first(&self, binary_name: &str) -> bool {
+        binary_name.starts_with("java/")
+            || match self {
+                Self::ParentFirst => true,
+                Self::ChildFirst => false,
+                Self::ParentFirstFor(prefixes) => prefixes
+                    .iter()
+                    .any(|prefix| binary_name.starts_with(prefix.as_str())),
+            }
+    }
+}
+
+/// A class and the class loader that defines it.
+#[derive(Debug, Clone, Copy)]
+pub struct DefinedClass<'h> {
+    /// The loader defining the class.
+    pub loader: LoaderId,
+    /// The class, which is cached by its defining loader.
+    pub class: &'h Class,
+}
+
+#[derive(Debug)]
+struct LoaderNode<P> {
+    name: String,
+    parent: Option<LoaderId>,
+    delegation: Delegation,
+    /// Caches the classes defined by the loader.
+    class_loader: CachingClassLoader<P>,
+}
+
+/// A set of class loaders organized in trees.
+///
+/// Loaders without a parent are isolated from each other, so are the subtrees under them.
+#[derive(Debug)]
+pub struct ClassLoaderHierarchy<P> {
+    loaders: Vec<LoaderNode<P>>,
+}
+
+impl<P> Default for ClassLoaderHierarchy<P> {
+    fn default() -> Self {
+        Self {
+            loaders: Vec::new(),
+        }
+    }
+}
+
+impl<P> ClassLoaderHierarchy<P> {
+    /// Creates an empty hierarchy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a loader without a parent, e.g., the bootstrap class loader.
+    pub fn add_root<C: Into<Vec<P>>>(
+        &mut self,
+        name: impl Into<String>,
+        class_path: C,
+    ) -> LoaderId {
+        self.add_loader(
+            name.into(),
+            None,
+            Delegation::ParentFirst,
+            class_path.into(),
+        )
+    }
+
+    /// Adds a loader that delegates to `parent` according to `delegation`.
+    ///
+    /// # Errors
+    /// - [`HierarchyError::UnknownLoader`] if `parent` is not in this hierarchy.
+    pub fn add_child<C: Into<Vec<P>>>(
+        &mut self,
+        parent: LoaderId,
+        name: impl Into<String>,
+        delegation: Delegation,
+        class_path: C,
+    ) -> Result<LoaderId, HierarchyError> {
+        if parent.0 >= self.loaders.len() {
+            return Err(HierarchyError::UnknownLoader(parent));
+        }
+        Ok(self.add_loader(name.into(), Some(parent), delegation, class_path.into()))
+    }
+
+    fn add_loader(
+        &mut self,
+        name: String,
+        parent: Option<LoaderId>,
+        delegation: Delegation,
+        class_path: Vec<P>,
+    ) -> LoaderId {
+        let id = LoaderId(self.loaders.len());
+        self.loaders.push(LoaderNode {
+            name,
+            parent,
+            delegation,
+            class_loader: ClassLoader::new(class_path).into(),
+        });
+        id
+    }
+
+    /// Returns the name of a loader.
+    ///
+    /// # Panics
+    /// Panics if `loader` is not in this hierarchy.
+    #[must_use]
+    pub fn name(&self, loader: LoaderId) -> &str {
+        &self.loaders[loader.0].name
+    }
+
+    /// Returns the parent of a loader, or `None` if it is a root.
+    ///
+    /// # Panics
+    /// Panics if `loader` is not in this hierarchy.
+    #[must_use]
+    pub fn parent(&self, loader: LoaderId) -> Option<LoaderId> {
+        self.loaders[loader.0].parent
+    }
+
+    /// Loads a class on behalf of `loader`, delegating to its ancestors according to their
+    /// [`Delegation`].
+    /// Each class is read only once by its defining loader and shared by all the loaders
+    /// delegating to it.
+    ///
+    /// # Errors
+    /// See [`Error`].
+    ///
+    /// # Panics
+    /// Panics if `loader` is not in this hierarchy.
+    pub fn load_class(&self, loader: LoaderId, binary_name: &str) -> Result<DefinedClass<'_>, Error>
+    where
+        P: ClassPath,
+    {
+        let node = &self.loaders[loader.0];
+        let define = || {
+            node.class_loader
+                .load_class(binary_name)
+                .map(|class| DefinedClass { loader, class })
+        };
+        let Some(parent) = node.parent else {
+            return define();
+        };
+        let delegate = || self.load_class(parent, binary_name);
+        if node.delegation.is_parent_first(binary_name) {
+            match delegate() {
+                Err(Error::NotFound) => define(),
+                result => result,
+            }
+        } else {
+            match define() {
+                Err(Error::NotFound) => delegate(),
+                result => result,
+            }
+        }
+    }
+
+    /// Returns a class path that finds the classes visible to `loader`, i.e., the ones
+    /// [`ClassLoaderHierarchy::load_class`] loads on its behalf.
+    ///
+    /// # Panics
+    /// Panics if `loader` is not in this hierarchy.
+    #[must_use]
+    pub fn class_path(&self, loader: LoaderId) -> LoaderClassPath<'_, P> {
+        assert!(loader.0 < self.loaders.len(), "Unknown class loader");
+        LoaderClassPath {
+            hierarchy: self,
+            loader,
+        }
+    }
+}
+
+/// The classes visible to a loader in a [`ClassLoaderHierarchy`], as a [`ClassPath`].
+///
+/// See [`ClassLoaderHierarchy::class_path`].
+#[derive(Debug)]
+pub struct LoaderClassPath<'h, P> {
+    hierarchy: &'h ClassLoaderHierarchy<P>,
+    loader: LoaderId,
+}
+
+impl<P: ClassPath> ClassPath for LoaderClassPath<'_, P> {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.hierarchy
+            .load_class(self.loader, binary_name)
+            .map(|it| it.class.clone())
+    }
+}
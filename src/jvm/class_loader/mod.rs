@@ -1,8 +1,15 @@
 //! Discovering and loading classes.
 
-use std::{borrow::Borrow, collections::HashMap, mem::transmute, ops::Deref, sync::RwLock};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    mem::transmute,
+    ops::Deref,
+    sync::{Mutex, RwLock},
+    thread,
+};
 
-use super::class::Class;
+use super::{class::Class, module::Module, parsing::ParseWarning};
 
 /// An error that can occur while loading a class.
 #[derive(thiserror::Error, Debug)]
@@ -16,6 +23,17 @@ pub enum Error {
     /// Error occurred while reading the class bytes or locating the class file.
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
+    /// The class is in a package that is not exported to the module loading it.
+    #[error("Class {class} in module {module} is not accessible")]
+    Inaccessible {
+        /// The binary name of the class.
+        class: String,
+        /// The name of the module containing the class.
+        module: String,
+    },
+    /// The class path does not support the operation, e.g., listing its classes.
+    #[error("The operation is not supported by the class path")]
+    Unsupported,
     /// Other error occurred.
     #[error("Cause: {0}")]
     Other(#[from] Box<dyn std::error::Error + Send + Sync>),
@@ -28,6 +46,76 @@ pub trait ClassPath {
     /// # Errors
     /// See [`Error`].
     fn find_class(&self, binary_name: &str) -> Result<Class, Error>;
+
+    /// Finds a class by its binary name, along with the warnings reported when parsing it in
+    /// lenient mode (see [`ParseOptions::with_lenient`](super::parsing::ParseOptions::with_lenient)).
+    /// The default implementation reports no warnings.
+    ///
+    /// # Errors
+    /// See [`Error`].
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        self.find_class(binary_name)
+            .map(|class| (class, Vec::new()))
+    }
+
+    /// Reads the module declaration (i.e., `module-info.class`) in the class path.
+    /// Returns `None` if the class path does not contain a named module.
+    ///
+    /// # Errors
+    /// See [`Error`].
+    fn module(&self) -> Result<Option<Module>, Error> {
+        match self.find_class("module-info") {
+            Ok(class) => Ok(class.module),
+            Err(Error::NotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Lists the binary names of all the classes in the class path.
+    ///
+    /// # Errors
+    /// - [`Error::Unsupported`] if the class path cannot list its classes.
+    /// - See [`Error`] for others.
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Loads all the classes in the class path in parallel.
+    /// Failing to load a class does not abort loading the others, so the result of loading each
+    /// class is returned, keyed by its binary name.
+    ///
+    /// # Errors
+    /// See [`ClassPath::class_names`].
+    fn load_all(&self) -> Result<BTreeMap<String, Result<Class, Error>>, Error>
+    where
+        Self: Sync,
+    {
+        let class_names: Vec<_> = self.class_names()?.into_iter().collect();
+        let parallelism = thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+        let chunk_size = class_names.len().div_ceil(parallelism).max(1);
+        let classes = Mutex::new(BTreeMap::new());
+        thread::scope(|scope| {
+            for chunk in class_names.chunks(chunk_size) {
+                let classes = &classes;
+                scope.spawn(move || {
+                    let loaded: Vec<_> = chunk
+                        .iter()
+                        .map(|name| (name.clone(), self.find_class(name)))
+                        .collect();
+                    classes
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .extend(loaded);
+                });
+            }
+        });
+        Ok(classes
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner))
+    }
 }
 
 impl<T> ClassPath for T
@@ -38,6 +126,21 @@ where
     fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
         self.deref().find_class(binary_name)
     }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        self.deref().find_class_with_warnings(binary_name)
+    }
+
+    fn module(&self) -> Result<Option<Module>, Error> {
+        self.deref().module()
+    }
+
+    fn class_names(&self) -> Result<BTreeSet<String>, Error> {
+        self.deref().class_names()
+    }
 }
 
 /// A class loader that can load classes from a list of class paths.
@@ -81,6 +184,8 @@ impl<P> ClassLoader<P> {
 }
 
 pub mod class_paths;
+pub mod hierarchy;
+pub mod module_path;
 
 /// A class loader that caches loaded classes.
 #[derive(Debug)]
@@ -0,0 +1,306 @@
+//! Loading classes from the module path.
+//!
+//! A [`ModulePath`] collects the modules available to an application.
+//! Resolving it from a set of root modules produces a [`ModuleGraph`], which records which
+//! modules each module reads according to their `requires` directives.
+//! Classes are then loaded on behalf of a module with [`ModuleGraph::class_path`], which only
+//! finds classes in the modules it reads and rejects classes in packages that are not exported to
+//! it.
+
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
+
+use crate::jvm::{
+    class::Class,
+    module::{self, Module, RequireFlags},
+    parsing::ParseWarning,
+};
+
+use super::{ClassPath, Error};
+
+/// An error that can occur while building or resolving a module path.
+#[derive(thiserror::Error, Debug)]
+pub enum ModuleError {
+    /// The class path does not contain a module declaration.
+    #[error("The class path does not contain a module declaration")]
+    NotModular,
+    /// Two modules on the module path have the same name.
+    #[error("Module {0} is defined more than once")]
+    DuplicateModule(String),
+    /// A root module is not on the module path.
+    #[error("Module {0} is not found")]
+    ModuleNotFound(String),
+    /// A module required by another module is not on the module path.
+    #[error("Module {module} requires {required}, which is not found")]
+    MissingDependency {
+        /// The name of the module that requires the missing module.
+        module: String,
+        /// The name of the missing module.
+        required: String,
+    },
+    /// Error occurred while reading a module declaration.
+    #[error("Fail to read the module declaration: {0}")]
+    Load(#[from] Error),
+}
+
+/// A set of modules with the class paths containing their classes.
+#[derive(Debug)]
+pub struct ModulePath<P> {
+    modules: BTreeMap<String, (Module, P)>,
+}
+
+impl<P> Default for ModulePath<P> {
+    fn default() -> Self {
+        Self {
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl<P: ClassPath> ModulePath<P> {
+    /// Creates an empty module path.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a module whose declaration is the `module-info.class` in `class_path`.
+    ///
+    /// # Errors
+    /// - [`ModuleError::NotModular`] if `class_path` does not contain a module declaration.
+    /// - [`ModuleError::DuplicateModule`] if a module with the same name is already added.
+    /// - [`ModuleError::Load`] if the module declaration cannot be read.
+    pub fn add(&mut self, class_path: P) -> Result<&Module, ModuleError> {
+        let module = class_path.module()?.ok_or(ModuleError::NotModular)?;
+        self.add_module(module, class_path)
+    }
+
+    /// Adds a module with the given declaration, whose classes are in `class_path`.
+    ///
+    /// # Errors
+    /// - [`ModuleError::DuplicateModule`] if a module with the same name is already added.
+    pub fn add_module(&mut self, module: Module, class_path: P) -> Result<&Module, ModuleError> {
+        match self.modules.entry(module.name.clone()) {
+            Entry::Occupied(_) => Err(ModuleError::DuplicateModule(module.name)),
+            Entry::Vacant(entry) => {
+                let (module, _) = entry.insert((module, class_path));
+                Ok(module)
+            }
+        }
+    }
+
+    /// Resolves the modules required by `roots`, directly or indirectly.
+    /// Modules that are not required are not included in the resulting graph.
+    ///
+    /// # Errors
+    /// - [`ModuleError::ModuleNotFound`] if a root module is not on the module path.
+    /// - [`ModuleError::MissingDependency`] if a required module is not on the module path.
+    pub fn resolve<'r>(
+        mut self,
+        roots: impl IntoIterator<Item = &'r str>,
+    ) -> Result<ModuleGraph<P>, ModuleError> {
+        let mut resolved = BTreeSet::new();
+        let mut worklist = Vec::new();
+        for root in roots {
+            if !self.modules.contains_key(root) {
+                return Err(ModuleError::ModuleNotFound(root.to_owned()));
+            }
+            worklist.push(root.to_owned());
+        }
+        while let Some(name) = worklist.pop() {
+            if !resolved.insert(name.clone()) {
+                continue;
+            }
+            let (module, _) = &self.modules[&name];
+            // Modules required with `static` are only read if they are resolved otherwise.
+            let requires = module
+                .requires
+                .iter()
+                .filter(|it| !it.flags.contains(RequireFlags::STATIC_PHASE));
+            for require in requires {
+                if !self.modules.contains_key(&require.module.name) {
+                    return Err(ModuleError::MissingDependency {
+                        module: name,
+                        required: require.module.name.clone(),
+                    });
+                }
+                worklist.push(require.module.name.clone());
+            }
+        }
+        self.modules.retain(|name, _| resolved.contains(name));
+
+        let modules = &self.modules;
+        let mut reads: BTreeMap<_, _> = modules
+            .iter()
+            .map(|(name, (module, _))| {
+                let mut reads = BTreeSet::new();
+                let mut worklist: Vec<_> = module
+                    .requires
+                    .iter()
+                    .map(|it| it.module.name.as_str())
+                    .filter(|it| modules.contains_key(*it))
+                    .collect();
+                // Reading a module implies reading the modules it requires transitively.
+                while let Some(read) = worklist.pop() {
+                    if reads.insert(read.to_owned()) {
+                        let (read, _) = &modules[read];
+                        worklist.extend(
+                            read.requires
+                                .iter()
+                                .filter(|it| it.flags.contains(RequireFlags::TRANSITIVE))
+                                .map(|it| it.module.name.as_str())
+                                .filter(|it| modules.contains_key(*it)),
+                        );
+                    }
+                }
+                reads.remove(name);
+                (name.clone(), reads)
+            })
+            .collect();
+        let modules = self
+            .modules
+            .into_iter()
+            .map(|(name, (descriptor, class_path))| {
+                let module = ResolvedModule {
+                    descriptor,
+                    class_path,
+                    reads: reads.remove(&name).unwrap_or_default(),
+                };
+                (name, module)
+            })
+            .collect();
+        Ok(ModuleGraph { modules })
+    }
+}
+
+#[derive(Debug)]
+struct ResolvedModule<P> {
+    descriptor: Module,
+    class_path: P,
+    reads: BTreeSet<String>,
+}
+
+/// The modules resolved from a [`ModulePath`] and the readability relation among them.
+#[derive(Debug)]
+pub struct ModuleGraph<P> {
+    modules: BTreeMap<String, ResolvedModule<P>>,
+}
+
+impl<P> ModuleGraph<P> {
+    /// Returns the declarations of the resolved modules.
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.modules.values().map(|it| &it.descriptor)
+    }
+
+    /// Returns the declaration of a resolved module.
+    #[must_use]
+    pub fn module(&self, name: &str) -> Option<&Module> {
+        self.modules.get(name).map(|it| &it.descriptor)
+    }
+
+    /// Returns the names of the modules read by `module`, excluding itself.
+    pub fn reads_of(&self, module: &str) -> impl Iterator<Item = &str> {
+        self.modules
+            .get(module)
+            .into_iter()
+            .flat_map(|it| it.reads.iter().map(String::as_str))
+    }
+
+    /// Checks whether `module` reads `other`.
+    /// A module always reads itself.
+    #[must_use]
+    pub fn reads(&self, module: &str, other: &str) -> bool {
+        self.modules
+            .get(module)
+            .is_some_and(|it| module == other || it.reads.contains(other))
+    }
+
+    /// Checks whether `module` exports `package` (e.g., `java/lang`) to `to`.
+    /// A module can always access its own packages.
+    #[must_use]
+    pub fn is_exported(&self, module: &str, package: &str, to: &str) -> bool {
+        module == to
+            || self.module(module).is_some_and(|it| {
+                it.exports.iter().any(|export| {
+                    export.package.binary_name == package
+                        && (export.to.is_empty() || export.to.iter().any(|it| it.name == to))
+                })
+            })
+    }
+
+    /// Checks whether `module` opens `package` (e.g., `java/lang`) to `to` for deep reflection.
+    #[must_use]
+    pub fn is_opened(&self, module: &str, package: &str, to: &str) -> bool {
+        module == to
+            || self.module(module).is_some_and(|it| {
+                it.flags.contains(module::Flags::OPEN)
+                    || it.opens.iter().any(|open| {
+                        open.package.binary_name == package
+                            && (open.to.is_empty() || open.to.iter().any(|it| it.name == to))
+                    })
+            })
+    }
+
+    /// Returns a class path finding the classes visible to the code in `module`.
+    #[must_use]
+    pub fn class_path(&self, module: &str) -> Option<ModuleClassPath<'_, P>> {
+        let (module, _) = self.modules.get_key_value(module)?;
+        Some(ModuleClassPath {
+            graph: self,
+            module,
+        })
+    }
+}
+
+/// The classes visible to the code in a module of a [`ModuleGraph`].
+///
+/// A class is found if it is in the module itself or in a module it reads.
+/// Classes in packages that are not exported to the module are reported as
+/// [`Error::Inaccessible`].
+#[derive(Debug)]
+pub struct ModuleClassPath<'g, P> {
+    graph: &'g ModuleGraph<P>,
+    module: &'g str,
+}
+
+impl<P: ClassPath> ClassPath for ModuleClassPath<'_, P> {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        self.find_class_with_warnings(binary_name)
+            .map(|(class, _)| class)
+    }
+
+    fn find_class_with_warnings(
+        &self,
+        binary_name: &str,
+    ) -> Result<(Class, Vec<ParseWarning>), Error> {
+        let Some((package, _)) = binary_name.rsplit_once('/') else {
+            // Named modules do not contain classes in the unnamed package.
+            return self.graph.modules[self.module]
+                .class_path
+                .find_class_with_warnings(binary_name);
+        };
+        let candidates = std::iter::once(self.module).chain(self.graph.reads_of(self.module));
+        for candidate in candidates {
+            match self.graph.modules[candidate]
+                .class_path
+                .find_class_with_warnings(binary_name)
+            {
+                Ok(found) if self.graph.is_exported(candidate, package, self.module) => {
+                    return Ok(found)
+                }
+                Ok(_) => {
+                    return Err(Error::Inaccessible {
+                        class: binary_name.to_owned(),
+                        module: candidate.to_owned(),
+                    })
+                }
+                Err(Error::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(Error::NotFound)
+    }
+
+    fn module(&self) -> Result<Option<Module>, Error> {
+        Ok(self.graph.module(self.module).cloned())
+    }
+}
@@ -6,8 +6,8 @@ use std::{
 
 use crate::{
     jvm::{
-        annotation::TypeAnnotation, constant_pool::ConstantPool, parsing::Error,
-        references::ClassRef,
+        annotation::TypeAnnotation, constant_pool::ConstantPool,
+        custom_attribute::CustomAttributes, parsing::Error, references::ClassRef,
     },
     macros::{malform, see_jvm_spec},
     types::field_type::FieldType,
@@ -39,6 +39,18 @@ pub struct MethodBody {
     pub runtime_invisible_type_annotations: Vec<TypeAnnotation>,
     /// Unrecognized JVM attributes.
     pub free_attributes: Vec<(String, Vec<u8>)>,
+    /// Attributes decoded by custom [`AttributeDecoder`](crate::jvm::parsing::AttributeDecoder)s.
+    pub custom_attributes: CustomAttributes,
+    /// The indices in the constant pool of the operands of the instructions (e.g., the
+    /// `Methodref` of an `invokevirtual`), keyed by the locations of the instructions.
+    /// It is only filled if
+    /// [`ParseOptions::with_retained_constant_pool`](crate::jvm::parsing::ParseOptions::with_retained_constant_pool)
+    /// is set.
+    ///
+    /// The indices are kept in a side table rather than in the operands of [`Instruction`]s,
+    /// whose operands are the resolved references so that equal instructions from different class
+    /// files compare equal and are independent of the layout of the constant pool.
+    pub constant_pool_indices: BTreeMap<ProgramCounter, u16>,
 }
 
 impl MethodBody {
@@ -47,6 +59,18 @@ impl MethodBody {
     pub fn instruction_at(&self, pc: ProgramCounter) -> Option<&Instruction> {
         self.instructions.get(&pc)
     }
+
+    /// Returns the line number in the source file of the instruction at the given program
+    /// counter, or `None` if the method body has no line number table covering it.
+    #[must_use]
+    pub fn line_number_of(&self, pc: ProgramCounter) -> Option<u16> {
+        self.line_number_table
+            .iter()
+            .flatten()
+            .filter(|it| it.start_pc <= pc)
+            .max_by_key(|it| it.start_pc)
+            .map(|it| it.line_number)
+    }
 }
 
 /// A list of instructions.
@@ -165,9 +189,11 @@ impl InstructionList<RawInstruction> {
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+
     use crate::jvm::code::{Instruction, InstructionList};
 
-    use super::MethodBody;
+    use super::{CustomAttributes, LineNumberTableEntry, MethodBody};
     use Instruction::*;
 
     #[test]
@@ -187,9 +213,44 @@ mod test {
             runtime_visible_type_annotations: vec![],
             runtime_invisible_type_annotations: vec![],
             free_attributes: vec![],
+            custom_attributes: CustomAttributes::default(),
+            constant_pool_indices: BTreeMap::new(),
         };
         assert_eq!(Some(&IConst0), body.instruction_at(1.into()));
     }
+
+    #[test]
+    fn line_number_of() {
+        let body = MethodBody {
+            instructions: InstructionList::from([
+                (0.into(), Nop),
+                (1.into(), Nop),
+                (2.into(), Nop),
+            ]),
+            max_stack: 0,
+            max_locals: 0,
+            exception_table: vec![],
+            line_number_table: Some(vec![
+                LineNumberTableEntry {
+                    start_pc: 2.into(),
+                    line_number: 12,
+                },
+                LineNumberTableEntry {
+                    start_pc: 0.into(),
+                    line_number: 10,
+                },
+            ]),
+            local_variable_table: None,
+            stack_map_table: None,
+            runtime_visible_type_annotations: vec![],
+            runtime_invisible_type_annotations: vec![],
+            free_attributes: vec![],
+            custom_attributes: CustomAttributes::default(),
+            constant_pool_indices: BTreeMap::new(),
+        };
+        assert_eq!(body.line_number_of(1.into()), Some(10));
+        assert_eq!(body.line_number_of(2.into()), Some(12));
+    }
 }
 
 /// An entry in the exception table.
@@ -284,7 +345,7 @@ pub struct LocalVariableTableEntry {
 
 /// The type of a value in the stack map table for verification.
 #[doc = see_jvm_spec!(4, 7, 4)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum VerificationType {
     /// Indicates that the local variable has the verification type `top`.
     TopVariable,
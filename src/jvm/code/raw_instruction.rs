@@ -353,6 +353,31 @@ impl RawInstruction {
         self.discriminant()
     }
 
+    /// Gets the index in the constant pool of the operand of the instruction, if any.
+    #[must_use]
+    pub fn constant_pool_index(&self) -> Option<u16> {
+        #[allow(clippy::enum_glob_use)]
+        use RawInstruction::*;
+        match *self {
+            Ldc { const_index } => Some(u16::from(const_index)),
+            LdcW { const_index } | Ldc2W { const_index } => Some(const_index),
+            GetStatic { field_ref_index }
+            | PutStatic { field_ref_index }
+            | GetField { field_ref_index }
+            | PutField { field_ref_index } => Some(field_ref_index),
+            InvokeVirtual { method_index }
+            | InvokeSpecial { method_index }
+            | InvokeStatic { method_index }
+            | InvokeInterface { method_index, .. } => Some(method_index),
+            InvokeDynamic { dynamic_index } => Some(dynamic_index),
+            New { index } | ANewArray { index } | MultiANewArray { index, .. } => Some(index),
+            CheckCast { target_type_index } | InstanceOf { target_type_index } => {
+                Some(target_type_index)
+            }
+            _ => None,
+        }
+    }
+
     const fn discriminant(&self) -> u8 {
         // SAFETY: Because `Self` is marked `repr(u8)`, its layout is a `repr(C)` `union`
         // between `repr(C)` structs, each of which has the `u8` discriminant as its first
@@ -45,6 +45,30 @@ impl ConstantPool {
         Ok(Self { inner })
     }
 
+    /// Returns the number of slots in the constant pool, i.e., `constant_pool_count` in the class
+    /// file, including the unusable ones.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Checks whether the constant pool has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.len() <= 1
+    }
+
+    /// Returns the entries with their indices.
+    pub fn entries(&self) -> impl Iterator<Item = (u16, &Entry)> {
+        self.inner
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                Slot::Entry(entry) => u16::try_from(index).ok().map(|index| (index, entry)),
+                Slot::Padding => None,
+            })
+    }
+
     /// Gets the constant pool entry at the given index.
     /// # Errors
     /// - [`BadConstantPoolIndex`] if `index` does not point to a valid entry.
@@ -0,0 +1,101 @@
+//! Attributes decoded by user-provided decoders.
+//!
+//! The JVM specification allows compilers and tools to define their own attributes, e.g., the
+//! `ScalaSig` attribute written by the Scala compiler or markers written by instrumentation agents.
+//! Such attributes are kept as raw bytes in the `free_attributes` of an element unless a decoder
+//! is registered for them in [`ParseOptions`](crate::jvm::parsing::ParseOptions), in which case
+//! the decoded values are stored in [`CustomAttributes`].
+
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+/// A value decoded from a custom attribute.
+///
+/// It is implemented for every type that can be shared across threads, so decoders can return
+/// their own types.
+pub trait CustomAttribute: Any + Debug + Send + Sync {
+    /// Returns the value as [`Any`] for downcasting.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + Debug + Send + Sync> CustomAttribute for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The custom attributes of a class, a field, a method, or a method body, keyed by their names.
+#[derive(Clone, Default)]
+pub struct CustomAttributes {
+    attributes: BTreeMap<String, Arc<dyn CustomAttribute>>,
+}
+
+impl CustomAttributes {
+    /// Gets the value of the attribute with the given name if it is decoded as a `T`.
+    #[must_use]
+    pub fn get<T: Any>(&self, name: &str) -> Option<&T> {
+        self.attributes
+            .get(name)
+            .and_then(|it| it.as_ref().as_any().downcast_ref())
+    }
+
+    /// Gets the value of the attribute with the given name regardless of its type.
+    #[must_use]
+    pub fn get_dyn(&self, name: &str) -> Option<&dyn CustomAttribute> {
+        self.attributes.get(name).map(AsRef::as_ref)
+    }
+
+    /// Adds an attribute, replacing the one with the same name if it exists.
+    pub fn insert<T: CustomAttribute>(&mut self, name: impl Into<String>, value: T) {
+        self.attributes.insert(name.into(), Arc::new(value));
+    }
+
+    pub(crate) fn insert_shared(&mut self, name: String, value: Arc<dyn CustomAttribute>) {
+        self.attributes.insert(name, value);
+    }
+
+    /// Returns the names of the attributes.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.attributes.keys().map(String::as_str)
+    }
+
+    /// Returns the number of attributes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    /// Checks whether there are no attributes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+impl Debug for CustomAttributes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(&self.attributes).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Marker(u16);
+
+    #[test]
+    fn downcast() {
+        let mut attributes = CustomAttributes::default();
+        attributes.insert("Marker", Marker(42));
+        assert_eq!(attributes.get::<Marker>("Marker"), Some(&Marker(42)));
+        assert_eq!(attributes.get::<u16>("Marker"), None);
+        assert!(attributes.get_dyn("Missing").is_none());
+        assert_eq!(attributes.names().collect::<Vec<_>>(), vec!["Marker"]);
+    }
+}
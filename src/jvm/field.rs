@@ -12,6 +12,7 @@ use crate::{
 use super::{
     annotation::{Annotation, TypeAnnotation},
     class::MethodHandle,
+    custom_attribute::CustomAttributes,
     references::{ClassRef, FieldRef},
 };
 
@@ -45,6 +46,8 @@ pub struct Field {
     pub runtime_invisible_type_annotations: Vec<TypeAnnotation>,
     /// Unrecognized JVM attributes.
     pub free_attributes: Vec<(String, Vec<u8>)>,
+    /// Attributes decoded by custom [`AttributeDecoder`](crate::jvm::parsing::AttributeDecoder)s.
+    pub custom_attributes: CustomAttributes,
 }
 
 impl Field {
@@ -0,0 +1,293 @@
+//! Interning of names and references.
+//!
+//! Each [`ClassRef`], [`FieldRef`], and [`MethodRef`] owns its strings, so analyses that hold
+//! many references to the same elements keep many copies of them.
+//! An [`Interner`] stores each distinct string once and hands out [`Symbol`]s, which are `Copy`
+//! and compared and hashed in constant time.
+//! The interned counterparts of the references, e.g., [`InternedClassRef`], are built from
+//! symbols and can be converted back with the interner that created them.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use crate::types::{field_type::FieldType, method_descriptor::MethodDescriptor};
+
+use super::references::{ClassRef, FieldRef, MethodRef};
+
+/// A handle to a string in an [`Interner`].
+///
+/// Symbols are only meaningful to the interner that creates them.
+/// They are ordered by the time they are interned rather than by the strings they represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Debug, Default)]
+struct Strings {
+    symbols: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+/// A thread-safe table of interned strings.
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: RwLock<Strings>,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns a string, returning the same symbol for equal strings.
+    ///
+    /// # Errors
+    /// - [`TooManySymbols`] if the interner already holds `u32::MAX` strings.
+    pub fn intern(&self, string: &str) -> Result<Symbol, TooManySymbols> {
+        if let Some(symbol) = self.get(string) {
+            return Ok(symbol);
+        }
+        let mut strings = self.strings.write().unwrap_or_else(PoisonError::into_inner);
+        // The string may have been interned before we get the write lock.
+        if let Some(symbol) = strings.symbols.get(string) {
+            return Ok(*symbol);
+        }
+        let symbol = u32::try_from(strings.strings.len())
+            .ok()
+            .filter(|it| *it < u32::MAX)
+            .map(Symbol)
+            .ok_or(TooManySymbols)?;
+        let string: Arc<str> = Arc::from(string);
+        strings.strings.push(Arc::clone(&string));
+        strings.symbols.insert(string, symbol);
+        Ok(symbol)
+    }
+
+    /// Gets the symbol of a string if it is interned.
+    #[must_use]
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        self.strings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .symbols
+            .get(string)
+            .copied()
+    }
+
+    /// Gets the string represented by a symbol.
+    /// Returns `None` if the symbol is not created by this interner.
+    #[must_use]
+    pub fn resolve(&self, symbol: Symbol) -> Option<Arc<str>> {
+        let strings = self.strings.read().unwrap_or_else(PoisonError::into_inner);
+        let index = usize::try_from(symbol.0).ok()?;
+        strings.strings.get(index).map(Arc::clone)
+    }
+
+    /// Returns the number of interned strings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.strings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .strings
+            .len()
+    }
+
+    /// Checks whether no strings are interned.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Interns a class reference.
+    ///
+    /// # Errors
+    /// See [`Interner::intern`].
+    pub fn class_ref(&self, class_ref: &ClassRef) -> Result<InternedClassRef, TooManySymbols> {
+        Ok(InternedClassRef {
+            binary_name: self.intern(&class_ref.binary_name)?,
+        })
+    }
+
+    /// Interns a field reference.
+    ///
+    /// # Errors
+    /// See [`Interner::intern`].
+    pub fn field_ref(&self, field_ref: &FieldRef) -> Result<InternedFieldRef, TooManySymbols> {
+        Ok(InternedFieldRef {
+            owner: self.class_ref(&field_ref.owner)?,
+            name: self.intern(&field_ref.name)?,
+            field_type: self.intern(&field_ref.field_type.descriptor())?,
+        })
+    }
+
+    /// Interns a method reference.
+    ///
+    /// # Errors
+    /// See [`Interner::intern`].
+    pub fn method_ref(&self, method_ref: &MethodRef) -> Result<InternedMethodRef, TooManySymbols> {
+        Ok(InternedMethodRef {
+            owner: self.class_ref(&method_ref.owner)?,
+            name: self.intern(&method_ref.name)?,
+            descriptor: self.intern(&method_ref.descriptor.to_string())?,
+        })
+    }
+
+    /// Gets the interned counterpart of a class reference without interning it.
+    /// Returns `None` if the binary name is not interned.
+    #[must_use]
+    pub fn get_class_ref(&self, class_ref: &ClassRef) -> Option<InternedClassRef> {
+        self.get(&class_ref.binary_name)
+            .map(|binary_name| InternedClassRef { binary_name })
+    }
+
+    /// Gets the interned counterpart of a field reference without interning it.
+    /// Returns `None` if any part of the reference is not interned.
+    #[must_use]
+    pub fn get_field_ref(&self, field_ref: &FieldRef) -> Option<InternedFieldRef> {
+        Some(InternedFieldRef {
+            owner: self.get_class_ref(&field_ref.owner)?,
+            name: self.get(&field_ref.name)?,
+            field_type: self.get(&field_ref.field_type.descriptor())?,
+        })
+    }
+
+    /// Gets the interned counterpart of a method reference without interning it.
+    /// Returns `None` if any part of the reference is not interned.
+    #[must_use]
+    pub fn get_method_ref(&self, method_ref: &MethodRef) -> Option<InternedMethodRef> {
+        Some(InternedMethodRef {
+            owner: self.get_class_ref(&method_ref.owner)?,
+            name: self.get(&method_ref.name)?,
+            descriptor: self.get(&method_ref.descriptor.to_string())?,
+        })
+    }
+
+    /// Converts an interned class reference back to a [`ClassRef`].
+    /// Returns `None` if the reference is not created by this interner.
+    #[must_use]
+    pub fn resolve_class_ref(&self, class_ref: InternedClassRef) -> Option<ClassRef> {
+        self.resolve(class_ref.binary_name)
+            .map(|it| ClassRef::new(&*it))
+    }
+
+    /// Converts an interned field reference back to a [`FieldRef`].
+    /// Returns `None` if the reference is not created by this interner.
+    #[must_use]
+    pub fn resolve_field_ref(&self, field_ref: InternedFieldRef) -> Option<FieldRef> {
+        // A symbol from another interner may resolve to a string that is not a descriptor.
+        let field_type: FieldType = self.resolve(field_ref.field_type)?.parse().ok()?;
+        Some(FieldRef {
+            owner: self.resolve_class_ref(field_ref.owner)?,
+            name: self.resolve(field_ref.name)?.to_string(),
+            field_type,
+        })
+    }
+
+    /// Converts an interned method reference back to a [`MethodRef`].
+    /// Returns `None` if the reference is not created by this interner.
+    #[must_use]
+    pub fn resolve_method_ref(&self, method_ref: InternedMethodRef) -> Option<MethodRef> {
+        let descriptor: MethodDescriptor = self.resolve(method_ref.descriptor)?.parse().ok()?;
+        Some(MethodRef {
+            owner: self.resolve_class_ref(method_ref.owner)?,
+            name: self.resolve(method_ref.name)?.to_string(),
+            descriptor,
+        })
+    }
+}
+
+/// An error indicating that an [`Interner`] cannot hold more strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("The interner cannot hold more than {} strings", u32::MAX)]
+pub struct TooManySymbols;
+
+/// An interned [`ClassRef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternedClassRef {
+    /// The binary name of the class.
+    pub binary_name: Symbol,
+}
+
+/// An interned [`FieldRef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternedFieldRef {
+    /// The class containing the field.
+    pub owner: InternedClassRef,
+    /// The name of the field.
+    pub name: Symbol,
+    /// The descriptor of the field type.
+    pub field_type: Symbol,
+}
+
+/// An interned [`MethodRef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternedMethodRef {
+    /// The class containing the method.
+    pub owner: InternedClassRef,
+    /// The name of the method.
+    pub name: Symbol,
+    /// The descriptor of the method.
+    pub descriptor: Symbol,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_strings() {
+        let interner = Interner::new();
+        let string = interner.intern("java/lang/String").unwrap();
+        let object = interner.intern("java/lang/Object").unwrap();
+        assert_ne!(string, object);
+        assert_eq!(interner.intern("java/lang/String"), Ok(string));
+        assert_eq!(interner.get("java/lang/Object"), Some(object));
+        assert_eq!(interner.get("java/lang/Integer"), None);
+        assert_eq!(
+            interner.resolve(string).as_deref(),
+            Some("java/lang/String")
+        );
+        assert_eq!(interner.len(), 2);
+
+        let other = Interner::new();
+        assert_eq!(other.resolve(string), None);
+    }
+
+    #[test]
+    fn intern_refs() {
+        let interner = Interner::new();
+        let method_ref = MethodRef {
+            owner: ClassRef::new("java/lang/String"),
+            name: "valueOf".to_owned(),
+            descriptor: "(I)Ljava/lang/String;".parse().unwrap(),
+        };
+        let field_ref = FieldRef {
+            owner: ClassRef::new("java/lang/String"),
+            name: "value".to_owned(),
+            field_type: "[B".parse().unwrap(),
+        };
+        assert_eq!(interner.get_field_ref(&field_ref), None);
+        let interned_method = interner.method_ref(&method_ref).unwrap();
+        let interned_field = interner.field_ref(&field_ref).unwrap();
+        assert_eq!(interned_method.owner, interned_field.owner);
+        assert_eq!(interner.method_ref(&method_ref), Ok(interned_method));
+        assert_eq!(interner.get_method_ref(&method_ref), Some(interned_method));
+        assert_eq!(interner.get_field_ref(&field_ref), Some(interned_field));
+        assert_eq!(
+            interner.resolve_method_ref(interned_method),
+            Some(method_ref)
+        );
+        assert_eq!(interner.resolve_field_ref(interned_field), Some(field_ref));
+
+        // The descriptor symbol of the method refers to a different string in another interner.
+        let other = Interner::new();
+        for name in ["a", "b", "c", "d"] {
+            other.intern(name).unwrap();
+        }
+        assert_eq!(other.resolve_method_ref(interned_method), None);
+    }
+}
@@ -0,0 +1,1485 @@
+//! Decoding of the `kotlin.Metadata` annotation into the model in [`super`].
+
+use std::cell::Cell;
+
+use prost::Message;
+
+use crate::jvm::{
+    annotation::{Annotation, ElementValue},
+    field::{ConstantValue, JavaString},
+};
+
+use super::{
+    proto, ClassFlags, ClassKind, ClassMetadata, Classifier, Constructor, Function, FunctionFlags,
+    JvmSignature, KotlinMetadata, KotlinMetadataError, KotlinType, MemberKind, MetadataKind,
+    Modality, PackageMetadata, Property, PropertyFlags, TypeArgument, TypeParameter,
+    ValueParameter, ValueParameterFlags, Variance, Visibility,
+};
+
+/// The marker at the start of `d1` when the bytes are stored as chars in `0..=255`.
+const UTF8_MODE_MARKER: char = '\u{0}';
+/// The optional marker at the start of `d1` when the bytes are packed into 7 bits.
+const BIT_7_MODE_MARKER: char = '\u{1}';
+/// The maximum depth of nested types, which guards against cycles in malformed type tables.
+const MAX_TYPE_DEPTH: usize = 64;
+/// The maximum number of types decoded from an annotation, which guards against types in
+/// malformed type tables referring to each other so many times that the model grows
+/// exponentially.
+const MAX_TYPE_COUNT: usize = 1 << 18;
+
+/// The strings that records can refer to by index instead of storing them in `d2`.
+const PREDEFINED_STRINGS: [&str; 44] = [
+    "kotlin/Any",
+    "kotlin/Nothing",
+    "kotlin/Unit",
+    "kotlin/Throwable",
+    "kotlin/Number",
+    "kotlin/Byte",
+    "kotlin/Double",
+    "kotlin/Float",
+    "kotlin/Int",
+    "kotlin/Long",
+    "kotlin/Short",
+    "kotlin/Boolean",
+    "kotlin/Char",
+    "kotlin/CharSequence",
+    "kotlin/String",
+    "kotlin/Comparable",
+    "kotlin/Enum",
+    "kotlin/Array",
+    "kotlin/ByteArray",
+    "kotlin/DoubleArray",
+    "kotlin/FloatArray",
+    "kotlin/IntArray",
+    "kotlin/LongArray",
+    "kotlin/ShortArray",
+    "kotlin/BooleanArray",
+    "kotlin/CharArray",
+    "kotlin/Cloneable",
+    "kotlin/Annotation",
+    "kotlin/collections/Iterable",
+    "kotlin/collections/MutableIterable",
+    "kotlin/collections/Collection",
+    "kotlin/collections/MutableCollection",
+    "kotlin/collections/List",
+    "kotlin/collections/MutableList",
+    "kotlin/collections/Set",
+    "kotlin/collections/MutableSet",
+    "kotlin/collections/Map",
+    "kotlin/collections/MutableMap",
+    "kotlin/collections/Map.Entry",
+    "kotlin/collections/MutableMap.MutableEntry",
+    "kotlin/collections/Iterator",
+    "kotlin/collections/MutableIterator",
+    "kotlin/collections/ListIterator",
+    "kotlin/collections/MutableListIterator",
+];
+
+pub(super) fn decode_metadata(
+    annotation: &Annotation,
+) -> Result<KotlinMetadata, KotlinMetadataError> {
+    let elements = Elements(annotation);
+    let version = elements.array("mv", int_value)?;
+    let d1 = elements.array("d1", string_value)?;
+    let d2 = elements.array("d2", string_value)?;
+    let package_name = elements.string("pn")?.filter(|it| !it.is_empty());
+    let kind = match elements.int("k")?.unwrap_or(1) {
+        1 => {
+            let (decoder, class) = Decoder::read::<proto::Class>(&d1, &d2)?;
+            MetadataKind::Class(decoder.class(&class)?)
+        }
+        2 => {
+            let (decoder, package) = Decoder::read::<proto::Package>(&d1, &d2)?;
+            MetadataKind::FileFacade(decoder.package(&package)?)
+        }
+        3 if d1.is_empty() => MetadataKind::SyntheticClass(None),
+        3 => {
+            let (decoder, function) = Decoder::read::<proto::Function>(&d1, &d2)?;
+            let types = TypeTable::default();
+            let function = decoder.function(&function, &Scope::new(&types))?;
+            MetadataKind::SyntheticClass(Some(function))
+        }
+        4 => MetadataKind::MultiFileClassFacade {
+            part_class_names: d1,
+        },
+        5 => {
+            let (decoder, package) = Decoder::read::<proto::Package>(&d1, &d2)?;
+            MetadataKind::MultiFileClassPart {
+                package: decoder.package(&package)?,
+                facade_class_name: elements
+                    .string("xs")?
+                    .ok_or(KotlinMetadataError::MalformedElement("xs"))?,
+            }
+        }
+        kind => return Err(KotlinMetadataError::UnsupportedKind(kind)),
+    };
+    Ok(KotlinMetadata {
+        version,
+        package_name,
+        kind,
+    })
+}
+
+/// The element-value pairs of the annotation.
+struct Elements<'a>(&'a Annotation);
+
+impl Elements<'_> {
+    fn get(&self, name: &str) -> Option<&ElementValue> {
+        self.0
+            .element_value_pairs
+            .iter()
+            .find_map(|(key, value)| (key == name).then_some(value))
+    }
+
+    fn int(&self, name: &'static str) -> Result<Option<i32>, KotlinMetadataError> {
+        self.get(name)
+            .map(|it| int_value(it).ok_or(KotlinMetadataError::MalformedElement(name)))
+            .transpose()
+    }
+
+    fn string(&self, name: &'static str) -> Result<Option<String>, KotlinMetadataError> {
+        self.get(name)
+            .map(|it| string_value(it).ok_or(KotlinMetadataError::MalformedElement(name)))
+            .transpose()
+    }
+
+    fn array<T>(
+        &self,
+        name: &'static str,
+        item: impl Fn(&ElementValue) -> Option<T>,
+    ) -> Result<Vec<T>, KotlinMetadataError> {
+        match self.get(name) {
+            None => Ok(Vec::new()),
+            Some(ElementValue::Array(items)) => items
+                .iter()
+                .map(|it| item(it).ok_or(KotlinMetadataError::MalformedElement(name)))
+                .collect(),
+            Some(_) => Err(KotlinMetadataError::MalformedElement(name)),
+        }
+    }
+}
+
+fn int_value(value: &ElementValue) -> Option<i32> {
+    match value {
+        ElementValue::Primitive(_, ConstantValue::Integer(it)) => Some(*it),
+        _ => None,
+    }
+}
+
+fn string_value(value: &ElementValue) -> Option<String> {
+    match value {
+        ElementValue::String(ConstantValue::String(JavaString::Utf8(it))) => Some(it.clone()),
+        _ => None,
+    }
+}
+
+/// Restores the bytes stored in the strings of `d1`.
+fn decode_bytes(d1: &[String]) -> Result<Vec<u8>, KotlinMetadataError> {
+    let marker = d1.first().and_then(|it| it.chars().next());
+    let mut chars = d1.iter().flat_map(|it| it.chars());
+    if marker == Some(UTF8_MODE_MARKER) {
+        chars.next();
+        return chars.map(char_to_byte).collect();
+    }
+    if marker == Some(BIT_7_MODE_MARKER) {
+        chars.next();
+    }
+    let packed = chars
+        .map(|it| char_to_byte(it).map(|b| b.wrapping_add(0x7F) & 0x7F))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(decode_7_to_8(&packed))
+}
+
+fn char_to_byte(c: char) -> Result<u8, KotlinMetadataError> {
+    u8::try_from(u32::from(c)).map_err(|_| KotlinMetadataError::InvalidEncoding)
+}
+
+/// Unpacks bytes whose bits are stored in the lower 7 bits of each byte, least significant first.
+fn decode_7_to_8(data: &[u8]) -> Vec<u8> {
+    let length = 7 * data.len() / 8;
+    let mut result = Vec::with_capacity(length);
+    let mut index = 0;
+    let mut bit = 0;
+    for _ in 0..length {
+        let low = data[index] >> bit;
+        index += 1;
+        let high = (data[index] & ((1 << (bit + 1)) - 1)) << (7 - bit);
+        result.push(low | high);
+        if bit == 6 {
+            index += 1;
+            bit = 0;
+        } else {
+            bit += 1;
+        }
+    }
+    result
+}
+
+/// Resolves the indices of strings in the declarations.
+struct NameResolver<'a> {
+    table: proto::StringTableTypes,
+    /// The exclusive end of the strings each record applies to, since a record applies to the
+    /// `range` strings following those of the previous records.
+    record_ends: Vec<usize>,
+    strings: &'a [String],
+}
+
+impl<'a> NameResolver<'a> {
+    fn new(table: proto::StringTableTypes, strings: &'a [String]) -> Self {
+        let record_ends = table
+            .record
+            .iter()
+            .scan(0usize, |end, record| {
+                let range = usize::try_from(record.range()).unwrap_or_default();
+                *end = end.saturating_add(range);
+                Some(*end)
+            })
+            .collect();
+        Self {
+            table,
+            record_ends,
+            strings,
+        }
+    }
+
+    fn get(&self, index: i32) -> Result<String, KotlinMetadataError> {
+        let invalid_index = || KotlinMetadataError::InvalidStringIndex(index);
+        let position = usize::try_from(index).map_err(|_| invalid_index())?;
+        let d2_string = || {
+            self.strings
+                .get(position)
+                .cloned()
+                .ok_or_else(invalid_index)
+        };
+        let record_index = self.record_ends.partition_point(|end| *end <= position);
+        let Some(record) = self.table.record.get(record_index) else {
+            return d2_string();
+        };
+        let predefined = record
+            .predefined_index
+            .and_then(|it| usize::try_from(it).ok())
+            .and_then(|it| PREDEFINED_STRINGS.get(it));
+        let mut string = match (&record.string, predefined) {
+            (Some(string), _) => string.clone(),
+            (None, Some(predefined)) => (*predefined).to_owned(),
+            (None, None) => d2_string()?,
+        };
+        if let [begin, end, ..] = record.substring_index[..] {
+            if let (Ok(begin), Ok(end)) = (usize::try_from(begin), usize::try_from(end)) {
+                if begin <= end && end <= string.chars().count() {
+                    string = string.chars().skip(begin).take(end - begin).collect();
+                }
+            }
+        }
+        if let [from, to, ..] = record.replace_char[..] {
+            let to_char = |it: i32| u32::try_from(it).ok().and_then(char::from_u32);
+            if let (Some(from), Some(to)) = (to_char(from), to_char(to)) {
+                string = string.replace(from, to.encode_utf8(&mut [0; 4]));
+            }
+        }
+        match record.operation() {
+            // `INTERNAL_TO_CLASS_ID`, e.g., `kotlin/Map$Entry` to `kotlin/Map.Entry`
+            1 => Ok(string.replace('$', ".")),
+            // `DESC_TO_CLASS_ID`, e.g., `Lkotlin/Map$Entry;` to `kotlin/Map.Entry`
+            2 => {
+                let mut chars = string.chars();
+                if string.chars().count() >= 2 {
+                    chars.next();
+                    chars.next_back();
+                }
+                Ok(chars.as_str().replace('$', "."))
+            }
+            _ => Ok(string),
+        }
+    }
+}
+
+/// The types referred by their indices, with the nullability applied.
+#[derive(Default)]
+struct TypeTable(Vec<proto::Type>);
+
+impl TypeTable {
+    fn new(table: Option<&proto::TypeTable>) -> Self {
+        let Some(table) = table else {
+            return Self::default();
+        };
+        let first_nullable = usize::try_from(table.first_nullable()).ok();
+        let types = table
+            .r#type
+            .iter()
+            .enumerate()
+            .map(|(index, it)| {
+                let mut it = it.clone();
+                if first_nullable.is_some_and(|first| index >= first) {
+                    it.nullable = Some(true);
+                }
+                it
+            })
+            .collect();
+        Self(types)
+    }
+
+    fn get(&self, index: i32) -> Result<&proto::Type, KotlinMetadataError> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|it| self.0.get(it))
+            .ok_or(KotlinMetadataError::InvalidTypeIndex(index))
+    }
+}
+
+/// The type table and the type parameters that the types of a declaration can refer to.
+struct Scope<'s> {
+    types: &'s TypeTable,
+    /// The names and the identifiers of the type parameters, from the outermost declaration.
+    type_parameters: Vec<(String, i32)>,
+}
+
+impl<'s> Scope<'s> {
+    const fn new(types: &'s TypeTable) -> Self {
+        Self {
+            types,
+            type_parameters: Vec::new(),
+        }
+    }
+
+    /// Finds the innermost type parameter with the given name.
+    fn type_parameter_id(&self, name: &str) -> Option<i32> {
+        self.type_parameters
+            .iter()
+            .rev()
+            .find_map(|(it, id)| (it == name).then_some(*id))
+    }
+}
+
+/// Converts the declarations in the protobuf messages into the model.
+struct Decoder<'a> {
+    names: NameResolver<'a>,
+    /// The number of types decoded so far.
+    type_count: Cell<usize>,
+}
+
+impl<'a> Decoder<'a> {
+    /// Reads the string table followed by a message of type `M` from `d1`.
+    fn read<M: Message + Default>(
+        d1: &[String],
+        d2: &'a [String],
+    ) -> Result<(Self, M), KotlinMetadataError> {
+        let bytes = decode_bytes(d1)?;
+        let mut buffer = bytes.as_slice();
+        let table = proto::StringTableTypes::decode_length_delimited(&mut buffer)?;
+        let message = M::decode(buffer)?;
+        let decoder = Self {
+            names: NameResolver::new(table, d2),
+            type_count: Cell::new(0),
+        };
+        Ok((decoder, message))
+    }
+
+    fn name(&self, index: Option<i32>, field: &'static str) -> Result<String, KotlinMetadataError> {
+        self.names
+            .get(index.ok_or(KotlinMetadataError::MissingField(field))?)
+    }
+
+    fn names(&self, indices: &[i32]) -> Result<Vec<String>, KotlinMetadataError> {
+        indices.iter().map(|it| self.names.get(*it)).collect()
+    }
+
+    /// Creates the scope of a declaration with the given type table and type parameters.
+    fn scope<'s>(
+        &self,
+        outer: &Scope<'_>,
+        types: &'s TypeTable,
+        parameters: &[proto::TypeParameter],
+    ) -> Result<Scope<'s>, KotlinMetadataError> {
+        let mut type_parameters = outer.type_parameters.clone();
+        for parameter in parameters {
+            let id = parameter
+                .id
+                .ok_or(KotlinMetadataError::MissingField("id"))?;
+            type_parameters.push((self.name(parameter.name, "name")?, id));
+        }
+        Ok(Scope {
+            types,
+            type_parameters,
+        })
+    }
+
+    fn class(&self, class: &proto::Class) -> Result<ClassMetadata, KotlinMetadataError> {
+        let types = TypeTable::new(class.type_table.as_ref());
+        let scope = self.scope(&Scope::new(&types), &types, &class.type_parameter)?;
+        let flags = flag_bits(class.flags());
+        let kind = match (flags >> 6) & 0b111 {
+            0 => ClassKind::Class,
+            1 => ClassKind::Interface,
+            2 => ClassKind::EnumClass,
+            3 => ClassKind::EnumEntry,
+            4 => ClassKind::AnnotationClass,
+            5 => ClassKind::Object,
+            6 => ClassKind::CompanionObject,
+            _ => return Err(KotlinMetadataError::InvalidFlags(flags)),
+        };
+        let supertypes = class
+            .supertype
+            .iter()
+            .map(|it| self.kotlin_type(it, &scope, 0))
+            .chain(
+                class
+                    .supertype_id
+                    .iter()
+                    .map(|it| self.kotlin_type(types.get(*it)?, &scope, 0)),
+            )
+            .collect::<Result<_, _>>()?;
+        let enum_entries = class
+            .enum_entry
+            .iter()
+            .map(|it| self.name(it.name, "name"))
+            .collect::<Result<_, _>>()?;
+        Ok(ClassMetadata {
+            name: self.name(class.fq_name, "fq_name")?,
+            flags: ClassFlags::from_bits_truncate(flags),
+            visibility: visibility(flags)?,
+            modality: modality(flags),
+            kind,
+            companion_object: class
+                .companion_object_name
+                .map(|it| self.names.get(it))
+                .transpose()?,
+            type_parameters: self.type_parameters(&class.type_parameter, &scope)?,
+            supertypes,
+            nested_classes: self.names(&class.nested_class_name)?,
+            constructors: class
+                .constructor
+                .iter()
+                .map(|it| self.constructor(it, &scope))
+                .collect::<Result<_, _>>()?,
+            functions: class
+                .function
+                .iter()
+                .map(|it| self.function(it, &scope))
+                .collect::<Result<_, _>>()?,
+            properties: class
+                .property
+                .iter()
+                .map(|it| self.property(it, &scope))
+                .collect::<Result<_, _>>()?,
+            enum_entries,
+            sealed_subclasses: self.names(&class.sealed_subclass_fq_name)?,
+        })
+    }
+
+    fn package(&self, package: &proto::Package) -> Result<PackageMetadata, KotlinMetadataError> {
+        let types = TypeTable::new(package.type_table.as_ref());
+        let scope = Scope::new(&types);
+        Ok(PackageMetadata {
+            functions: package
+                .function
+                .iter()
+                .map(|it| self.function(it, &scope))
+                .collect::<Result<_, _>>()?,
+            properties: package
+                .property
+                .iter()
+                .map(|it| self.property(it, &scope))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn constructor(
+        &self,
+        constructor: &proto::Constructor,
+        scope: &Scope<'_>,
+    ) -> Result<Constructor, KotlinMetadataError> {
+        let flags = flag_bits(constructor.flags());
+        let value_parameters = self.value_parameters(&constructor.value_parameter, scope)?;
+        let signature = constructor.signature.as_ref();
+        let jvm_signature = self.jvm_signature(
+            signature.and_then(|it| it.name),
+            signature.and_then(|it| it.desc),
+            || "<init>".to_owned(),
+            || method_descriptor(value_parameters.iter().map(|it| &it.parameter_type), None),
+        )?;
+        Ok(Constructor {
+            visibility: visibility(flags)?,
+            is_secondary: flags & (1 << 4) != 0,
+            value_parameters,
+            jvm_signature,
+        })
+    }
+
+    fn function(
+        &self,
+        function: &proto::Function,
+        scope: &Scope<'_>,
+    ) -> Result<Function, KotlinMetadataError> {
+        let own_types;
+        let types = if function.type_table.is_some() {
+            own_types = TypeTable::new(function.type_table.as_ref());
+            &own_types
+        } else {
+            scope.types
+        };
+        let scope = self.scope(scope, types, &function.type_parameter)?;
+        let flags = member_flags(function.flags, function.old_flags());
+        let name = self.name(function.name, "name")?;
+        let receiver_type = self.optional_type(
+            function.receiver_type.as_ref(),
+            function.receiver_type_id,
+            &scope,
+            0,
+        )?;
+        let value_parameters = self.value_parameters(&function.value_parameter, &scope)?;
+        let return_type = self
+            .optional_type(
+                function.return_type.as_ref(),
+                function.return_type_id,
+                &scope,
+                0,
+            )?
+            .ok_or(KotlinMetadataError::MissingField("return_type"))?;
+        let signature = function.signature.as_ref();
+        let jvm_signature = self.jvm_signature(
+            signature.and_then(|it| it.name),
+            signature.and_then(|it| it.desc),
+            || name.clone(),
+            || {
+                let parameters = value_parameters.iter().map(|it| &it.parameter_type);
+                method_descriptor(receiver_type.iter().chain(parameters), Some(&return_type))
+            },
+        )?;
+        Ok(Function {
+            flags: FunctionFlags::from_bits_truncate(flags),
+            visibility: visibility(flags)?,
+            modality: modality(flags),
+            member_kind: member_kind(flags),
+            type_parameters: self.type_parameters(&function.type_parameter, &scope)?,
+            receiver_type,
+            value_parameters,
+            return_type,
+            jvm_signature,
+            name,
+        })
+    }
+
+    fn property(
+        &self,
+        property: &proto::Property,
+        scope: &Scope<'_>,
+    ) -> Result<Property, KotlinMetadataError> {
+        let scope = self.scope(scope, scope.types, &property.type_parameter)?;
+        let flags = member_flags(property.flags, property.old_flags());
+        let name = self.name(property.name, "name")?;
+        let receiver_type = self.optional_type(
+            property.receiver_type.as_ref(),
+            property.receiver_type_id,
+            &scope,
+            0,
+        )?;
+        let return_type = self
+            .optional_type(
+                property.return_type.as_ref(),
+                property.return_type_id,
+                &scope,
+                0,
+            )?
+            .ok_or(KotlinMetadataError::MissingField("return_type"))?;
+        let signature = property.signature.as_ref();
+        let field_signature = signature
+            .and_then(|it| it.field.as_ref())
+            .map(|field| {
+                self.jvm_signature(
+                    field.name,
+                    field.desc,
+                    || name.clone(),
+                    || type_descriptor(&return_type),
+                )
+            })
+            .transpose()?
+            .flatten();
+        let getter_signature = signature
+            .and_then(|it| it.getter.as_ref())
+            .map(|getter| {
+                self.jvm_signature(
+                    getter.name,
+                    getter.desc,
+                    || getter_name(&name),
+                    || method_descriptor(receiver_type.iter(), Some(&return_type)),
+                )
+            })
+            .transpose()?
+            .flatten();
+        let setter_signature = signature
+            .and_then(|it| it.setter.as_ref())
+            .map(|setter| {
+                self.jvm_signature(
+                    setter.name,
+                    setter.desc,
+                    || setter_name(&name),
+                    || method_descriptor(receiver_type.iter().chain([&return_type]), None),
+                )
+            })
+            .transpose()?
+            .flatten();
+        Ok(Property {
+            flags: PropertyFlags::from_bits_truncate(flags),
+            visibility: visibility(flags)?,
+            modality: modality(flags),
+            member_kind: member_kind(flags),
+            type_parameters: self.type_parameters(&property.type_parameter, &scope)?,
+            receiver_type,
+            return_type,
+            field_signature,
+            getter_signature,
+            setter_signature,
+            name,
+        })
+    }
+
+    fn value_parameters(
+        &self,
+        parameters: &[proto::ValueParameter],
+        scope: &Scope<'_>,
+    ) -> Result<Vec<ValueParameter>, KotlinMetadataError> {
+        parameters
+            .iter()
+            .map(|parameter| {
+                Ok(ValueParameter {
+                    name: self.name(parameter.name, "name")?,
+                    flags: ValueParameterFlags::from_bits_truncate(flag_bits(parameter.flags())),
+                    parameter_type: self
+                        .optional_type(parameter.r#type.as_ref(), parameter.type_id, scope, 0)?
+                        .ok_or(KotlinMetadataError::MissingField("type"))?,
+                    vararg_element_type: self.optional_type(
+                        parameter.vararg_element_type.as_ref(),
+                        parameter.vararg_element_type_id,
+                        scope,
+                        0,
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    fn type_parameters(
+        &self,
+        parameters: &[proto::TypeParameter],
+        scope: &Scope<'_>,
+    ) -> Result<Vec<TypeParameter>, KotlinMetadataError> {
+        parameters
+            .iter()
+            .map(|parameter| {
+                let upper_bounds = parameter
+                    .upper_bound
+                    .iter()
+                    .map(|it| self.kotlin_type(it, scope, 0))
+                    .chain(
+                        parameter
+                            .upper_bound_id
+                            .iter()
+                            .map(|it| self.kotlin_type(scope.types.get(*it)?, scope, 0)),
+                    )
+                    .collect::<Result<_, _>>()?;
+                Ok(TypeParameter {
+                    id: parameter
+                        .id
+                        .ok_or(KotlinMetadataError::MissingField("id"))?,
+                    name: self.name(parameter.name, "name")?,
+                    is_reified: parameter.reified(),
+                    variance: variance(parameter.variance()),
+                    upper_bounds,
+                })
+            })
+            .collect()
+    }
+
+    /// Resolves a type stored either inline or in the type table.
+    fn optional_type(
+        &self,
+        inline: Option<&proto::Type>,
+        id: Option<i32>,
+        scope: &Scope<'_>,
+        depth: usize,
+    ) -> Result<Option<KotlinType>, KotlinMetadataError> {
+        let kotlin_type = match (inline, id) {
+            (Some(it), _) => it,
+            (None, Some(id)) => scope.types.get(id)?,
+            (None, None) => return Ok(None),
+        };
+        self.kotlin_type(kotlin_type, scope, depth).map(Some)
+    }
+
+    fn kotlin_type(
+        &self,
+        kotlin_type: &proto::Type,
+        scope: &Scope<'_>,
+        depth: usize,
+    ) -> Result<KotlinType, KotlinMetadataError> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(KotlinMetadataError::TypeTooDeep);
+        }
+        let type_count = self.type_count.get() + 1;
+        if type_count > MAX_TYPE_COUNT {
+            return Err(KotlinMetadataError::TooManyTypes);
+        }
+        self.type_count.set(type_count);
+        let classifier = match (
+            kotlin_type.class_name,
+            kotlin_type.type_parameter,
+            kotlin_type.type_parameter_name,
+            kotlin_type.type_alias_name,
+        ) {
+            (Some(name), _, _, _) => Classifier::Class(self.names.get(name)?),
+            (None, Some(id), _, _) => Classifier::TypeParameter(id),
+            (None, None, Some(name), _) => {
+                let name = self.names.get(name)?;
+                let Some(id) = scope.type_parameter_id(&name) else {
+                    return Err(KotlinMetadataError::UnknownTypeParameter(name));
+                };
+                Classifier::TypeParameter(id)
+            }
+            (None, None, None, Some(name)) => Classifier::TypeAlias(self.names.get(name)?),
+            (None, None, None, None) => {
+                return Err(KotlinMetadataError::MissingField("class_name"))
+            }
+        };
+        let arguments = kotlin_type
+            .argument
+            .iter()
+            .map(|argument| match argument.projection() {
+                // STAR
+                3 => Ok::<_, KotlinMetadataError>(TypeArgument::Star),
+                projection => {
+                    let argument_type = self
+                        .optional_type(
+                            argument.r#type.as_ref(),
+                            argument.type_id,
+                            scope,
+                            depth + 1,
+                        )?
+                        .ok_or(KotlinMetadataError::MissingField("type"))?;
+                    Ok(TypeArgument::Projection(
+                        variance(projection),
+                        argument_type,
+                    ))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        let flexible_upper_bound = self
+            .optional_type(
+                kotlin_type.flexible_upper_bound.as_deref(),
+                kotlin_type.flexible_upper_bound_id,
+                scope,
+                depth + 1,
+            )?
+            .map(Box::new);
+        let flags = flag_bits(kotlin_type.flags());
+        Ok(KotlinType {
+            classifier,
+            arguments,
+            is_nullable: kotlin_type.nullable(),
+            is_suspend: flags & 1 != 0,
+            is_definitely_non_null: flags & (1 << 1) != 0,
+            flexible_upper_bound,
+        })
+    }
+
+    /// Resolves the signature of a JVM method or field.
+    /// The compiler omits the name or the descriptor if it is the default one, which is derived
+    /// in the same way as `JvmProtoBufUtil` of the Kotlin compiler.
+    fn jvm_signature(
+        &self,
+        name: Option<i32>,
+        descriptor: Option<i32>,
+        default_name: impl FnOnce() -> String,
+        default_descriptor: impl FnOnce() -> Option<String>,
+    ) -> Result<Option<JvmSignature>, KotlinMetadataError> {
+        let descriptor = match descriptor {
+            Some(descriptor) => self.names.get(descriptor)?,
+            None => match default_descriptor() {
+                Some(descriptor) => descriptor,
+                None => return Ok(None),
+            },
+        };
+        let name = match name {
+            Some(name) => self.names.get(name)?,
+            None => default_name(),
+        };
+        Ok(Some(JvmSignature { name, descriptor }))
+    }
+}
+
+/// Builds the default descriptor of a method, or returns `None` if any of the types does not
+/// refer to a class.
+/// The return type is `void` if `return_type` is `None`.
+fn method_descriptor<'t>(
+    parameter_types: impl IntoIterator<Item = &'t KotlinType>,
+    return_type: Option<&KotlinType>,
+) -> Option<String> {
+    let mut descriptor = String::from("(");
+    for parameter_type in parameter_types {
+        descriptor.push_str(&type_descriptor(parameter_type)?);
+    }
+    descriptor.push(')');
+    match return_type {
+        Some(return_type) => descriptor.push_str(&type_descriptor(return_type)?),
+        None => descriptor.push('V'),
+    }
+    Some(descriptor)
+}
+
+/// Maps a type to its default descriptor, which ignores the type arguments and the nullability.
+fn type_descriptor(kotlin_type: &KotlinType) -> Option<String> {
+    match &kotlin_type.classifier {
+        Classifier::Class(name) => Some(class_descriptor(name)),
+        Classifier::TypeParameter(_) | Classifier::TypeAlias(_) => None,
+    }
+}
+
+/// The Kotlin primitive types and the descriptors of the corresponding JVM types.
+const PRIMITIVE_DESCRIPTORS: [(&str, &str); 8] = [
+    ("Boolean", "Z"),
+    ("Char", "C"),
+    ("Byte", "B"),
+    ("Short", "S"),
+    ("Int", "I"),
+    ("Float", "F"),
+    ("Long", "J"),
+    ("Double", "D"),
+];
+
+/// Maps a Kotlin class to the descriptor of the corresponding JVM type, following
+/// `ClassMapperLite` of the Kotlin compiler.
+fn class_descriptor(name: &str) -> String {
+    let primitive = |name: &str| {
+        PRIMITIVE_DESCRIPTORS
+            .iter()
+            .find_map(|(kotlin, jvm)| (*kotlin == name).then_some(*jvm))
+    };
+    let is_function = |name: &str, prefix: &str| {
+        name.strip_prefix(prefix)
+            .filter(|arity| arity.bytes().all(|it| it.is_ascii_digit()))
+            .and_then(|arity| arity.parse::<u8>().ok())
+            .is_some_and(|arity| arity <= 22)
+    };
+    let Some(simple_name) = name.strip_prefix("kotlin/") else {
+        return format!("L{};", name.replace('.', "$"));
+    };
+    if let Some(descriptor) = primitive(simple_name) {
+        return descriptor.to_owned();
+    }
+    if let Some(descriptor) = simple_name.strip_suffix("Array").and_then(primitive) {
+        return format!("[{descriptor}");
+    }
+    if let Some(collection) = simple_name.strip_prefix("collections/") {
+        let collection = collection.strip_prefix("Mutable").unwrap_or(collection);
+        if matches!(
+            collection,
+            "Iterator" | "Collection" | "List" | "Set" | "Map" | "ListIterator"
+        ) {
+            return format!("Ljava/util/{collection};");
+        }
+    }
+    if is_function(simple_name, "Function") {
+        return format!("Lkotlin/jvm/functions/{simple_name};");
+    }
+    if is_function(simple_name, "reflect/KFunction") {
+        return "Lkotlin/reflect/KFunction;".to_owned();
+    }
+    if let Some(class) = simple_name.strip_suffix(".Companion").filter(|it| {
+        matches!(
+            *it,
+            "Char" | "Byte" | "Short" | "Int" | "Float" | "Long" | "Double" | "String" | "Enum"
+        )
+    }) {
+        return format!("Lkotlin/jvm/internal/{class}CompanionObject;");
+    }
+    let jvm_name = match simple_name {
+        "Unit" => return "V".to_owned(),
+        "Any" => "java/lang/Object",
+        "Nothing" => "java/lang/Void",
+        "Annotation" => "java/lang/annotation/Annotation",
+        "String" | "CharSequence" | "Throwable" | "Cloneable" | "Number" | "Comparable"
+        | "Enum" => return format!("Ljava/lang/{simple_name};"),
+        "collections/Iterable" | "collections/MutableIterable" => "java/lang/Iterable",
+        "collections/Map.Entry" | "collections/MutableMap.MutableEntry" => "java/util/Map$Entry",
+        _ => return format!("L{};", name.replace('.', "$")),
+    };
+    format!("L{jvm_name};")
+}
+
+/// Derives the default name of the getter of a property, e.g., `getName` for `name` and
+/// `isEmpty` for `isEmpty`.
+fn getter_name(property_name: &str) -> String {
+    if has_is_prefix(property_name) {
+        property_name.to_owned()
+    } else {
+        format!("get{}", capitalize(property_name))
+    }
+}
+
+/// Derives the default name of the setter of a property, e.g., `setName` for `name` and
+/// `setEmpty` for `isEmpty`.
+fn setter_name(property_name: &str) -> String {
+    match property_name.strip_prefix("is") {
+        Some(rest) if has_is_prefix(property_name) => format!("set{rest}"),
+        _ => format!("set{}", capitalize(property_name)),
+    }
+}
+
+/// Checks whether the name starts with `is` followed by a character other than `a..=z`.
+fn has_is_prefix(name: &str) -> bool {
+    name.strip_prefix("is")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|it| !it.is_ascii_lowercase())
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Reads the flags of a function or a property.
+/// Compilers before Kotlin 1.1 wrote only `old_flags`, which have two more bits after the
+/// modality.
+fn member_flags(flags: Option<i32>, old_flags: i32) -> u32 {
+    flags.map_or_else(
+        || {
+            let old_flags = flag_bits(old_flags);
+            (old_flags & 0x3F) | ((old_flags >> 8) << 6)
+        },
+        flag_bits,
+    )
+}
+
+/// Reinterprets the flags, which are `int32` in the messages, as bits.
+fn flag_bits(flags: i32) -> u32 {
+    u32::from_ne_bytes(flags.to_ne_bytes())
+}
+
+fn visibility(flags: u32) -> Result<Visibility, KotlinMetadataError> {
+    match (flags >> 1) & 0b111 {
+        0 => Ok(Visibility::Internal),
+        1 => Ok(Visibility::Private),
+        2 => Ok(Visibility::Protected),
+        3 => Ok(Visibility::Public),
+        4 => Ok(Visibility::PrivateToThis),
+        5 => Ok(Visibility::Local),
+        _ => Err(KotlinMetadataError::InvalidFlags(flags)),
+    }
+}
+
+fn modality(flags: u32) -> Modality {
+    match (flags >> 4) & 0b11 {
+        0 => Modality::Final,
+        1 => Modality::Open,
+        2 => Modality::Abstract,
+        _ => Modality::Sealed,
+    }
+}
+
+fn member_kind(flags: u32) -> MemberKind {
+    match (flags >> 6) & 0b11 {
+        0 => MemberKind::Declaration,
+        1 => MemberKind::FakeOverride,
+        2 => MemberKind::Delegation,
+        _ => MemberKind::Synthesized,
+    }
+}
+
+fn variance(variance: i32) -> Variance {
+    match variance {
+        0 => Variance::In,
+        1 => Variance::Out,
+        _ => Variance::Invariant,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        jvm::references::ClassRef,
+        types::field_type::{FieldType, PrimitiveType},
+    };
+
+    use super::*;
+
+    fn string_element(value: &str) -> ElementValue {
+        ElementValue::String(ConstantValue::String(JavaString::Utf8(value.to_owned())))
+    }
+
+    fn int_element(value: i32) -> ElementValue {
+        ElementValue::Primitive(PrimitiveType::Int, ConstantValue::Integer(value))
+    }
+
+    /// Encodes the messages in the way of the Kotlin compiler, i.e., in UTF-8 mode.
+    fn metadata_annotation(
+        kind: i32,
+        table: &proto::StringTableTypes,
+        message: &impl Message,
+        d2: &[&str],
+    ) -> Annotation {
+        let mut bytes = table.encode_length_delimited_to_vec();
+        message.encode(&mut bytes).unwrap();
+        let d1 = std::iter::once(UTF8_MODE_MARKER)
+            .chain(bytes.into_iter().map(char::from))
+            .collect::<String>();
+        Annotation {
+            annotation_type: FieldType::Object(ClassRef::new(KotlinMetadata::ANNOTATION_NAME)),
+            element_value_pairs: vec![
+                ("k".to_owned(), int_element(kind)),
+                (
+                    "mv".to_owned(),
+                    ElementValue::Array(vec![int_element(1), int_element(9), int_element(0)]),
+                ),
+                (
+                    "d1".to_owned(),
+                    ElementValue::Array(vec![string_element(&d1)]),
+                ),
+                (
+                    "d2".to_owned(),
+                    ElementValue::Array(d2.iter().map(|it| string_element(it)).collect()),
+                ),
+            ],
+        }
+    }
+
+    fn class_type(name: i32) -> proto::Type {
+        proto::Type {
+            class_name: Some(name),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decode_data_class() {
+        // data class User(val name: String?) { suspend fun String.greet(times: Int): Unit }
+        let d2 = [
+            "User",
+            "",
+            "name",
+            "",
+            "greet",
+            "times",
+            "(Ljava/lang/String;I)V",
+        ];
+        let table = proto::StringTableTypes {
+            record: vec![
+                proto::Record {
+                    range: Some(1),
+                    ..Default::default()
+                },
+                proto::Record {
+                    range: Some(1),
+                    string: Some("Lkotlin/String;".to_owned()),
+                    operation: Some(2),
+                    ..Default::default()
+                },
+                proto::Record {
+                    range: Some(5),
+                    ..Default::default()
+                },
+                proto::Record {
+                    predefined_index: Some(2),
+                    ..Default::default()
+                },
+                proto::Record {
+                    predefined_index: Some(8),
+                    ..Default::default()
+                },
+            ],
+            local_name: Vec::new(),
+        };
+        let class = proto::Class {
+            flags: Some(0b110 | (1 << 10)),
+            fq_name: Some(0),
+            property: vec![proto::Property {
+                name: Some(2),
+                return_type_id: Some(0),
+                ..Default::default()
+            }],
+            function: vec![proto::Function {
+                flags: Some(0b110 | (1 << 13)),
+                name: Some(4),
+                receiver_type_id: Some(1),
+                value_parameter: vec![proto::ValueParameter {
+                    name: Some(5),
+                    r#type: Some(class_type(8)),
+                    ..Default::default()
+                }],
+                return_type: Some(class_type(7)),
+                signature: Some(proto::JvmMethodSignature {
+                    name: None,
+                    desc: Some(6),
+                }),
+                ..Default::default()
+            }],
+            type_table: Some(proto::TypeTable {
+                r#type: vec![class_type(1), class_type(1)],
+                first_nullable: Some(0),
+            }),
+            ..Default::default()
+        };
+        let annotation = metadata_annotation(1, &table, &class, &d2);
+        let metadata = KotlinMetadata::from_annotation(&annotation).unwrap();
+        assert_eq!(metadata.version, vec![1, 9, 0]);
+        let MetadataKind::Class(class) = &metadata.kind else {
+            panic!("Expected class metadata");
+        };
+        assert_eq!(class.name, "User");
+        assert_eq!(class.kind, ClassKind::Class);
+        assert_eq!(class.visibility, Visibility::Public);
+        assert!(class.flags.contains(ClassFlags::DATA));
+
+        let property = &class.properties[0];
+        assert_eq!(property.name, "name");
+        assert!(property.flags.contains(PropertyFlags::HAS_GETTER));
+        assert_eq!(
+            property.return_type.classifier,
+            Classifier::Class("kotlin/String".to_owned())
+        );
+        assert!(property.return_type.is_nullable);
+
+        let function = &class.functions[0];
+        assert!(function.is_suspend());
+        assert!(function.is_extension());
+        assert_eq!(function.value_parameters[0].name, "times");
+        assert_eq!(
+            function.value_parameters[0].parameter_type.classifier,
+            Classifier::Class("kotlin/Int".to_owned())
+        );
+        assert!(!function.value_parameters[0].parameter_type.is_nullable);
+        assert_eq!(
+            function.return_type.classifier,
+            Classifier::Class("kotlin/Unit".to_owned())
+        );
+        let signature = function.jvm_signature.as_ref().unwrap();
+        assert_eq!(signature.name, "greet");
+        assert_eq!(signature.descriptor, "(Ljava/lang/String;I)V");
+    }
+
+    #[test]
+    fn decode_multi_file_facade() {
+        let annotation = Annotation {
+            annotation_type: FieldType::Object(ClassRef::new(KotlinMetadata::ANNOTATION_NAME)),
+            element_value_pairs: vec![
+                ("k".to_owned(), int_element(4)),
+                (
+                    "d1".to_owned(),
+                    ElementValue::Array(vec![string_element("FooKt__BarKt")]),
+                ),
+            ],
+        };
+        let metadata = KotlinMetadata::from_annotation(&annotation).unwrap();
+        assert_eq!(
+            metadata.kind,
+            MetadataKind::MultiFileClassFacade {
+                part_class_names: vec!["FooKt__BarKt".to_owned()]
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_metadata() {
+        let annotation = Annotation {
+            annotation_type: FieldType::Object(ClassRef::new(KotlinMetadata::ANNOTATION_NAME)),
+            element_value_pairs: vec![("k".to_owned(), string_element("1"))],
+        };
+        assert!(matches!(
+            KotlinMetadata::from_annotation(&annotation),
+            Err(KotlinMetadataError::MalformedElement("k"))
+        ));
+        let annotation = Annotation {
+            annotation_type: FieldType::Object(ClassRef::new(KotlinMetadata::ANNOTATION_NAME)),
+            element_value_pairs: vec![("k".to_owned(), int_element(42))],
+        };
+        assert!(matches!(
+            KotlinMetadata::from_annotation(&annotation),
+            Err(KotlinMetadataError::UnsupportedKind(42))
+        ));
+    }
+
+    /// Packs the bits of the bytes into 7-bit groups, least significant first.
+    fn encode_8_to_7(data: &[u8]) -> Vec<u8> {
+        let bits = data
+            .iter()
+            .flat_map(|byte| (0..8).map(move |bit| (byte >> bit) & 1))
+            .collect::<Vec<_>>();
+        bits.chunks(7)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (bit, value)| acc | (value << bit))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_7_bit_mode() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let encoded = encode_8_to_7(&data)
+            .into_iter()
+            .map(|it| char::from((it + 1) & 0x7F))
+            .collect::<String>();
+        let d1 = format!("{BIT_7_MODE_MARKER}{encoded}");
+        assert_eq!(decode_bytes(&[d1]).unwrap(), data);
+        assert!(matches!(
+            decode_bytes(&["\u{0}\u{100}".to_owned()]),
+            Err(KotlinMetadataError::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    fn resolve_names() {
+        let table = proto::StringTableTypes {
+            record: vec![
+                proto::Record {
+                    operation: Some(1),
+                    ..Default::default()
+                },
+                proto::Record {
+                    substring_index: vec![0, 3],
+                    replace_char: vec![i32::from(b'o'), i32::from(b'0')],
+                    ..Default::default()
+                },
+            ],
+            local_name: Vec::new(),
+        };
+        let strings = ["kotlin/Map$Entry".to_owned(), "foobar".to_owned()];
+        let names = NameResolver::new(table, &strings);
+        assert_eq!(names.get(0).unwrap(), "kotlin/Map.Entry");
+        assert_eq!(names.get(1).unwrap(), "f00");
+        assert!(matches!(
+            names.get(2),
+            Err(KotlinMetadataError::InvalidStringIndex(2))
+        ));
+    }
+
+    #[test]
+    fn resolve_names_with_large_ranges() {
+        let table = proto::StringTableTypes {
+            record: vec![
+                proto::Record {
+                    range: Some(1),
+                    string: Some("kotlin/Any".to_owned()),
+                    ..Default::default()
+                },
+                proto::Record {
+                    range: Some(i32::MAX),
+                    operation: Some(1),
+                    ..Default::default()
+                },
+                proto::Record {
+                    range: Some(i32::MAX),
+                    ..Default::default()
+                },
+            ],
+            local_name: Vec::new(),
+        };
+        let strings = [String::new(), "kotlin/Map$Entry".to_owned()];
+        let names = NameResolver::new(table, &strings);
+        assert_eq!(names.get(0).unwrap(), "kotlin/Any");
+        assert_eq!(names.get(1).unwrap(), "kotlin/Map.Entry");
+        assert!(matches!(
+            names.get(i32::MAX),
+            Err(KotlinMetadataError::InvalidStringIndex(i32::MAX))
+        ));
+    }
+
+    fn empty_table() -> proto::StringTableTypes {
+        proto::StringTableTypes {
+            record: Vec::new(),
+            local_name: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn too_many_types() {
+        // Each type has two arguments referring to the previous type.
+        let types = std::iter::once(class_type(1))
+            .chain((0..40).map(|previous| proto::Type {
+                class_name: Some(1),
+                argument: vec![
+                    proto::Argument {
+                        type_id: Some(previous),
+                        ..Default::default()
+                    };
+                    2
+                ],
+                ..Default::default()
+            }))
+            .collect();
+        let package = proto::Package {
+            property: vec![proto::Property {
+                name: Some(0),
+                return_type_id: Some(40),
+                ..Default::default()
+            }],
+            type_table: Some(proto::TypeTable {
+                r#type: types,
+                first_nullable: None,
+            }),
+            ..Default::default()
+        };
+        let annotation = metadata_annotation(2, &empty_table(), &package, &["pair", "kotlin/Pair"]);
+        assert!(matches!(
+            KotlinMetadata::from_annotation(&annotation),
+            Err(KotlinMetadataError::TooManyTypes)
+        ));
+    }
+
+    #[test]
+    fn decode_top_level_declarations() {
+        // inline fun <T> String.repeat(count: Int): String? and var isEmpty: Boolean
+        let d2 = [
+            "repeat",
+            "T",
+            "count",
+            "kotlin/String",
+            "kotlin/Int",
+            "isEmpty",
+            "kotlin/Boolean",
+            "id",
+        ];
+        let public_open = 0b110 | (1 << 4);
+        let type_parameter = proto::TypeParameter {
+            id: Some(0),
+            name: Some(1),
+            ..Default::default()
+        };
+        let package = proto::Package {
+            function: vec![
+                proto::Function {
+                    // Written by compilers before Kotlin 1.1 with `inline` at bit 12
+                    old_flags: Some(public_open | (1 << 8) | (1 << 12)),
+                    name: Some(0),
+                    type_parameter: vec![type_parameter.clone()],
+                    receiver_type: Some(class_type(3)),
+                    value_parameter: vec![proto::ValueParameter {
+                        name: Some(2),
+                        r#type: Some(class_type(4)),
+                        ..Default::default()
+                    }],
+                    return_type: Some(proto::Type {
+                        nullable: Some(true),
+                        ..class_type(3)
+                    }),
+                    ..Default::default()
+                },
+                proto::Function {
+                    name: Some(7),
+                    type_parameter: vec![type_parameter],
+                    value_parameter: vec![proto::ValueParameter {
+                        name: Some(1),
+                        r#type: Some(proto::Type {
+                            type_parameter_name: Some(1),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }],
+                    return_type: Some(proto::Type {
+                        type_parameter_name: Some(1),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            property: vec![proto::Property {
+                flags: Some(0b110 | (1 << 8) | (1 << 9) | (1 << 10)),
+                name: Some(5),
+                return_type: Some(class_type(6)),
+                signature: Some(proto::JvmPropertySignature {
+                    field: Some(proto::JvmFieldSignature::default()),
+                    getter: Some(proto::JvmMethodSignature::default()),
+                    setter: Some(proto::JvmMethodSignature::default()),
+                }),
+                ..Default::default()
+            }],
+            type_table: None,
+        };
+        let annotation = metadata_annotation(2, &empty_table(), &package, &d2);
+        let metadata = KotlinMetadata::from_annotation(&annotation).unwrap();
+
+        let repeat = &metadata.functions()[0];
+        assert_eq!(repeat.modality, Modality::Open);
+        assert_eq!(repeat.member_kind, MemberKind::FakeOverride);
+        assert!(repeat.flags.contains(FunctionFlags::INLINE));
+        assert_eq!(
+            repeat.jvm_signature,
+            Some(JvmSignature {
+                name: "repeat".to_owned(),
+                descriptor: "(Ljava/lang/String;I)Ljava/lang/String;".to_owned(),
+            })
+        );
+
+        let id = &metadata.functions()[1];
+        assert_eq!(id.return_type.classifier, Classifier::TypeParameter(0));
+        assert_eq!(id.jvm_signature, None);
+
+        let is_empty = &metadata.properties()[0];
+        let signature = |name: &str, descriptor: &str| {
+            Some(JvmSignature {
+                name: name.to_owned(),
+                descriptor: descriptor.to_owned(),
+            })
+        };
+        assert_eq!(is_empty.field_signature, signature("isEmpty", "Z"));
+        assert_eq!(is_empty.getter_signature, signature("isEmpty", "()Z"));
+        assert_eq!(is_empty.setter_signature, signature("setEmpty", "(Z)V"));
+    }
+
+    #[test]
+    fn unknown_type_parameter() {
+        let package = proto::Package {
+            function: vec![proto::Function {
+                name: Some(0),
+                return_type: Some(proto::Type {
+                    type_parameter_name: Some(1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let annotation = metadata_annotation(2, &empty_table(), &package, &["id", "T"]);
+        assert!(matches!(
+            KotlinMetadata::from_annotation(&annotation),
+            Err(KotlinMetadataError::UnknownTypeParameter(name)) if name == "T"
+        ));
+    }
+
+    #[test]
+    fn default_descriptors() {
+        assert_eq!(class_descriptor("kotlin/Long"), "J");
+        assert_eq!(class_descriptor("kotlin/IntArray"), "[I");
+        assert_eq!(class_descriptor("kotlin/Unit"), "V");
+        assert_eq!(class_descriptor("kotlin/Any"), "Ljava/lang/Object;");
+        assert_eq!(
+            class_descriptor("kotlin/collections/MutableList"),
+            "Ljava/util/List;"
+        );
+        assert_eq!(
+            class_descriptor("kotlin/collections/Map.Entry"),
+            "Ljava/util/Map$Entry;"
+        );
+        assert_eq!(
+            class_descriptor("kotlin/Function2"),
+            "Lkotlin/jvm/functions/Function2;"
+        );
+        assert_eq!(
+            class_descriptor("kotlin/Int.Companion"),
+            "Lkotlin/jvm/internal/IntCompanionObject;"
+        );
+        assert_eq!(class_descriptor("kotlin/Array"), "Lkotlin/Array;");
+        assert_eq!(
+            class_descriptor("com/example/Outer.Inner"),
+            "Lcom/example/Outer$Inner;"
+        );
+        assert_eq!(getter_name("name"), "getName");
+        assert_eq!(setter_name("name"), "setName");
+        assert_eq!(getter_name("issue"), "getIssue");
+        assert_eq!(setter_name("isEmpty"), "setEmpty");
+    }
+}
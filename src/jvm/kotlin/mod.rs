@@ -0,0 +1,512 @@
+//! Metadata of classes compiled by the Kotlin compiler.
+//!
+//! The Kotlin compiler annotates every class it generates with `kotlin.Metadata`, which
+//! describes the Kotlin declarations that cannot be expressed in JVM bytecode, e.g., nullability,
+//! properties, extension receivers, `suspend` functions, and `data` classes.
+//! The declarations are encoded with [Protocol Buffers](https://protobuf.dev) in the `d1` element
+//! and the strings they refer to are stored in the `d2` element.
+//!
+//! Names of classes are in the format used by the Kotlin compiler, i.e., packages are separated
+//! by `/` and nested classes by `.` (e.g., `kotlin/collections/Map.Entry`).
+
+use bitflags::bitflags;
+
+use crate::types::field_type::FieldType;
+
+use super::{annotation::Annotation, class::Class, method::Method, references::ClassRef};
+
+mod decode;
+mod proto;
+
+/// An error when decoding the Kotlin metadata.
+#[derive(Debug, thiserror::Error)]
+pub enum KotlinMetadataError {
+    /// An element of the `kotlin.Metadata` annotation is missing or has an unexpected type.
+    #[error("Missing or malformed element `{0}` in kotlin.Metadata")]
+    MalformedElement(&'static str),
+    /// The kind of the metadata is not known.
+    #[error("Unsupported metadata kind {0}")]
+    UnsupportedKind(i32),
+    /// The `d1` element is not a valid encoding of bytes.
+    #[error("Invalid encoding of the d1 element")]
+    InvalidEncoding,
+    /// The declarations in `d1` are malformed.
+    #[error("Malformed protobuf message: {0}")]
+    Protobuf(#[from] prost::DecodeError),
+    /// The flags of a declaration have an invalid value.
+    #[error("Invalid flags {0:#x}")]
+    InvalidFlags(u32),
+    /// A required field of a declaration is missing.
+    #[error("Missing field `{0}`")]
+    MissingField(&'static str),
+    /// A declaration refers to a string that does not exist.
+    #[error("Invalid string index {0}")]
+    InvalidStringIndex(i32),
+    /// A declaration refers to a type that does not exist in the type table.
+    #[error("Invalid type index {0}")]
+    InvalidTypeIndex(i32),
+    /// The types are nested too deeply, which usually indicates a cycle in the type table.
+    #[error("Types are nested too deeply")]
+    TypeTooDeep,
+    /// The types refer to other types in the type table too many times.
+    #[error("Too many types")]
+    TooManyTypes,
+    /// A type refers by name to a type parameter that is not in scope.
+    #[error("Unknown type parameter `{0}`")]
+    UnknownTypeParameter(String),
+}
+
+/// The content of the `kotlin.Metadata` annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KotlinMetadata {
+    /// The version of the metadata format, e.g., `[1, 9, 0]`.
+    pub version: Vec<i32>,
+    /// The fully qualified name of the package, if it differs from the package of the class.
+    pub package_name: Option<String>,
+    /// The declarations described by the metadata.
+    pub kind: MetadataKind,
+}
+
+/// The kind of a [`KotlinMetadata`] with the declarations it describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataKind {
+    /// A class, an interface, an object, or an annotation class.
+    Class(ClassMetadata),
+    /// A class containing the top-level declarations in a source file, e.g., `MainKt`.
+    FileFacade(PackageMetadata),
+    /// A class generated by the compiler, e.g., for a lambda, in which case the function is
+    /// present.
+    SyntheticClass(Option<Function>),
+    /// A facade of the top-level declarations in the files annotated with `@JvmMultifileClass`.
+    MultiFileClassFacade {
+        /// The binary names of the classes of the parts.
+        part_class_names: Vec<String>,
+    },
+    /// A part of a multi-file class.
+    MultiFileClassPart {
+        /// The declarations in the part.
+        package: PackageMetadata,
+        /// The binary name of the facade class.
+        facade_class_name: String,
+    },
+}
+
+/// A Kotlin class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMetadata {
+    /// The name of the class.
+    pub name: String,
+    /// The flags of the class.
+    pub flags: ClassFlags,
+    /// The visibility of the class.
+    pub visibility: Visibility,
+    /// The modality of the class.
+    pub modality: Modality,
+    /// The kind of the class.
+    pub kind: ClassKind,
+    /// The simple name of the companion object, if present.
+    pub companion_object: Option<String>,
+    /// The type parameters of the class.
+    pub type_parameters: Vec<TypeParameter>,
+    /// The supertypes of the class.
+    pub supertypes: Vec<KotlinType>,
+    /// The simple names of the nested classes.
+    pub nested_classes: Vec<String>,
+    /// The constructors of the class.
+    pub constructors: Vec<Constructor>,
+    /// The functions declared in the class.
+    pub functions: Vec<Function>,
+    /// The properties declared in the class.
+    pub properties: Vec<Property>,
+    /// The names of the entries if the class is an enum class.
+    pub enum_entries: Vec<String>,
+    /// The names of the direct subclasses if the class is sealed.
+    pub sealed_subclasses: Vec<String>,
+}
+
+/// The top-level declarations in a file facade or a multi-file class part.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PackageMetadata {
+    /// The top-level functions.
+    pub functions: Vec<Function>,
+    /// The top-level properties.
+    pub properties: Vec<Property>,
+}
+
+/// A Kotlin constructor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    /// The visibility of the constructor.
+    pub visibility: Visibility,
+    /// Whether the constructor is a secondary one.
+    pub is_secondary: bool,
+    /// The value parameters of the constructor.
+    pub value_parameters: Vec<ValueParameter>,
+    /// The signature of the corresponding JVM method, if recorded by the compiler or derivable
+    /// from the types.
+    pub jvm_signature: Option<JvmSignature>,
+}
+
+/// A Kotlin function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    /// The name of the function.
+    pub name: String,
+    /// The flags of the function.
+    pub flags: FunctionFlags,
+    /// The visibility of the function.
+    pub visibility: Visibility,
+    /// The modality of the function.
+    pub modality: Modality,
+    /// The kind of the function.
+    pub member_kind: MemberKind,
+    /// The type parameters of the function.
+    pub type_parameters: Vec<TypeParameter>,
+    /// The receiver type if the function is an extension function.
+    pub receiver_type: Option<KotlinType>,
+    /// The value parameters of the function.
+    pub value_parameters: Vec<ValueParameter>,
+    /// The return type of the function.
+    pub return_type: KotlinType,
+    /// The signature of the corresponding JVM method, if recorded by the compiler or derivable
+    /// from the types.
+    pub jvm_signature: Option<JvmSignature>,
+}
+
+impl Function {
+    /// Checks whether the function is an extension function.
+    #[must_use]
+    pub const fn is_extension(&self) -> bool {
+        self.receiver_type.is_some()
+    }
+
+    /// Checks whether the function is a `suspend` function.
+    #[must_use]
+    pub const fn is_suspend(&self) -> bool {
+        self.flags.contains(FunctionFlags::SUSPEND)
+    }
+}
+
+/// A Kotlin property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    /// The name of the property.
+    pub name: String,
+    /// The flags of the property.
+    pub flags: PropertyFlags,
+    /// The visibility of the property.
+    pub visibility: Visibility,
+    /// The modality of the property.
+    pub modality: Modality,
+    /// The kind of the property.
+    pub member_kind: MemberKind,
+    /// The type parameters of the property.
+    pub type_parameters: Vec<TypeParameter>,
+    /// The receiver type if the property is an extension property.
+    pub receiver_type: Option<KotlinType>,
+    /// The type of the property.
+    pub return_type: KotlinType,
+    /// The signature of the backing field, if recorded by the compiler.
+    pub field_signature: Option<JvmSignature>,
+    /// The signature of the getter, if recorded by the compiler.
+    pub getter_signature: Option<JvmSignature>,
+    /// The signature of the setter, if recorded by the compiler.
+    pub setter_signature: Option<JvmSignature>,
+}
+
+/// A value parameter of a function or a constructor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueParameter {
+    /// The name of the parameter.
+    pub name: String,
+    /// The flags of the parameter.
+    pub flags: ValueParameterFlags,
+    /// The type of the parameter, which is an array type if the parameter is a `vararg`.
+    pub parameter_type: KotlinType,
+    /// The type of the elements if the parameter is a `vararg`.
+    pub vararg_element_type: Option<KotlinType>,
+}
+
+/// A type parameter of a class, a function, or a property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParameter {
+    /// The identifier referred by [`Classifier::TypeParameter`].
+    pub id: i32,
+    /// The name of the type parameter.
+    pub name: String,
+    /// Whether the type parameter is `reified`.
+    pub is_reified: bool,
+    /// The declaration-site variance of the type parameter.
+    pub variance: Variance,
+    /// The upper bounds of the type parameter.
+    pub upper_bounds: Vec<KotlinType>,
+}
+
+/// A Kotlin type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KotlinType {
+    /// The classifier of the type.
+    pub classifier: Classifier,
+    /// The type arguments.
+    pub arguments: Vec<TypeArgument>,
+    /// Whether the type is nullable, e.g., `String?`.
+    pub is_nullable: bool,
+    /// Whether the type is a `suspend` function type.
+    pub is_suspend: bool,
+    /// Whether the type is a definitely non-nullable type, e.g., `T & Any`.
+    pub is_definitely_non_null: bool,
+    /// The upper bound if the type is a flexible type, i.e., a platform type from Java.
+    pub flexible_upper_bound: Option<Box<KotlinType>>,
+}
+
+/// The classifier of a [`KotlinType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Classifier {
+    /// A class.
+    Class(String),
+    /// A type parameter with the given [`TypeParameter::id`].
+    TypeParameter(i32),
+    /// A type alias.
+    TypeAlias(String),
+}
+
+/// A type argument of a [`KotlinType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeArgument {
+    /// The star projection, i.e., `*`.
+    Star,
+    /// A type with its use-site variance.
+    Projection(Variance, KotlinType),
+}
+
+/// The signature of a JVM method or field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JvmSignature {
+    /// The name of the method or field.
+    pub name: String,
+    /// The descriptor of the method or field.
+    pub descriptor: String,
+}
+
+/// The visibility of a declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// `internal`.
+    Internal,
+    /// `private`.
+    Private,
+    /// `protected`.
+    Protected,
+    /// `public`.
+    Public,
+    /// `private` to the receiver, i.e., accessible only via `this`.
+    PrivateToThis,
+    /// A local declaration.
+    Local,
+}
+
+/// The modality of a declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modality {
+    /// `final`.
+    Final,
+    /// `open`.
+    Open,
+    /// `abstract`.
+    Abstract,
+    /// `sealed`.
+    Sealed,
+}
+
+/// The kind of a [`ClassMetadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassKind {
+    /// A class.
+    Class,
+    /// An interface.
+    Interface,
+    /// An enum class.
+    EnumClass,
+    /// An enum entry with a body.
+    EnumEntry,
+    /// An annotation class.
+    AnnotationClass,
+    /// An object.
+    Object,
+    /// A companion object.
+    CompanionObject,
+}
+
+/// The kind of a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    /// A member declared in the source.
+    Declaration,
+    /// A member inherited from a supertype.
+    FakeOverride,
+    /// A member generated for interface delegation.
+    Delegation,
+    /// A member generated by the compiler, e.g., `copy` of a `data` class.
+    Synthesized,
+}
+
+/// The variance of a type parameter or a type argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variance {
+    /// `in`.
+    In,
+    /// `out`.
+    Out,
+    /// Invariant.
+    Invariant,
+}
+
+bitflags! {
+    /// The flags of a [`ClassMetadata`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct ClassFlags: u32 {
+        /// The class has annotations.
+        const HAS_ANNOTATIONS = 1 << 0;
+        /// Declared `inner`.
+        const INNER = 1 << 9;
+        /// Declared `data`.
+        const DATA = 1 << 10;
+        /// Declared `external`.
+        const EXTERNAL = 1 << 11;
+        /// Declared `expect`.
+        const EXPECT = 1 << 12;
+        /// Declared `value` or `inline`.
+        const VALUE = 1 << 13;
+        /// Declared `fun interface`.
+        const FUN_INTERFACE = 1 << 14;
+    }
+}
+
+bitflags! {
+    /// The flags of a [`Function`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct FunctionFlags: u32 {
+        /// The function has annotations.
+        const HAS_ANNOTATIONS = 1 << 0;
+        /// Declared `operator`.
+        const OPERATOR = 1 << 8;
+        /// Declared `infix`.
+        const INFIX = 1 << 9;
+        /// Declared `inline`.
+        const INLINE = 1 << 10;
+        /// Declared `tailrec`.
+        const TAILREC = 1 << 11;
+        /// Declared `external`.
+        const EXTERNAL = 1 << 12;
+        /// Declared `suspend`.
+        const SUSPEND = 1 << 13;
+        /// Declared `expect`.
+        const EXPECT = 1 << 14;
+    }
+}
+
+bitflags! {
+    /// The flags of a [`Property`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct PropertyFlags: u32 {
+        /// The property has annotations.
+        const HAS_ANNOTATIONS = 1 << 0;
+        /// Declared `var`.
+        const VAR = 1 << 8;
+        /// The property has a getter.
+        const HAS_GETTER = 1 << 9;
+        /// The property has a setter.
+        const HAS_SETTER = 1 << 10;
+        /// Declared `const`.
+        const CONST = 1 << 11;
+        /// Declared `lateinit`.
+        const LATEINIT = 1 << 12;
+        /// The property has a constant initializer.
+        const HAS_CONSTANT = 1 << 13;
+        /// Declared `external`.
+        const EXTERNAL = 1 << 14;
+        /// The property is delegated, i.e., declared with `by`.
+        const DELEGATED = 1 << 15;
+        /// Declared `expect`.
+        const EXPECT = 1 << 16;
+    }
+}
+
+bitflags! {
+    /// The flags of a [`ValueParameter`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct ValueParameterFlags: u32 {
+        /// The parameter has annotations.
+        const HAS_ANNOTATIONS = 1 << 0;
+        /// The parameter has a default value.
+        const DECLARES_DEFAULT_VALUE = 1 << 1;
+        /// Declared `crossinline`.
+        const CROSSINLINE = 1 << 2;
+        /// Declared `noinline`.
+        const NOINLINE = 1 << 3;
+    }
+}
+
+impl KotlinMetadata {
+    /// The binary name of the `kotlin.Metadata` annotation.
+    pub const ANNOTATION_NAME: &'static str = "kotlin/Metadata";
+
+    /// Decodes the metadata from a `kotlin.Metadata` annotation.
+    ///
+    /// # Errors
+    /// See [`KotlinMetadataError`].
+    pub fn from_annotation(annotation: &Annotation) -> Result<Self, KotlinMetadataError> {
+        decode::decode_metadata(annotation)
+    }
+
+    /// Returns the functions described by the metadata.
+    #[must_use]
+    pub fn functions(&self) -> &[Function] {
+        match &self.kind {
+            MetadataKind::Class(class) => &class.functions,
+            MetadataKind::FileFacade(package)
+            | MetadataKind::MultiFileClassPart { package, .. } => &package.functions,
+            MetadataKind::SyntheticClass(function) => function.as_slice(),
+            MetadataKind::MultiFileClassFacade { .. } => &[],
+        }
+    }
+
+    /// Returns the properties described by the metadata.
+    #[must_use]
+    pub fn properties(&self) -> &[Property] {
+        match &self.kind {
+            MetadataKind::Class(class) => &class.properties,
+            MetadataKind::FileFacade(package)
+            | MetadataKind::MultiFileClassPart { package, .. } => &package.properties,
+            MetadataKind::SyntheticClass(_) | MetadataKind::MultiFileClassFacade { .. } => &[],
+        }
+    }
+
+    /// Finds the Kotlin function compiled to the given JVM method.
+    #[must_use]
+    pub fn function_of(&self, method: &Method) -> Option<&Function> {
+        let descriptor = method.descriptor.to_string();
+        self.functions().iter().find(|function| {
+            function
+                .jvm_signature
+                .as_ref()
+                .is_some_and(|sig| sig.name == method.name && sig.descriptor == descriptor)
+        })
+    }
+}
+
+impl Class {
+    /// Decodes the Kotlin metadata of the class.
+    /// Returns `None` if the class is not annotated with `kotlin.Metadata`.
+    ///
+    /// # Errors
+    /// See [`KotlinMetadataError`].
+    pub fn kotlin_metadata(&self) -> Result<Option<KotlinMetadata>, KotlinMetadataError> {
+        let metadata_type = FieldType::Object(ClassRef::new(KotlinMetadata::ANNOTATION_NAME));
+        self.runtime_visible_annotations
+            .iter()
+            .find(|it| it.annotation_type == metadata_type)
+            .map(KotlinMetadata::from_annotation)
+            .transpose()
+    }
+}
@@ -0,0 +1,235 @@
+//! The protobuf messages in the `d1` element of `kotlin.Metadata`.
+//!
+//! The messages mirror `metadata.proto` and `jvm_metadata.proto` in the Kotlin compiler, where
+//! the JVM-specific extensions are declared as plain fields with the extension numbers.
+//! Enum fields are kept as integers and interpreted by the caller.
+
+#![allow(clippy::struct_field_names)]
+
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct StringTableTypes {
+    #[prost(message, repeated, tag = "1")]
+    pub record: Vec<Record>,
+    #[prost(int32, repeated, packed = "true", tag = "5")]
+    pub local_name: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Record {
+    #[prost(int32, optional, tag = "1", default = "1")]
+    pub range: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub predefined_index: Option<i32>,
+    #[prost(string, optional, tag = "6")]
+    pub string: Option<String>,
+    #[prost(int32, optional, tag = "3")]
+    pub operation: Option<i32>,
+    #[prost(int32, repeated, packed = "true", tag = "4")]
+    pub substring_index: Vec<i32>,
+    #[prost(int32, repeated, packed = "true", tag = "5")]
+    pub replace_char: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Class {
+    #[prost(int32, optional, tag = "1", default = "6")]
+    pub flags: Option<i32>,
+    #[prost(int32, optional, tag = "3")]
+    pub fq_name: Option<i32>,
+    #[prost(int32, optional, tag = "4")]
+    pub companion_object_name: Option<i32>,
+    #[prost(message, repeated, tag = "5")]
+    pub type_parameter: Vec<TypeParameter>,
+    #[prost(message, repeated, tag = "6")]
+    pub supertype: Vec<Type>,
+    #[prost(int32, repeated, packed = "true", tag = "2")]
+    pub supertype_id: Vec<i32>,
+    #[prost(int32, repeated, packed = "true", tag = "7")]
+    pub nested_class_name: Vec<i32>,
+    #[prost(message, repeated, tag = "8")]
+    pub constructor: Vec<Constructor>,
+    #[prost(message, repeated, tag = "9")]
+    pub function: Vec<Function>,
+    #[prost(message, repeated, tag = "10")]
+    pub property: Vec<Property>,
+    #[prost(message, repeated, tag = "13")]
+    pub enum_entry: Vec<EnumEntry>,
+    #[prost(int32, repeated, packed = "true", tag = "16")]
+    pub sealed_subclass_fq_name: Vec<i32>,
+    #[prost(message, optional, tag = "30")]
+    pub type_table: Option<TypeTable>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Package {
+    #[prost(message, repeated, tag = "3")]
+    pub function: Vec<Function>,
+    #[prost(message, repeated, tag = "4")]
+    pub property: Vec<Property>,
+    #[prost(message, optional, tag = "30")]
+    pub type_table: Option<TypeTable>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Constructor {
+    #[prost(int32, optional, tag = "1", default = "6")]
+    pub flags: Option<i32>,
+    #[prost(message, repeated, tag = "2")]
+    pub value_parameter: Vec<ValueParameter>,
+    #[prost(message, optional, tag = "100")]
+    pub signature: Option<JvmMethodSignature>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Function {
+    #[prost(int32, optional, tag = "9", default = "6")]
+    pub flags: Option<i32>,
+    #[prost(int32, optional, tag = "1", default = "6")]
+    pub old_flags: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub name: Option<i32>,
+    #[prost(message, optional, tag = "3")]
+    pub return_type: Option<Type>,
+    #[prost(int32, optional, tag = "7")]
+    pub return_type_id: Option<i32>,
+    #[prost(message, repeated, tag = "4")]
+    pub type_parameter: Vec<TypeParameter>,
+    #[prost(message, optional, tag = "5")]
+    pub receiver_type: Option<Type>,
+    #[prost(int32, optional, tag = "8")]
+    pub receiver_type_id: Option<i32>,
+    #[prost(message, repeated, tag = "6")]
+    pub value_parameter: Vec<ValueParameter>,
+    #[prost(message, optional, tag = "30")]
+    pub type_table: Option<TypeTable>,
+    #[prost(message, optional, tag = "100")]
+    pub signature: Option<JvmMethodSignature>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Property {
+    #[prost(int32, optional, tag = "11", default = "518")]
+    pub flags: Option<i32>,
+    #[prost(int32, optional, tag = "1", default = "2054")]
+    pub old_flags: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub name: Option<i32>,
+    #[prost(message, optional, tag = "3")]
+    pub return_type: Option<Type>,
+    #[prost(int32, optional, tag = "9")]
+    pub return_type_id: Option<i32>,
+    #[prost(message, repeated, tag = "4")]
+    pub type_parameter: Vec<TypeParameter>,
+    #[prost(message, optional, tag = "5")]
+    pub receiver_type: Option<Type>,
+    #[prost(int32, optional, tag = "10")]
+    pub receiver_type_id: Option<i32>,
+    #[prost(message, optional, tag = "100")]
+    pub signature: Option<JvmPropertySignature>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct ValueParameter {
+    #[prost(int32, optional, tag = "1", default = "0")]
+    pub flags: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub name: Option<i32>,
+    #[prost(message, optional, tag = "3")]
+    pub r#type: Option<Type>,
+    #[prost(int32, optional, tag = "5")]
+    pub type_id: Option<i32>,
+    #[prost(message, optional, tag = "4")]
+    pub vararg_element_type: Option<Type>,
+    #[prost(int32, optional, tag = "6")]
+    pub vararg_element_type_id: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Type {
+    #[prost(int32, optional, tag = "1", default = "0")]
+    pub flags: Option<i32>,
+    #[prost(message, repeated, tag = "2")]
+    pub argument: Vec<Argument>,
+    #[prost(bool, optional, tag = "3", default = "false")]
+    pub nullable: Option<bool>,
+    #[prost(message, optional, boxed, tag = "5")]
+    pub flexible_upper_bound: Option<Box<Type>>,
+    #[prost(int32, optional, tag = "8")]
+    pub flexible_upper_bound_id: Option<i32>,
+    #[prost(int32, optional, tag = "6")]
+    pub class_name: Option<i32>,
+    #[prost(int32, optional, tag = "7")]
+    pub type_parameter: Option<i32>,
+    #[prost(int32, optional, tag = "9")]
+    pub type_parameter_name: Option<i32>,
+    #[prost(int32, optional, tag = "12")]
+    pub type_alias_name: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct Argument {
+    #[prost(int32, optional, tag = "1", default = "2")]
+    pub projection: Option<i32>,
+    #[prost(message, optional, tag = "2")]
+    pub r#type: Option<Type>,
+    #[prost(int32, optional, tag = "3")]
+    pub type_id: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct TypeTable {
+    #[prost(message, repeated, tag = "1")]
+    pub r#type: Vec<Type>,
+    #[prost(int32, optional, tag = "2", default = "-1")]
+    pub first_nullable: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct TypeParameter {
+    #[prost(int32, optional, tag = "1")]
+    pub id: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub name: Option<i32>,
+    #[prost(bool, optional, tag = "3", default = "false")]
+    pub reified: Option<bool>,
+    #[prost(int32, optional, tag = "4", default = "2")]
+    pub variance: Option<i32>,
+    #[prost(message, repeated, tag = "5")]
+    pub upper_bound: Vec<Type>,
+    #[prost(int32, repeated, packed = "true", tag = "6")]
+    pub upper_bound_id: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct EnumEntry {
+    #[prost(int32, optional, tag = "1")]
+    pub name: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct JvmMethodSignature {
+    #[prost(int32, optional, tag = "1")]
+    pub name: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub desc: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct JvmFieldSignature {
+    #[prost(int32, optional, tag = "1")]
+    pub name: Option<i32>,
+    #[prost(int32, optional, tag = "2")]
+    pub desc: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(super) struct JvmPropertySignature {
+    #[prost(message, optional, tag = "1")]
+    pub field: Option<JvmFieldSignature>,
+    #[prost(message, optional, tag = "3")]
+    pub getter: Option<JvmMethodSignature>,
+    #[prost(message, optional, tag = "4")]
+    pub setter: Option<JvmMethodSignature>,
+}
@@ -10,6 +10,7 @@ use crate::{
 use super::{
     annotation::{Annotation, ElementValue, TypeAnnotation},
     code::MethodBody,
+    custom_attribute::CustomAttributes,
     references::{ClassRef, MethodRef},
 };
 
@@ -53,6 +54,8 @@ pub struct Method {
     pub signature: Option<MethodSignature>,
     /// Unrecognized JVM attributes.
     pub free_attributes: Vec<(String, Vec<u8>)>,
+    /// Attributes decoded by custom [`AttributeDecoder`](crate::jvm::parsing::AttributeDecoder)s.
+    pub custom_attributes: CustomAttributes,
 }
 
 impl Method {
@@ -73,6 +76,28 @@ impl Method {
         self.name == Self::CLASS_INITIALIZER_NAME
     }
 
+    /// Checks if the method is signature polymorphic (e.g., `MethodHandle::invokeExact`).
+    #[doc = see_jvm_spec!(2, 9, 3)]
+    #[must_use]
+    pub fn is_signature_polymorphic(&self) -> bool {
+        use crate::types::field_type::FieldType;
+
+        let declared_in_handle_class = matches!(
+            self.owner.binary_name.as_str(),
+            "java/lang/invoke/MethodHandle" | "java/lang/invoke/VarHandle"
+        );
+        let takes_object_array = matches!(
+            self.descriptor.parameters_types.as_slice(),
+            [FieldType::Array(elem)]
+                if matches!(elem.as_ref(), FieldType::Object(it) if it.binary_name == "java/lang/Object")
+        );
+        declared_in_handle_class
+            && takes_object_array
+            && self
+                .access_flags
+                .contains(AccessFlags::VARARGS | AccessFlags::NATIVE)
+    }
+
     /// Creates a [`MethodRef`] pointting to this method.
     #[must_use]
     pub fn as_ref(&self) -> MethodRef {
@@ -164,6 +189,7 @@ mod tests {
             is_deprecated: false,
             signature: None,
             free_attributes: vec![],
+            custom_attributes: CustomAttributes::default(),
         }
     }
 
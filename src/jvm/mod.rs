@@ -5,8 +5,13 @@ pub mod class;
 pub mod class_loader;
 pub mod code;
 pub mod constant_pool;
+pub mod custom_attribute;
 pub mod field;
+pub mod interner;
+#[cfg(feature = "kotlin")]
+pub mod kotlin;
 pub mod method;
 pub mod module;
 pub mod parsing;
 pub mod references;
+pub mod smap;
@@ -1,5 +1,6 @@
 use std::{
-    io::{self, Read},
+    io::{self, Read, Seek, SeekFrom},
+    sync::Arc,
     usize,
 };
 
@@ -10,6 +11,7 @@ use crate::{
         annotation::{Annotation, ElementValue, TypeAnnotation},
         class::{BootstrapMethod, EnclosingMethod, InnerClassInfo, RecordComponent},
         code::{LineNumberTableEntry, MethodBody, StackMapFrame},
+        custom_attribute::CustomAttribute,
         field::ConstantValue,
         method::ParameterInfo,
         module::Module,
@@ -21,21 +23,46 @@ use crate::{
 use super::{
     code::{LocalVariableDescAttr, LocalVariableTypeAttr},
     jvm_element_parser::ClassElement,
-    reader_utils::{read_byte_chunk, ReadBytes, ValueReaderExt},
-    Context, Error,
+    reader_utils::{read_byte_chunk, ReadBytes, TrackedReader, ValueReaderExt},
+    Context, Error, PathSegment, WarningKind,
 };
 
 /// Represent an attribute of a class file, method, field, or code.
 #[doc = see_jvm_spec!(4, 7)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct AttributeInfo {
     name_idx: u16,
     info: Vec<u8>,
+    /// The offset of the attribute in the class file if it is not nested in another attribute.
+    offset: Option<usize>,
 }
 
 impl AttributeInfo {
     fn from_raw_parts(name_idx: u16, info: Vec<u8>) -> Self {
-        Self { name_idx, info }
+        Self {
+            name_idx,
+            info,
+            offset: None,
+        }
+    }
+
+    /// Resolves the name of the attribute.
+    pub(super) fn name<'c>(&self, ctx: &'c Context) -> Result<&'c str, Error> {
+        ctx.constant_pool
+            .get_str(self.name_idx)
+            .map_err(|e| e.at_offset(self.offset))
+    }
+
+    /// Reads an attribute, recording its offset in the class file.
+    pub(super) fn read_tracked<R: Read + ?Sized>(
+        reader: &mut TrackedReader<'_, R>,
+    ) -> io::Result<Self> {
+        let offset = reader.position();
+        let attribute = Self::read_bytes(reader)?;
+        Ok(Self {
+            offset: Some(offset),
+            ..attribute
+        })
     }
 }
 
@@ -84,6 +111,7 @@ pub(crate) enum Attribute {
     Record(Vec<RecordComponent>),
     PermittedSubclasses(Vec<ClassRef>),
     Unrecognized(String, Vec<u8>),
+    Custom(String, Arc<dyn CustomAttribute>),
 }
 
 impl Attribute {
@@ -119,7 +147,7 @@ impl Attribute {
             Self::NestMembers(_) => "NestMembers",
             Self::Record(_) => "Record",
             Self::PermittedSubclasses(_) => "PermittedSubclasses",
-            Self::Unrecognized(name, _) => name,
+            Self::Unrecognized(name, _) | Self::Custom(name, _) => name,
         }
     }
 }
@@ -131,19 +159,67 @@ macro_rules! parse {
     }};
     ($len_type:ty; $reader:expr, || $with:expr $(=> $attr:ident )?) => {{
         let count: $len_type = $reader.read_value()?;
-        (0..count).map(|_| $with).try_collect()$( .map(Self::$attr) )?
+        (0..count)
+            .map(|i| {
+                let item: Result<_, Error> = (|| $with)();
+                item.map_err(|e| e.within(PathSegment::Index(usize::from(i))))
+            })
+            .try_collect()$( .map(Self::$attr) )?
     }};
     ($len_type:ty; $reader:expr, $ctx:expr $(=> $attr:ident )?) => {
         parse![$len_type; $reader, || parse!($reader, $ctx)] $( .map(Self::$attr) )?
     };
 }
 
+impl Attribute {
+    /// Decodes the attributes of a structure.
+    /// In lenient mode, an attribute that cannot be decoded is kept as unrecognized, or dropped if
+    /// even its name cannot be resolved.
+    pub(super) fn from_raw_all(raw: &[AttributeInfo], ctx: &Context) -> Result<Vec<Self>, Error> {
+        if !ctx.is_lenient() {
+            return raw.iter().map(|it| Self::from_info(it, ctx)).collect();
+        }
+        let mut attributes = Vec::with_capacity(raw.len());
+        for attribute in raw {
+            match Self::from_info(attribute, ctx) {
+                Ok(it) => attributes.push(it),
+                Err(err) => {
+                    let name = ctx.constant_pool.get_str(attribute.name_idx).ok();
+                    let kind = WarningKind::MalformedAttribute {
+                        name: name.map(str::to_owned),
+                        message: err.root().to_string(),
+                    };
+                    ctx.warn_or(attribute.offset, kind, err)?;
+                    if let Some(name) = name {
+                        attributes
+                            .push(Self::Unrecognized(name.to_owned(), attribute.info.clone()));
+                    }
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Decodes an attribute without taking the ownership of its content.
+    pub(super) fn from_info(raw: &AttributeInfo, ctx: &Context) -> Result<Self, Error> {
+        let name = raw.name(ctx)?;
+        Self::decode(name, &raw.info, ctx).map_err(|e| {
+            e.within(PathSegment::Element(name.to_owned()))
+                .at_offset(raw.offset)
+        })
+    }
+}
+
 impl ClassElement for Attribute {
     type Raw = AttributeInfo;
 
     fn from_raw(raw: Self::Raw, ctx: &Context) -> Result<Self, Error> {
-        let AttributeInfo { name_idx, info } = raw;
-        let name = ctx.constant_pool.get_str(name_idx)?;
+        Self::from_info(&raw, ctx)
+    }
+}
+
+impl Attribute {
+    fn decode(name: &str, info: &[u8], ctx: &Context) -> Result<Self, Error> {
         let reader = &mut io::Cursor::new(info);
 
         let result = match name {
@@ -215,11 +291,18 @@ impl ClassElement for Attribute {
                 let idx = reader.read_value()?;
                 ctx.constant_pool.get_class_ref(idx)
             } => PermittedSubclasses],
-            name => reader
-                .bytes()
-                .try_collect()
-                .map(|bytes| Attribute::Unrecognized(name.to_owned(), bytes))
-                .map_err(Into::into),
+            name => match ctx.options.attribute_decoders().get(name) {
+                Some(decoder) => {
+                    let value = decoder.decode_dyn(info, &ctx.constant_pool)?;
+                    reader.seek(SeekFrom::End(0))?;
+                    Ok(Self::Custom(name.to_owned(), value))
+                }
+                None => reader
+                    .bytes()
+                    .try_collect()
+                    .map(|bytes| Attribute::Unrecognized(name.to_owned(), bytes))
+                    .map_err(Into::into),
+            },
         }?;
         match reader.read(&mut [0]) {
             Ok(0) => Ok(result),
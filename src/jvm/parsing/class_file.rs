@@ -3,35 +3,41 @@ use std::io::{self, Read};
 use crate::{
     jvm::{
         class::{
-            self, BootstrapMethod, Class, EnclosingMethod, InnerClassInfo, NestedClassAccessFlags,
-            RecordComponent, Version,
+            BootstrapMethod, Class, EnclosingMethod, InnerClassInfo, NestedClassAccessFlags,
+            RecordComponent,
         },
         constant_pool::ConstantPool,
         parsing::reader_utils::ValueReaderExt,
-        references::ClassRef,
     },
-    macros::{extract_attributes, malform, see_jvm_spec},
+    macros::{extract_attributes, see_jvm_spec},
 };
 
 use super::{
-    attribute::AttributeInfo, field_info::FieldInfo, jvm_element_parser::ClassElement,
-    method_info::MethodInfo, raw_attributes, reader_utils::ReadBytes, Context, Error,
+    attribute::AttributeInfo,
+    diagnostics::Diagnostics,
+    field_info::FieldInfo,
+    jvm_element_parser::ClassElement,
+    method_info::MethodInfo,
+    raw_attributes,
+    reader_utils::{ReadBytes, TrackedReader},
+    Context, Error, LazyClass, ParseOptions, ParseWarning,
 };
 
 /// The raw representation of a class file.
 #[doc = see_jvm_spec!(4, 1)]
 #[derive(Debug)]
 pub(crate) struct ClassFile {
-    minor_version: u16,
-    major_version: u16,
-    constant_pool: ConstantPool,
-    access_flags: u16,
-    this_class: u16,
-    super_class: u16,
-    interfaces: Vec<u16>,
-    fields: Vec<FieldInfo>,
-    methods: Vec<MethodInfo>,
-    attributes: Vec<AttributeInfo>,
+    pub(super) minor_version: u16,
+    pub(super) major_version: u16,
+    pub(super) constant_pool: ConstantPool,
+    pub(super) access_flags: u16,
+    pub(super) access_flags_offset: usize,
+    pub(super) this_class: u16,
+    pub(super) super_class: u16,
+    pub(super) interfaces: Vec<u16>,
+    pub(super) fields: Vec<FieldInfo>,
+    pub(super) methods: Vec<MethodInfo>,
+    pub(super) attributes: Vec<AttributeInfo>,
 }
 const JAVA_CLASS_MAIGC: u32 = 0xCAFE_BABE;
 
@@ -47,10 +53,50 @@ impl Class {
         let class_file = ClassFile::read_bytes(&mut reader)?;
         Class::from_raw(class_file)
     }
+
+    /// Parses a class file from the given reader, recovering from the problems that do not
+    /// prevent the class from being used, e.g., for analyzing obfuscated or newer class files.
+    ///
+    /// In particular,
+    /// - attributes that cannot be decoded are kept in the `free_attributes`,
+    /// - unknown bits in access flags are retained,
+    /// - class files with a newer version keep their version and invalid minor versions are
+    ///   replaced with `0`, and
+    /// - methods violating the constraints on their bodies are accepted.
+    ///
+    /// Each recovered problem is reported as a [`ParseWarning`] along with the class.
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn from_reader_lenient<R>(reader: R) -> Result<(Class, Vec<ParseWarning>), Error>
+    where
+        R: std::io::Read,
+    {
+        Class::from_reader_with_options(reader, &ParseOptions::new().with_lenient(true))
+    }
+
+    /// Parses a class file from the given reader with the given options.
+    /// The warnings are always empty unless the options enable lenient parsing.
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn from_reader_with_options<R>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Class, Vec<ParseWarning>), Error>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = reader;
+        let class_file = ClassFile::read_bytes(&mut reader)?;
+        let diagnostics = options.is_lenient().then(Diagnostics::default);
+        let class = LazyClass::from_raw(class_file, options, diagnostics.clone())?.into_class()?;
+        let warnings = diagnostics.map(|it| it.take()).unwrap_or_default();
+        Ok((class, warnings))
+    }
 }
 
 impl ReadBytes for ClassFile {
     fn read_bytes<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let reader = &mut TrackedReader::new(reader);
         let magic: u32 = reader.read_value()?;
         if magic != JAVA_CLASS_MAIGC {
             return Err(io::Error::new(
@@ -58,10 +104,20 @@ impl ReadBytes for ClassFile {
                 "This is not a Java class file",
             ));
         }
+        Self::read_contents(reader).map_err(|e| {
+            io::Error::new(e.kind(), format!("{e} at offset {:#x}", reader.position()))
+        })
+    }
+}
+
+impl ClassFile {
+    /// Reads the class file after the magic number.
+    fn read_contents<R: Read + ?Sized>(reader: &mut TrackedReader<'_, R>) -> io::Result<Self> {
         let minor_version = reader.read_value()?;
         let major_version = reader.read_value()?;
         let constant_pool_count = reader.read_value()?;
         let constant_pool = ConstantPool::from_reader(reader, constant_pool_count)?;
+        let access_flags_offset = reader.position();
         let access_flags = reader.read_value()?;
         let this_class = reader.read_value()?;
         let super_class = reader.read_value()?;
@@ -71,15 +127,15 @@ impl ReadBytes for ClassFile {
             .collect::<io::Result<_>>()?;
         let fields_count: u16 = reader.read_value()?;
         let fields = (0..fields_count)
-            .map(|_| FieldInfo::read_bytes(reader))
+            .map(|_| FieldInfo::read_tracked(reader))
             .collect::<io::Result<_>>()?;
         let methods_count: u16 = reader.read_value()?;
         let methods = (0..methods_count)
-            .map(|_| MethodInfo::read_bytes(reader))
+            .map(|_| MethodInfo::read_tracked(reader))
             .collect::<io::Result<_>>()?;
         let attributes_count: u16 = reader.read_value()?;
         let attributes = (0..attributes_count)
-            .map(|_| AttributeInfo::read_bytes(reader))
+            .map(|_| AttributeInfo::read_tracked(reader))
             .collect::<io::Result<_>>()?;
 
         // Make sure there is no extra data in the reader
@@ -89,6 +145,7 @@ impl ReadBytes for ClassFile {
                 major_version,
                 constant_pool,
                 access_flags,
+                access_flags_offset,
                 this_class,
                 super_class,
                 interfaces,
@@ -104,108 +161,7 @@ impl ReadBytes for ClassFile {
 
 impl Class {
     pub(crate) fn from_raw(raw: ClassFile) -> Result<Self, Error> {
-        let ClassFile {
-            minor_version,
-            major_version,
-            constant_pool,
-            access_flags,
-            this_class,
-            super_class,
-            interfaces,
-            fields,
-            methods,
-            attributes,
-        } = raw;
-        let version = Version::from_versions(major_version, minor_version)?;
-        let access_flags = class::AccessFlags::from_bits(access_flags)
-            .ok_or(Error::UnknownFlags("ClassAccessFlags", access_flags))?;
-        let ClassRef { binary_name } = constant_pool.get_class_ref(this_class)?;
-        let super_class = match super_class {
-            0 if binary_name == "java/lang/Object" => None,
-            0 if access_flags.contains(class::AccessFlags::MODULE) => None,
-            0 => malform!("Class must have a super type except for java/lang/Object or a module"),
-            it => Some(constant_pool.get_class_ref(it)?),
-        };
-
-        let parsing_context = Context {
-            constant_pool,
-            class_version: version,
-            current_class_binary_name: binary_name.clone(),
-        };
-
-        let ctx = &parsing_context;
-
-        let interfaces = interfaces
-            .into_iter()
-            .map(|it| ctx.constant_pool.get_class_ref(it))
-            .collect::<Result<_, _>>()?;
-        let fields = fields
-            .into_iter()
-            .map(|it| ClassElement::from_raw(it, ctx))
-            .collect::<Result<_, _>>()?;
-        let methods = methods
-            .into_iter()
-            .map(|it| ClassElement::from_raw(it, ctx))
-            .collect::<Result<_, _>>()?;
-        let attributes: Vec<Attribute> = attributes
-            .into_iter()
-            .map(|it| ClassElement::from_raw(it, ctx))
-            .collect::<Result<_, _>>()?;
-
-        extract_attributes! {
-            for attributes in "class_file" {
-                let source_file: SourceFile,
-                let inner_classes: InnerClasses as unwrap_or_default,
-                let enclosing_method: EnclosingMethod,
-                let source_debug_extension: SourceDebugExtension,
-                let bootstrap_methods: BootstrapMethods as unwrap_or_default,
-                let runtime_visible_annotations: RuntimeVisibleAnnotations as unwrap_or_default,
-                let runtime_invisible_annotations: RuntimeInvisibleAnnotations as unwrap_or_default,
-                let runtime_visible_type_annotations: RuntimeVisibleTypeAnnotations as unwrap_or_default,
-                let runtime_invisible_type_annotations: RuntimeInvisibleTypeAnnotations as unwrap_or_default,
-                let module: Module,
-                let module_packages: ModulePackages as unwrap_or_default,
-                let module_main_class: ModuleMainClass,
-                let nest_host: NestHost,
-                let nest_members: NestMembers as unwrap_or_default,
-                let permitted_subclasses: PermittedSubclasses as unwrap_or_default,
-                let signature: Signature,
-                let record: Record,
-                if let is_synthetic: Synthetic,
-                if let is_deprecated: Deprecated,
-                else let free_attributes
-            }
-        };
-
-        Ok(Class {
-            version,
-            access_flags,
-            binary_name,
-            super_class,
-            interfaces,
-            fields,
-            methods,
-            source_file,
-            inner_classes,
-            enclosing_method,
-            source_debug_extension,
-            runtime_visible_annotations,
-            runtime_invisible_annotations,
-            runtime_visible_type_annotations,
-            runtime_invisible_type_annotations,
-            bootstrap_methods,
-            module,
-            module_packages,
-            module_main_class,
-            nest_host,
-            nest_members,
-            permitted_subclasses,
-            is_synthetic,
-            is_deprecated,
-            signature,
-            record,
-            free_attributes,
-        })
+        LazyClass::from_raw(raw, &ParseOptions::default(), None)?.into_class()
     }
 }
 
@@ -248,8 +204,8 @@ impl ClassElement for InnerClassInfo {
         } else {
             Some(ctx.constant_pool.get_str(inner_name_index)?.to_owned())
         };
-        let access_flags = NestedClassAccessFlags::from_bits(access_flags)
-            .ok_or(Error::UnknownFlags("NextClassAccessFlags", access_flags))?;
+        let access_flags: NestedClassAccessFlags =
+            ctx.parse_flags(access_flags, "NextClassAccessFlags", None)?;
         Ok(Self {
             inner_class,
             outer_class,
@@ -283,7 +239,7 @@ impl ClassElement for RecordComponent {
                     : RuntimeVisibleTypeAnnotations as unwrap_or_default,
                 let runtime_invisible_type_annotations
                     : RuntimeInvisibleTypeAnnotations as unwrap_or_default,
-                else let free_attributes
+                else let free_attributes, custom_attributes
             }
         }
 
@@ -296,6 +252,7 @@ impl ClassElement for RecordComponent {
             runtime_visible_type_annotations,
             runtime_invisible_type_annotations,
             free_attributes,
+            custom_attributes,
         })
     }
 }
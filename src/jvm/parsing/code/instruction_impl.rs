@@ -9,7 +9,7 @@ use crate::{
             WideInstruction,
         },
         constant_pool::{self, ConstantPool},
-        parsing::{jvm_element_parser::ClassElement, Context, Error},
+        parsing::{constant_pool::mismatch, jvm_element_parser::ClassElement, Context, Error},
     },
     macros::malform,
     types::field_type::PrimitiveType,
@@ -360,10 +360,7 @@ impl Instruction {
                     name_and_type_index,
                 } = entry
                 else {
-                    Err(Error::MismatchedConstantPoolEntryType {
-                        expected: "InvokeDynamic",
-                        found: entry.constant_kind(),
-                    })?
+                    return mismatch("InvokeDynamic", dynamic_index, entry);
                 };
                 let (name, descriptor) = constant_pool.get_name_and_type(name_and_type_index)?;
                 Self::InvokeDynamic {
@@ -3,6 +3,7 @@ pub(super) mod raw_instruction;
 pub(super) mod stack_map;
 
 use std::{
+    collections::BTreeMap,
     io::{self, Read},
     str::FromStr,
 };
@@ -147,8 +148,8 @@ impl ClassElement for ParameterInfo {
         } else {
             Some(ctx.constant_pool.get_str(name_index)?.to_owned())
         };
-        let access_flags = ParameterAccessFlags::from_bits(access_flags)
-            .ok_or(Error::UnknownFlags("ParameterAccessFlags", access_flags))?;
+        let access_flags: ParameterAccessFlags =
+            ctx.parse_flags(access_flags, "ParameterAccessFlags", None)?;
         Ok(ParameterInfo { name, access_flags })
     }
 }
@@ -166,6 +167,14 @@ impl ClassElement for MethodBody {
         } = raw;
 
         let raw_instructions = RawInstruction::from_bytes(instruction_bytes)?;
+        let constant_pool_indices = if ctx.options.retains_constant_pool() {
+            raw_instructions
+                .iter()
+                .filter_map(|(pc, it)| it.constant_pool_index().map(|index| (*pc, index)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
         let instructions = ClassElement::from_raw(raw_instructions, ctx)?;
 
         let exception_table = exception_table
@@ -197,7 +206,7 @@ impl ClassElement for MethodBody {
                         table.merge_signature(id, name, signature)?;
                     }
                 },
-                else let free_attributes
+                else let free_attributes, custom_attributes
             }
         }
 
@@ -212,6 +221,8 @@ impl ClassElement for MethodBody {
             runtime_visible_type_annotations,
             runtime_invisible_type_annotations,
             free_attributes,
+            custom_attributes,
+            constant_pool_indices,
         })
     }
 }
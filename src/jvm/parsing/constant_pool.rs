@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     io::{self, Read},
     str::FromStr,
 };
@@ -19,47 +20,108 @@ use crate::{
 };
 
 #[inline]
-const fn mismatch<T>(expected: &'static str, entry: &Entry) -> Result<T, Error> {
+pub(super) fn mismatch<T>(expected: &'static str, index: u16, entry: &Entry) -> Result<T, Error> {
     Err(Error::MismatchedConstantPoolEntryType {
         expected,
         found: entry.constant_kind(),
-    })
+    }
+    .at_constant_pool_index(index))
 }
 
 impl ConstantPool {
-    pub(super) fn get_str(&self, index: u16) -> Result<&str, Error> {
+    /// Returns the classes referenced by the `CONSTANT_Class_info` entries, including array
+    /// types (e.g., `[Ljava/lang/String;`).
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn referenced_classes(&self) -> Result<BTreeSet<ClassRef>, Error> {
+        self.entries()
+            .filter(|(_, entry)| matches!(entry, Entry::Class { .. }))
+            .map(|(index, _)| self.get_class_ref(index))
+            .collect()
+    }
+
+    /// Returns the fields referenced by the `CONSTANT_Fieldref_info` entries.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn referenced_fields(&self) -> Result<BTreeSet<FieldRef>, Error> {
+        self.entries()
+            .filter(|(_, entry)| matches!(entry, Entry::FieldRef { .. }))
+            .map(|(index, _)| self.get_field_ref(index))
+            .collect()
+    }
+
+    /// Returns the methods referenced by the `CONSTANT_Methodref_info` and
+    /// `CONSTANT_InterfaceMethodref_info` entries.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn referenced_methods(&self) -> Result<BTreeSet<MethodRef>, Error> {
+        self.entries()
+            .filter(|(_, entry)| {
+                matches!(
+                    entry,
+                    Entry::MethodRef { .. } | Entry::InterfaceMethodRef { .. }
+                )
+            })
+            .map(|(index, _)| self.get_method_ref(index))
+            .collect()
+    }
+
+    /// Gets the string in a `CONSTANT_Utf8_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry has an unexpected type.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_str(&self, index: u16) -> Result<&str, Error> {
         let entry = self.get_entry(index)?;
         match entry {
             Entry::Utf8(JavaString::Utf8(string)) => Ok(string),
-            Entry::Utf8(JavaString::InvalidUtf8(_)) => Err(Error::BrokenUTF8),
-            it => mismatch("Utf8", it),
+            Entry::Utf8(JavaString::InvalidUtf8(_)) => {
+                Err(Error::BrokenUTF8.at_constant_pool_index(index))
+            }
+            it => mismatch("Utf8", index, it),
         }
     }
 
-    pub(super) fn get_class_ref(&self, index: u16) -> Result<ClassRef, Error> {
+    /// Gets the class referenced by a `CONSTANT_Class_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry has an unexpected type.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_class_ref(&self, index: u16) -> Result<ClassRef, Error> {
         let entry = self.get_entry(index)?;
         if let &Entry::Class { name_index } = entry {
             let name = self.get_str(name_index)?;
             Ok(ClassRef::new(name))
         } else {
-            mismatch("Class", entry)
+            mismatch("Class", index, entry)
         }
     }
 
-    pub(super) fn get_constant_value(&self, value_index: u16) -> Result<ConstantValue, Error> {
-        let entry = self.get_entry(value_index)?;
+    /// Gets the value of a loadable constant, e.g., a `CONSTANT_Integer_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry has an unexpected type.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_constant_value(&self, index: u16) -> Result<ConstantValue, Error> {
+        let entry = self.get_entry(index)?;
         match entry {
             &Entry::Integer(it) => Ok(ConstantValue::Integer(it)),
             &Entry::Long(it) => Ok(ConstantValue::Long(it)),
             &Entry::Float(it) => Ok(ConstantValue::Float(it)),
             &Entry::Double(it) => Ok(ConstantValue::Double(it)),
-            &Entry::String { string_index } => {
-                if let Entry::Utf8(java_str) = self.get_entry(string_index)? {
-                    Ok(ConstantValue::String(java_str.clone()))
-                } else {
-                    mismatch("Utf8", entry)
-                }
-            }
+            &Entry::String { string_index } => match self.get_entry(string_index)? {
+                Entry::Utf8(java_str) => Ok(ConstantValue::String(java_str.clone())),
+                it => mismatch("Utf8", string_index, it),
+            },
             &Entry::MethodType { descriptor_index } => self
                 .get_str(descriptor_index)
                 .and_then(|it| it.parse().map_err(Into::into))
@@ -68,9 +130,7 @@ impl ConstantPool {
                 .get_str(name_index)
                 .map(ClassRef::new)
                 .map(ConstantValue::Class),
-            Entry::MethodHandle { .. } => self
-                .get_method_handle(value_index)
-                .map(ConstantValue::Handle),
+            Entry::MethodHandle { .. } => self.get_method_handle(index).map(ConstantValue::Handle),
             &Entry::Dynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
@@ -87,6 +147,7 @@ impl ConstantPool {
                     "Integer | Long | Float | Double | String ",
                     "| MethodType | Class | MethodHandle | Dynamic"
                 ),
+                index,
                 unexpected,
             ),
         }
@@ -98,7 +159,7 @@ impl ConstantPool {
             let name = self.get_str(name_index)?.to_owned();
             Ok(ModuleRef { name })
         } else {
-            mismatch("Module", entry)
+            mismatch("Module", index, entry)
         }
     }
 
@@ -110,11 +171,18 @@ impl ConstantPool {
                 binary_name: name.to_owned(),
             })
         } else {
-            mismatch("Package", entry)
+            mismatch("Package", index, entry)
         }
     }
 
-    pub(super) fn get_field_ref(&self, index: u16) -> Result<FieldRef, Error> {
+    /// Gets the field referenced by a `CONSTANT_Fieldref_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry has an unexpected type.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_field_ref(&self, index: u16) -> Result<FieldRef, Error> {
         let entry = self.get_entry(index)?;
         if let &Entry::FieldRef {
             class_index,
@@ -129,7 +197,7 @@ impl ConstantPool {
                 field_type,
             })
         } else {
-            mismatch("Field", entry)
+            mismatch("Field", index, entry)
         }
     }
 
@@ -151,11 +219,19 @@ impl ConstantPool {
                 .map_err(Into::into)?;
             Ok((name.to_owned(), descriptor))
         } else {
-            mismatch("NameAndType", entry)
+            mismatch("NameAndType", index, entry)
         }
     }
 
-    pub(super) fn get_method_ref(&self, index: u16) -> Result<MethodRef, Error> {
+    /// Gets the method referenced by a `CONSTANT_Methodref_info` or
+    /// `CONSTANT_InterfaceMethodref_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry has an unexpected type.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_method_ref(&self, index: u16) -> Result<MethodRef, Error> {
         let entry = self.get_entry(index)?;
         if let &Entry::MethodRef {
             class_index,
@@ -174,7 +250,7 @@ impl ConstantPool {
                 descriptor,
             })
         } else {
-            mismatch("MethodRef | InterfaceMethodRef", entry)
+            mismatch("MethodRef | InterfaceMethodRef", index, entry)
         }
     }
 
@@ -188,7 +264,7 @@ impl ConstantPool {
             reference_index: idx,
         } = entry
         else {
-            return mismatch("MethodHandle", entry);
+            return mismatch("MethodHandle", index, entry);
         };
         match reference_kind {
             1 => self.get_field_ref(idx).map(RefGetField),
@@ -0,0 +1,68 @@
+//! Diagnostics reported when parsing class files in lenient mode.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A problem in a class file that is tolerated when parsing in lenient mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// The offset in bytes from the start of the class file of the structure that causes the
+    /// warning, or `None` if the structure is nested in an attribute.
+    pub offset: Option<usize>,
+    /// What is wrong with the structure.
+    pub kind: WarningKind,
+}
+
+/// The kind of a [`ParseWarning`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// The class file version is not supported.
+    /// A newer version is kept as it is, while an invalid minor version is replaced with `0`.
+    UnsupportedVersion {
+        /// The major version in the class file.
+        major: u16,
+        /// The minor version in the class file.
+        minor: u16,
+    },
+    /// The flags contain unknown bits, which are retained.
+    UnknownFlags {
+        /// The type of the flags.
+        flags_type: &'static str,
+        /// The raw flags.
+        flags: u16,
+    },
+    /// An attribute cannot be decoded, so it is kept as a free attribute.
+    MalformedAttribute {
+        /// The name of the attribute, or `None` if the name cannot be resolved, in which case
+        /// the attribute is dropped.
+        name: Option<String>,
+        /// The error that occurs when decoding the attribute.
+        message: String,
+    },
+    /// A method violates the constraints of the JVM specification.
+    InvalidMethod {
+        /// The name of the method.
+        name: String,
+        /// The violated constraint.
+        message: &'static str,
+    },
+}
+
+/// A shared list of warnings collected during parsing.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Diagnostics(Arc<Mutex<Vec<ParseWarning>>>);
+
+impl Diagnostics {
+    /// Records a warning unless an identical one is already recorded, since lazily decoded
+    /// members may be checked more than once.
+    pub(super) fn warn(&self, warning: ParseWarning) {
+        let mut warnings = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+
+    pub(super) fn take(&self) -> Vec<ParseWarning> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
@@ -1,10 +1,16 @@
+use std::fmt::{self, Display, Formatter};
+
 use crate::{
     jvm::{code::InvalidOffset, constant_pool::BadConstantPoolIndex},
     types::method_descriptor::InvalidDescriptor,
 };
 
 /// An error that occurs when parsing a Java class file.
+///
+/// Errors occurring in a known location are wrapped in [`Error::Located`]; use [`Error::root`] to
+/// get the underlying error.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// An error that occurs when reading from a buffer.
     #[error("Failed to read from buffer: {0}")]
@@ -62,4 +68,110 @@ pub enum Error {
     /// The instruction list is too long.
     #[error("The instruction list is too long, it should be at most 65536 bytes")]
     TooLongInstructionList,
+    /// An error with the location in the class file where it occurs.
+    /// It is displayed as the location only; the error itself is its [`source`].
+    ///
+    /// [`source`]: std::error::Error::source
+    #[error("{context}")]
+    Located {
+        /// Where the error occurs.
+        context: ErrorContext,
+        /// The error without the location.
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Returns the location where the error occurs, if known.
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Located { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without the location, e.g., [`Error::MismatchedConstantPoolEntryType`]
+    /// for an error located at the index of the mismatched constant pool entry.
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Located { source, .. } => source,
+            it => it,
+        }
+    }
+
+    fn map_context(self, f: impl FnOnce(&mut ErrorContext)) -> Self {
+        let (mut context, source) = match self {
+            Self::Located { context, source } => (context, source),
+            it => (ErrorContext::default(), Box::new(it)),
+        };
+        f(&mut context);
+        Self::Located { context, source }
+    }
+
+    /// Adds an enclosing element to the path of the error.
+    pub(super) fn within(self, segment: PathSegment) -> Self {
+        self.map_context(|it| it.path.insert(0, segment))
+    }
+
+    /// Sets the offset of the error unless a more precise one is already known.
+    pub(super) fn at_offset(self, offset: Option<usize>) -> Self {
+        match offset {
+            Some(_) if self.context().and_then(|it| it.offset).is_none() => {
+                self.map_context(|it| it.offset = offset)
+            }
+            _ => self,
+        }
+    }
+
+    /// Sets the index of the constant pool entry involved in the error.
+    pub(super) fn at_constant_pool_index(self, index: u16) -> Self {
+        self.map_context(|it| it.constant_pool_index = Some(index))
+    }
+}
+
+/// The location of a parsing [`Error`] in a class file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The offset in bytes from the start of the class file of the innermost structure
+    /// containing the error, if known.
+    pub offset: Option<usize>,
+    /// The path from the outermost element (e.g., a method) to the element containing the error.
+    pub path: Vec<PathSegment>,
+    /// The index of the constant pool entry involved in the error, if any.
+    pub constant_pool_index: Option<u16>,
+}
+
+/// An element in the path of an [`ErrorContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A named element, e.g., `Foo.bar(I)V` for a method or `Code` for an attribute.
+    Element(String),
+    /// The index of an item in the enclosing element, e.g., a frame in a `StackMapTable`.
+    Index(usize),
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut is_first = true;
+        for segment in &self.path {
+            match segment {
+                PathSegment::Element(name) if is_first => write!(f, "{name}")?,
+                PathSegment::Element(name) => write!(f, " > {name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+            is_first = false;
+        }
+        if let Some(offset) = self.offset {
+            let separator = if is_first { "" } else { " " };
+            write!(f, "{separator}at offset {offset:#x}")?;
+            is_first = false;
+        }
+        if let Some(index) = self.constant_pool_index {
+            let separator = if is_first { "" } else { " " };
+            write!(f, "{separator}(constant pool index {index})")?;
+        }
+        Ok(())
+    }
 }
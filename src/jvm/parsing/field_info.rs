@@ -10,93 +10,103 @@ use crate::{
 
 use super::{
     attribute::AttributeInfo,
-    jvm_element_parser::ClassElement,
-    reader_utils::{ReadBytes, ValueReaderExt},
+    reader_utils::{TrackedReader, ValueReaderExt},
     Context, Error,
 };
 
 /// The raw representation of a `field_info` structure.
 #[doc = see_jvm_spec!(4, 5)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct FieldInfo {
-    access_flags: u16,
-    name_index: u16,
-    descriptor_index: u16,
+    pub(super) access_flags: u16,
+    pub(super) name_index: u16,
+    pub(super) descriptor_index: u16,
     attributes: Vec<AttributeInfo>,
+    /// The offset of the structure in the class file.
+    pub(super) offset: usize,
 }
 
-impl ReadBytes for FieldInfo {
-    fn read_bytes<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+impl FieldInfo {
+    pub(super) fn read_tracked<R: Read + ?Sized>(
+        reader: &mut TrackedReader<'_, R>,
+    ) -> io::Result<Self> {
+        let offset = reader.position();
         let access_flags = reader.read_value()?;
         let name_index = reader.read_value()?;
         let descriptor_index = reader.read_value()?;
         let attributes_count: u16 = reader.read_value()?;
         let attributes = (0..attributes_count)
-            .map(|_| AttributeInfo::read_bytes(reader))
+            .map(|_| AttributeInfo::read_tracked(reader))
             .collect::<io::Result<_>>()?;
         Ok(Self {
             access_flags,
             name_index,
             descriptor_index,
             attributes,
+            offset,
         })
     }
 }
 
-impl ClassElement for Field {
-    type Raw = FieldInfo;
+impl Field {
+    /// Decodes a `field_info` structure including its attributes.
+    pub(super) fn from_info(raw: &FieldInfo, ctx: &Context) -> Result<Self, Error> {
+        decode(raw, ctx).map_err(|e| {
+            e.within(ctx.member_path(raw.name_index, raw.descriptor_index, ":"))
+                .at_offset(Some(raw.offset))
+        })
+    }
+}
 
-    fn from_raw(raw: Self::Raw, ctx: &Context) -> Result<Self, Error> {
-        let FieldInfo {
-            access_flags,
-            name_index,
-            descriptor_index,
-            attributes,
-        } = raw;
-        let access_flags = field::AccessFlags::from_bits(access_flags)
-            .ok_or(Error::UnknownFlags("FieldAccessFlag", access_flags))?;
-        let name = ctx.constant_pool.get_str(name_index)?.to_owned();
-        let field_type = ctx.constant_pool.get_str(descriptor_index)?.parse()?;
-        let owner = ClassRef {
-            binary_name: ctx.current_class_binary_name.clone(),
-        };
-        let attributes: Vec<Attribute> = attributes
-            .into_iter()
-            .map(|it| Attribute::from_raw(it, ctx))
-            .collect::<Result<_, _>>()?;
+fn decode(raw: &FieldInfo, ctx: &Context) -> Result<Field, Error> {
+    let &FieldInfo {
+        access_flags,
+        name_index,
+        descriptor_index,
+        ref attributes,
+        offset,
+    } = raw;
+    let access_flags: field::AccessFlags =
+        ctx.parse_flags(access_flags, "FieldAccessFlag", Some(offset))?;
+    let name = ctx.constant_pool.get_str(name_index)?.to_owned();
+    let field_type = ctx.constant_pool.get_str(descriptor_index)?.parse()?;
+    let owner = ClassRef {
+        binary_name: ctx.current_class_binary_name.clone(),
+    };
+    let attributes = Attribute::from_raw_all(attributes, ctx)?;
 
-        extract_attributes! {
-            for attributes in "field_info" {
-                let constant_value: ConstantValue,
-                let signature: Signature,
-                let runtime_visible_annotations
-                    : RuntimeVisibleAnnotations as unwrap_or_default,
-                let runtime_invisible_annotations
-                    : RuntimeInvisibleAnnotations as unwrap_or_default,
-                let runtime_visible_type_annotations
-                    : RuntimeVisibleTypeAnnotations as unwrap_or_default,
-                let runtime_invisible_type_annotations
-                    : RuntimeInvisibleTypeAnnotations as unwrap_or_default,
-                if let is_synthetic: Synthetic,
-                if let is_deperecated: Deprecated,
-                else let free_attributes
-            }
+    extract_attributes! {
+        for attributes in "field_info" {
+            let constant_value: ConstantValue,
+            let signature: Signature,
+            let runtime_visible_annotations
+                : RuntimeVisibleAnnotations as unwrap_or_default,
+            let runtime_invisible_annotations
+                : RuntimeInvisibleAnnotations as unwrap_or_default,
+            let runtime_visible_type_annotations
+                : RuntimeVisibleTypeAnnotations as unwrap_or_default,
+            let runtime_invisible_type_annotations
+                : RuntimeInvisibleTypeAnnotations as unwrap_or_default,
+            if let is_synthetic: Synthetic,
+            if let is_deperecated: Deprecated,
+            else let free_attributes, custom_attributes
         }
-
-        Ok(Field {
-            access_flags,
-            name,
-            owner,
-            field_type,
-            constant_value,
-            is_synthetic,
-            is_deperecated,
-            signature,
-            runtime_visible_annotations,
-            runtime_invisible_annotations,
-            runtime_visible_type_annotations,
-            runtime_invisible_type_annotations,
-            free_attributes,
-        })
     }
+
+    Ok(Field {
+        access_flags,
+        name,
+        owner,
+        field_type,
+        constant_value,
+        is_synthetic,
+        is_deperecated,
+        signature,
+        runtime_visible_annotations,
+        runtime_invisible_annotations,
+        runtime_visible_type_annotations,
+        runtime_invisible_type_annotations,
+        free_attributes,
+        custom_attributes,
+    })
 }
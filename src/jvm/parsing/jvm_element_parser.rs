@@ -14,7 +14,7 @@ where
 {
     type Raw = u16;
 
-    fn from_raw(raw: Self::Raw, _ctx: &Context) -> Result<Self, Error> {
-        T::from_bits(raw).ok_or(Error::UnknownFlags(std::any::type_name::<Self>(), raw))
+    fn from_raw(raw: Self::Raw, ctx: &Context) -> Result<Self, Error> {
+        ctx.parse_flags(raw, std::any::type_name::<Self>(), None)
     }
 }
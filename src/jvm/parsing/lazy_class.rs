@@ -0,0 +1,494 @@
+//! Lazily decoded classes.
+
+use std::{
+    io::Read,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    jvm::{
+        annotation::Annotation,
+        class::{
+            self, BootstrapMethod, Class, EnclosingMethod, InnerClassInfo, RecordComponent, Version,
+        },
+        code::MethodBody,
+        constant_pool::ConstantPool,
+        field::{self, Field},
+        method::{self, Method},
+        references::ClassRef,
+    },
+    macros::{extract_attributes, malform},
+    types::{field_type::FieldType, method_descriptor::MethodDescriptor},
+};
+
+use super::{
+    attribute::{Attribute, AttributeInfo},
+    class_file::ClassFile,
+    diagnostics::Diagnostics,
+    field_info::FieldInfo,
+    method_info::MethodInfo,
+    options::ParseOptions,
+    reader_utils::ReadBytes,
+    Context, Error, ParseWarning, PathSegment, WarningKind,
+};
+
+/// The offset of the `minor_version` item in a class file.
+const VERSION_OFFSET: usize = 4;
+
+/// Generates the accessors decoding a single class attribute, which return the default value
+/// (i.e., `None` or an empty list) when the attribute is absent.
+macro_rules! attribute_accessors {
+    ($( $(#[$doc:meta])* $name:ident: $attr:ident -> $ty:ty; )*) => {$(
+        $(#[$doc])*
+        /// The attribute is decoded on each call without decoding the others.
+        ///
+        /// # Errors
+        /// See [`Error`] for more information.
+        pub fn $name(&self) -> Result<$ty, Error> {
+            Ok(match self.decode_attribute(stringify!($attr))? {
+                Some(Attribute::$attr(it)) => it.into(),
+                _ => <$ty>::default(),
+            })
+        }
+    )*};
+}
+
+/// A class whose header, supertypes and member signatures are decoded eagerly, while the
+/// attributes (e.g., method bodies, annotations, and tables) are decoded on first access.
+///
+/// This avoids decoding method bodies when only the structure of the class is needed, e.g., for
+/// building a class hierarchy; the `lazy_class` benchmark compares it with [`Class::from_reader`].
+/// The class attributes can be decoded individually with the accessors such as
+/// [`LazyClass::source_file`].
+/// Note that malformed attributes are not detected until they are decoded.
+#[derive(Debug)]
+pub struct LazyClass {
+    /// The version of the class file.
+    pub version: Version,
+    /// The access modifiers of the class.
+    pub access_flags: class::AccessFlags,
+    /// The binary name of the class (e.g., `org/mokapot/jvm/Class`).
+    pub binary_name: String,
+    /// A reference to the superclass of the class.
+    pub super_class: Option<ClassRef>,
+    /// The interfaces implemented by the class.
+    pub interfaces: Vec<ClassRef>,
+    /// The fields declared the class.
+    pub fields: Vec<LazyField>,
+    /// The methods declared in the class.
+    pub methods: Vec<LazyMethod>,
+    context: Arc<Context>,
+    attributes: Vec<AttributeInfo>,
+}
+
+impl LazyClass {
+    /// Parses the header and the member signatures of a class file from the given reader.
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn from_reader<R>(reader: R) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        let mut reader = reader;
+        let class_file = ClassFile::read_bytes(&mut reader)?;
+        Self::from_raw(class_file, &ParseOptions::default(), None)
+    }
+
+    /// Parses the header and the member signatures of a class file from the given reader in
+    /// lenient mode, as in [`Class::from_reader_lenient`].
+    ///
+    /// Since the attributes are decoded on access, the problems in them are only reported after
+    /// they are decoded. Use [`LazyClass::take_warnings`] to get the warnings reported so far.
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn from_reader_lenient<R>(reader: R) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        Self::from_reader_with_options(reader, &ParseOptions::new().with_lenient(true))
+    }
+
+    /// Parses the header and the member signatures of a class file from the given reader with
+    /// the given options, as in [`Class::from_reader_with_options`].
+    /// See [`LazyClass::from_reader_lenient`] for the warnings in lenient mode.
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn from_reader_with_options<R>(reader: R, options: &ParseOptions) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        let mut reader = reader;
+        let class_file = ClassFile::read_bytes(&mut reader)?;
+        let diagnostics = options.is_lenient().then(Diagnostics::default);
+        Self::from_raw(class_file, options, diagnostics)
+    }
+
+    pub(super) fn from_raw(
+        raw: ClassFile,
+        options: &ParseOptions,
+        diagnostics: Option<Diagnostics>,
+    ) -> Result<Self, Error> {
+        let ClassFile {
+            minor_version,
+            major_version,
+            constant_pool,
+            access_flags,
+            access_flags_offset,
+            this_class,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            attributes,
+        } = raw;
+        let version = match (
+            Version::from_versions(major_version, minor_version),
+            &diagnostics,
+        ) {
+            (Err(_), Some(diagnostics)) if major_version >= 45 => {
+                diagnostics.warn(ParseWarning {
+                    offset: Some(VERSION_OFFSET),
+                    kind: WarningKind::UnsupportedVersion {
+                        major: major_version,
+                        minor: minor_version,
+                    },
+                });
+                // Versions newer than the supported ones are kept as they are, assuming that the
+                // class files are backward compatible.
+                Version::new(major_version, minor_version)
+                    .or_else(|| Version::new(major_version, 0))
+                    .ok_or(Error::Other("Invalid class version"))?
+            }
+            (version, _) => version?,
+        };
+        let mut context = Context {
+            constant_pool: Arc::new(constant_pool),
+            class_version: version,
+            current_class_binary_name: String::new(),
+            options: options.clone(),
+            diagnostics,
+        };
+        let access_flags: class::AccessFlags =
+            context.parse_flags(access_flags, "ClassAccessFlags", Some(access_flags_offset))?;
+        let ClassRef { binary_name } = context.constant_pool.get_class_ref(this_class)?;
+        let super_class = match super_class {
+            0 if binary_name == "java/lang/Object" => None,
+            0 if access_flags.contains(class::AccessFlags::MODULE) => None,
+            0 => malform!("Class must have a super type except for java/lang/Object or a module"),
+            it => Some(context.constant_pool.get_class_ref(it)?),
+        };
+        context.current_class_binary_name.clone_from(&binary_name);
+        let context = Arc::new(context);
+        let interfaces = interfaces
+            .into_iter()
+            .map(|it| context.constant_pool.get_class_ref(it))
+            .collect::<Result<_, _>>()?;
+        let fields = fields
+            .into_iter()
+            .map(|it| LazyField::new(it, &context))
+            .collect::<Result<_, _>>()?;
+        let methods = methods
+            .into_iter()
+            .map(|it| LazyMethod::new(it, &context))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            version,
+            access_flags,
+            binary_name,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            context,
+            attributes,
+        })
+    }
+
+    /// Returns the constant pool of the class file.
+    #[must_use]
+    pub fn constant_pool(&self) -> &ConstantPool {
+        &self.context.constant_pool
+    }
+
+    /// Takes the warnings reported since the class is parsed or this method is last called.
+    /// The warnings are always empty unless the class is parsed in lenient mode.
+    #[must_use]
+    pub fn take_warnings(&self) -> Vec<ParseWarning> {
+        self.context
+            .diagnostics
+            .as_ref()
+            .map(Diagnostics::take)
+            .unwrap_or_default()
+    }
+
+    attribute_accessors! {
+        /// Decodes the `SourceFile` attribute.
+        source_file: SourceFile -> Option<String>;
+        /// Decodes the `InnerClasses` attribute.
+        inner_classes: InnerClasses -> Vec<InnerClassInfo>;
+        /// Decodes the `EnclosingMethod` attribute.
+        enclosing_method: EnclosingMethod -> Option<EnclosingMethod>;
+        /// Decodes the `SourceDebugExtension` attribute.
+        source_debug_extension: SourceDebugExtension -> Option<Vec<u8>>;
+        /// Decodes the `RuntimeVisibleAnnotations` attribute.
+        runtime_visible_annotations: RuntimeVisibleAnnotations -> Vec<Annotation>;
+        /// Decodes the `RuntimeInvisibleAnnotations` attribute.
+        runtime_invisible_annotations: RuntimeInvisibleAnnotations -> Vec<Annotation>;
+        /// Decodes the `BootstrapMethods` attribute.
+        bootstrap_methods: BootstrapMethods -> Vec<BootstrapMethod>;
+        /// Decodes the `NestHost` attribute.
+        nest_host: NestHost -> Option<ClassRef>;
+        /// Decodes the `NestMembers` attribute.
+        nest_members: NestMembers -> Vec<ClassRef>;
+        /// Decodes the `PermittedSubclasses` attribute.
+        permitted_subclasses: PermittedSubclasses -> Vec<ClassRef>;
+        /// Decodes the `Record` attribute.
+        record: Record -> Option<Vec<RecordComponent>>;
+    }
+
+    /// Decodes the first class attribute with the given name, if any.
+    /// In lenient mode, a malformed attribute is reported as a warning and decoded as
+    /// [`Attribute::Unrecognized`].
+    fn decode_attribute(&self, name: &str) -> Result<Option<Attribute>, Error> {
+        let Some(info) = self
+            .attributes
+            .iter()
+            .find(|it| it.name(&self.context).is_ok_and(|it| it == name))
+        else {
+            return Ok(None);
+        };
+        Attribute::from_raw_all(std::slice::from_ref(info), &self.context)
+            .map(|it| it.into_iter().next())
+            .map_err(|e| e.within(PathSegment::Element(self.binary_name.clone())))
+    }
+
+    /// Decodes the remaining parts of the class.
+    /// The members that are already decoded are reused.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn into_class(self) -> Result<Class, Error> {
+        let Self {
+            version,
+            access_flags,
+            binary_name,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            context,
+            attributes,
+        } = self;
+        let fields = fields
+            .into_iter()
+            .map(LazyField::into_field)
+            .collect::<Result<_, _>>()?;
+        let methods = methods
+            .into_iter()
+            .map(LazyMethod::into_method)
+            .collect::<Result<_, _>>()?;
+        let ctx = context.as_ref();
+        let attributes = Attribute::from_raw_all(&attributes, ctx)
+            .map_err(|e| e.within(PathSegment::Element(binary_name.clone())))?;
+
+        extract_attributes! {
+            for attributes in "class_file" {
+                let source_file: SourceFile,
+                let inner_classes: InnerClasses as unwrap_or_default,
+                let enclosing_method: EnclosingMethod,
+                let source_debug_extension: SourceDebugExtension,
+                let bootstrap_methods: BootstrapMethods as unwrap_or_default,
+                let runtime_visible_annotations: RuntimeVisibleAnnotations as unwrap_or_default,
+                let runtime_invisible_annotations: RuntimeInvisibleAnnotations as unwrap_or_default,
+                let runtime_visible_type_annotations: RuntimeVisibleTypeAnnotations as unwrap_or_default,
+                let runtime_invisible_type_annotations: RuntimeInvisibleTypeAnnotations as unwrap_or_default,
+                let module: Module,
+                let module_packages: ModulePackages as unwrap_or_default,
+                let module_main_class: ModuleMainClass,
+                let nest_host: NestHost,
+                let nest_members: NestMembers as unwrap_or_default,
+                let permitted_subclasses: PermittedSubclasses as unwrap_or_default,
+                let signature: Signature,
+                let record: Record,
+                if let is_synthetic: Synthetic,
+                if let is_deprecated: Deprecated,
+                else let free_attributes, custom_attributes
+            }
+        };
+
+        Ok(Class {
+            version,
+            access_flags,
+            binary_name,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            source_file,
+            inner_classes,
+            enclosing_method,
+            source_debug_extension,
+            runtime_visible_annotations,
+            runtime_invisible_annotations,
+            runtime_visible_type_annotations,
+            runtime_invisible_type_annotations,
+            bootstrap_methods,
+            module,
+            module_packages,
+            module_main_class,
+            nest_host,
+            nest_members,
+            permitted_subclasses,
+            is_synthetic,
+            is_deprecated,
+            signature,
+            record,
+            free_attributes,
+            custom_attributes,
+            constant_pool: context
+                .options
+                .retains_constant_pool()
+                .then(|| Arc::clone(&context.constant_pool)),
+        })
+    }
+}
+
+/// A field whose signature is decoded eagerly and whose attributes are decoded on first access.
+#[derive(Debug)]
+pub struct LazyField {
+    /// The access modifiers of the field.
+    pub access_flags: field::AccessFlags,
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    pub field_type: FieldType,
+    raw: FieldInfo,
+    context: Arc<Context>,
+    decoded: OnceLock<Field>,
+}
+
+impl LazyField {
+    fn new(raw: FieldInfo, context: &Arc<Context>) -> Result<Self, Error> {
+        let signature = || -> Result<_, Error> {
+            let access_flags =
+                context.parse_flags(raw.access_flags, "FieldAccessFlag", Some(raw.offset))?;
+            let name = context.constant_pool.get_str(raw.name_index)?.to_owned();
+            let field_type = context
+                .constant_pool
+                .get_str(raw.descriptor_index)?
+                .parse()?;
+            Ok((access_flags, name, field_type))
+        };
+        let (access_flags, name, field_type) = signature().map_err(|e| {
+            e.within(context.member_path(raw.name_index, raw.descriptor_index, ":"))
+                .at_offset(Some(raw.offset))
+        })?;
+        Ok(Self {
+            access_flags,
+            name,
+            field_type,
+            raw,
+            context: Arc::clone(context),
+            decoded: OnceLock::new(),
+        })
+    }
+
+    /// Decodes the field including its attributes.
+    /// The result is cached, so the attributes are decoded at most once.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn decode(&self) -> Result<&Field, Error> {
+        if let Some(field) = self.decoded.get() {
+            return Ok(field);
+        }
+        let field = Field::from_info(&self.raw, &self.context)?;
+        Ok(self.decoded.get_or_init(|| field))
+    }
+
+    /// Decodes the field including its attributes, reusing the decoded one if any.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn into_field(self) -> Result<Field, Error> {
+        match self.decoded.into_inner() {
+            Some(field) => Ok(field),
+            None => Field::from_info(&self.raw, &self.context),
+        }
+    }
+}
+
+/// A method whose signature is decoded eagerly and whose body and other attributes are decoded
+/// on first access.
+#[derive(Debug)]
+pub struct LazyMethod {
+    /// The access flags of the method.
+    pub access_flags: method::AccessFlags,
+    /// The name of the method.
+    pub name: String,
+    /// The descriptor of the method.
+    pub descriptor: MethodDescriptor,
+    raw: MethodInfo,
+    context: Arc<Context>,
+    decoded: OnceLock<Method>,
+}
+
+impl LazyMethod {
+    fn new(raw: MethodInfo, context: &Arc<Context>) -> Result<Self, Error> {
+        let signature = || -> Result<_, Error> {
+            let access_flags =
+                context.parse_flags(raw.access_flags, "MethodAccessFlags", Some(raw.offset))?;
+            let name = context.constant_pool.get_str(raw.name_index)?.to_owned();
+            let descriptor = context
+                .constant_pool
+                .get_str(raw.descriptor_index)?
+                .parse()?;
+            Ok((access_flags, name, descriptor))
+        };
+        let (access_flags, name, descriptor) = signature().map_err(|e| {
+            e.within(context.member_path(raw.name_index, raw.descriptor_index, ""))
+                .at_offset(Some(raw.offset))
+        })?;
+        Ok(Self {
+            access_flags,
+            name,
+            descriptor,
+            raw,
+            context: Arc::clone(context),
+            decoded: OnceLock::new(),
+        })
+    }
+
+    /// Decodes the method including its body and other attributes.
+    /// The result is cached, so the attributes are decoded at most once.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn decode(&self) -> Result<&Method, Error> {
+        if let Some(method) = self.decoded.get() {
+            return Ok(method);
+        }
+        let method = Method::from_info(&self.raw, &self.context)?;
+        Ok(self.decoded.get_or_init(|| method))
+    }
+
+    /// Decodes the body of the method.
+    /// Returns `None` if the method is `abstract` or `native`.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn body(&self) -> Result<Option<&MethodBody>, Error> {
+        self.decode().map(|it| it.body.as_ref())
+    }
+
+    /// Decodes the method including its attributes, reusing the decoded one if any.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn into_method(self) -> Result<Method, Error> {
+        match self.decoded.into_inner() {
+            Some(method) => Ok(method),
+            None => Method::from_info(&self.raw, &self.context),
+        }
+    }
+}
@@ -6,140 +6,169 @@ use crate::{
         parsing::Context,
         references::ClassRef,
     },
-    macros::{extract_attributes, malform, see_jvm_spec},
+    macros::{extract_attributes, see_jvm_spec},
     types::method_descriptor::MethodDescriptor,
 };
 
 use super::{
     attribute::AttributeInfo,
-    jvm_element_parser::ClassElement,
-    reader_utils::{ReadBytes, ValueReaderExt},
-    Error,
+    reader_utils::{TrackedReader, ValueReaderExt},
+    Error, WarningKind,
 };
 
 /// The raw representation of a `method_info` structure.
 #[doc = see_jvm_spec!(4, 6)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct MethodInfo {
-    access_flags: u16,
-    name_index: u16,
-    descriptor_index: u16,
+    pub(super) access_flags: u16,
+    pub(super) name_index: u16,
+    pub(super) descriptor_index: u16,
     attributes: Vec<AttributeInfo>,
+    /// The offset of the structure in the class file.
+    pub(super) offset: usize,
 }
 
-impl ReadBytes for MethodInfo {
-    fn read_bytes<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+impl MethodInfo {
+    pub(super) fn read_tracked<R: Read + ?Sized>(
+        reader: &mut TrackedReader<'_, R>,
+    ) -> io::Result<Self> {
+        let offset = reader.position();
         let access_flags = reader.read_value()?;
         let name_index = reader.read_value()?;
         let descriptor_index = reader.read_value()?;
         let attributes_count: u16 = reader.read_value()?;
         let attributes = (0..attributes_count)
-            .map(|_| AttributeInfo::read_bytes(reader))
+            .map(|_| AttributeInfo::read_tracked(reader))
             .collect::<io::Result<_>>()?;
         Ok(Self {
             access_flags,
             name_index,
             descriptor_index,
             attributes,
+            offset,
         })
     }
 }
 
-impl ClassElement for Method {
-    type Raw = MethodInfo;
-
-    fn from_raw(raw: Self::Raw, ctx: &Context) -> Result<Self, Error> {
-        let MethodInfo {
-            access_flags,
-            name_index,
-            descriptor_index,
-            attributes,
-        } = raw;
-        let access_flags = method::AccessFlags::from_bits(access_flags)
-            .ok_or(Error::UnknownFlags("MethodAccessFlags", access_flags))?;
-        let name = ctx.constant_pool.get_str(name_index)?.to_owned();
-        let descriptor: MethodDescriptor = ctx.constant_pool.get_str(descriptor_index)?.parse()?;
-        let owner = ClassRef {
-            binary_name: ctx.current_class_binary_name.clone(),
+impl Context {
+    fn invalid_method(
+        &self,
+        name: &str,
+        offset: usize,
+        message: &'static str,
+    ) -> Result<(), Error> {
+        let kind = WarningKind::InvalidMethod {
+            name: name.to_owned(),
+            message,
         };
+        self.warn_or(Some(offset), kind, Error::Other(message))
+    }
+}
 
-        let attributes: Vec<Attribute> = attributes
-            .into_iter()
-            .map(|it| Attribute::from_raw(it, ctx))
-            .collect::<Result<_, _>>()?;
-        extract_attributes! {
-            for attributes in "method_info" {
-                let body: Code,
-                let exceptions: Exceptions as unwrap_or_default,
-                let runtime_visible_annotations
-                    : RuntimeVisibleAnnotations as unwrap_or_default,
-                let runtime_invisible_annotations
-                    : RuntimeInvisibleAnnotations as unwrap_or_default,
-                let runtime_visible_type_annotations
-                    : RuntimeVisibleTypeAnnotations as unwrap_or_default,
-                let runtime_invisible_type_annotations
-                    : RuntimeInvisibleTypeAnnotations as unwrap_or_default,
-                let runtime_visible_parameter_annotations
-                    : RuntimeVisibleParameterAnnotations as unwrap_or_default,
-                let runtime_invisible_parameter_annotations
-                    : RuntimeInvisibleParameterAnnotations as unwrap_or_default,
-                let annotation_default: AnnotationDefault,
-                let parameters: MethodParameters as unwrap_or_default,
-                let signature: Signature,
-                if let is_synthetic: Synthetic,
-                if let is_deprecated: Deprecated,
-                else let free_attributes
-            }
-        };
+impl Method {
+    /// Decodes a `method_info` structure including its attributes.
+    pub(super) fn from_info(raw: &MethodInfo, ctx: &Context) -> Result<Self, Error> {
+        decode(raw, ctx).map_err(|e| {
+            e.within(ctx.member_path(raw.name_index, raw.descriptor_index, ""))
+                .at_offset(Some(raw.offset))
+        })
+    }
+}
 
-        // JVM specification 4.7.3
-        // If the method is either `native` or `abstract`, and is not a class or interface initialization method
-        if (access_flags.contains(method::AccessFlags::NATIVE)
-            || access_flags.contains(method::AccessFlags::ABSTRACT))
-            && name != Method::CLASS_INITIALIZER_NAME
-        {
-            // then its method_info structure must not have a Code attribute in its attributes table
-            if body.is_some() {
-                malform!("Unexpected code attribute");
-            }
-        } else {
-            // Otherwise, its method_info structure must have exactly one Code attribute in its attributes table
-            if body.is_none() {
-                malform!("The method must have a body");
-            }
+fn decode(raw: &MethodInfo, ctx: &Context) -> Result<Method, Error> {
+    let &MethodInfo {
+        access_flags,
+        name_index,
+        descriptor_index,
+        ref attributes,
+        offset,
+    } = raw;
+    let access_flags: method::AccessFlags =
+        ctx.parse_flags(access_flags, "MethodAccessFlags", Some(offset))?;
+    let name = ctx.constant_pool.get_str(name_index)?.to_owned();
+    let descriptor: MethodDescriptor = ctx.constant_pool.get_str(descriptor_index)?.parse()?;
+    let owner = ClassRef {
+        binary_name: ctx.current_class_binary_name.clone(),
+    };
+
+    let attributes = Attribute::from_raw_all(attributes, ctx)?;
+    extract_attributes! {
+        for attributes in "method_info" {
+            let body: Code,
+            let exceptions: Exceptions as unwrap_or_default,
+            let runtime_visible_annotations
+                : RuntimeVisibleAnnotations as unwrap_or_default,
+            let runtime_invisible_annotations
+                : RuntimeInvisibleAnnotations as unwrap_or_default,
+            let runtime_visible_type_annotations
+                : RuntimeVisibleTypeAnnotations as unwrap_or_default,
+            let runtime_invisible_type_annotations
+                : RuntimeInvisibleTypeAnnotations as unwrap_or_default,
+            let runtime_visible_parameter_annotations
+                : RuntimeVisibleParameterAnnotations as unwrap_or_default,
+            let runtime_invisible_parameter_annotations
+                : RuntimeInvisibleParameterAnnotations as unwrap_or_default,
+            let annotation_default: AnnotationDefault,
+            let parameters: MethodParameters as unwrap_or_default,
+            let signature: Signature,
+            if let is_synthetic: Synthetic,
+            if let is_deprecated: Deprecated,
+            else let free_attributes, custom_attributes
+        }
+    };
+
+    // JVM specification 4.7.3
+    // If the method is either `native` or `abstract`, and is not a class or interface initialization method
+    if (access_flags.contains(method::AccessFlags::NATIVE)
+        || access_flags.contains(method::AccessFlags::ABSTRACT))
+        && name != Method::CLASS_INITIALIZER_NAME
+    {
+        // then its method_info structure must not have a Code attribute in its attributes table
+        if body.is_some() {
+            ctx.invalid_method(&name, offset, "Unexpected code attribute")?;
+        }
+    } else {
+        // Otherwise, its method_info structure must have exactly one Code attribute in its attributes table
+        if body.is_none() {
+            ctx.invalid_method(&name, offset, "The method must have a body")?;
         }
+    }
 
-        if ctx.class_version.major() > 51 && name == Method::CLASS_INITIALIZER_NAME {
-            // In a class file whose version number is 51.0 or above, the method has its ACC_STATIC flag set and takes no arguments (§4.6).
-            if !access_flags.contains(method::AccessFlags::STATIC)
-                || !descriptor.parameters_types.is_empty()
-            {
-                malform!(concat!(
+    if ctx.class_version.major() > 51 && name == Method::CLASS_INITIALIZER_NAME {
+        // In a class file whose version number is 51.0 or above, the method has its ACC_STATIC flag set and takes no arguments (§4.6).
+        if !access_flags.contains(method::AccessFlags::STATIC)
+            || !descriptor.parameters_types.is_empty()
+        {
+            ctx.invalid_method(
+                &name,
+                offset,
+                concat!(
                     "Class initializer in class version 51 or above",
                     "must be static and takes no arguments"
-                ));
-            }
+                ),
+            )?;
         }
-
-        Ok(Method {
-            access_flags,
-            name,
-            descriptor,
-            owner,
-            body,
-            exceptions,
-            runtime_visible_annotations,
-            runtime_invisible_annotations,
-            runtime_visible_type_annotations,
-            runtime_invisible_type_annotations,
-            runtime_visible_parameter_annotations,
-            runtime_invisible_parameter_annotations,
-            annotation_default,
-            parameters,
-            is_synthetic,
-            is_deprecated,
-            signature,
-            free_attributes,
-        })
     }
+
+    Ok(Method {
+        access_flags,
+        name,
+        descriptor,
+        owner,
+        body,
+        exceptions,
+        runtime_visible_annotations,
+        runtime_invisible_annotations,
+        runtime_visible_type_annotations,
+        runtime_invisible_type_annotations,
+        runtime_visible_parameter_annotations,
+        runtime_invisible_parameter_annotations,
+        annotation_default,
+        parameters,
+        is_synthetic,
+        is_deprecated,
+        signature,
+        free_attributes,
+        custom_attributes,
+    })
 }
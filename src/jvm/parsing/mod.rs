@@ -4,24 +4,115 @@ mod attribute;
 pub(super) mod class_file;
 mod code;
 pub(super) mod constant_pool;
+mod diagnostics;
 pub(super) mod errors;
 mod field_info;
 mod jvm_element_parser;
+mod lazy_class;
 mod method_info;
 mod module;
+mod options;
 mod raw_attributes;
 mod reader_utils;
+mod view;
+
+use bitflags::Flags;
+
+use std::sync::Arc;
 
 use crate::jvm::{class::Version, constant_pool::ConstantPool};
-pub use errors::Error;
+use diagnostics::Diagnostics;
+pub use diagnostics::{ParseWarning, WarningKind};
+pub use errors::{Error, ErrorContext, PathSegment};
+pub use lazy_class::{LazyClass, LazyField, LazyMethod};
+pub use options::{AttributeDecoder, ParseOptions};
+pub use view::{AttributeView, ClassView, ConstantPoolView, JavaStr, MemberView};
 
 /// Context used to parse a class file.
 #[derive(Debug, Clone)]
 pub struct Context {
-    /// The constant pool of the class file.
-    pub constant_pool: ConstantPool,
+    /// The constant pool of the class file, which is shared with the parsed [`Class`] if
+    /// [`ParseOptions::with_retained_constant_pool`] is set.
+    ///
+    /// [`Class`]: crate::jvm::class::Class
+    pub constant_pool: Arc<ConstantPool>,
     /// The version of the class file being parsed.
     pub class_version: Version,
     /// The binary name of the class being parsed.
     pub current_class_binary_name: String,
+    /// The options used to parse the class file.
+    options: ParseOptions,
+    /// The warnings collected in lenient mode, or `None` in strict mode.
+    diagnostics: Option<Diagnostics>,
+}
+
+impl Context {
+    /// Creates a context for parsing a class file strictly with the default [`ParseOptions`].
+    #[must_use]
+    pub fn new(
+        constant_pool: ConstantPool,
+        class_version: Version,
+        current_class_binary_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            constant_pool: Arc::new(constant_pool),
+            class_version,
+            current_class_binary_name: current_class_binary_name.into(),
+            options: ParseOptions::default(),
+            diagnostics: None,
+        }
+    }
+
+    /// Checks whether problems that can be recovered from are reported as warnings rather than
+    /// errors.
+    fn is_lenient(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    /// Reports a warning in lenient mode, or returns `error` in strict mode.
+    fn warn_or(&self, offset: Option<usize>, kind: WarningKind, error: Error) -> Result<(), Error> {
+        match &self.diagnostics {
+            Some(diagnostics) => {
+                diagnostics.warn(ParseWarning { offset, kind });
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+
+    /// Describes a member of the current class in the path of an error, e.g., `Foo.bar(I)V`.
+    fn member_path(&self, name_index: u16, descriptor_index: u16, separator: &str) -> PathSegment {
+        let resolve = |index| {
+            self.constant_pool
+                .get_str(index)
+                .map_or_else(|_| format!("#{index}"), str::to_owned)
+        };
+        PathSegment::Element(format!(
+            "{}.{}{separator}{}",
+            self.current_class_binary_name,
+            resolve(name_index),
+            resolve(descriptor_index)
+        ))
+    }
+
+    /// Parses access flags, retaining the unknown bits in lenient mode.
+    fn parse_flags<F>(
+        &self,
+        flags: u16,
+        flags_type: &'static str,
+        offset: Option<usize>,
+    ) -> Result<F, Error>
+    where
+        F: Flags<Bits = u16>,
+    {
+        if let Some(parsed) = F::from_bits(flags) {
+            return Ok(parsed);
+        }
+        self.warn_or(
+            offset,
+            WarningKind::UnknownFlags { flags_type, flags },
+            Error::UnknownFlags(flags_type, flags),
+        )?;
+        Ok(F::from_bits_retain(flags))
+    }
 }
@@ -3,7 +3,9 @@ use crate::jvm::{
     module::{Export, Module, Open, Provide, Require},
 };
 
-use super::{jvm_element_parser::ClassElement, raw_attributes, Context, Error};
+use super::{
+    constant_pool::mismatch, jvm_element_parser::ClassElement, raw_attributes, Context, Error,
+};
 
 impl ClassElement for Require {
     type Raw = raw_attributes::RequiresInfo;
@@ -101,10 +103,7 @@ impl ClassElement for Module {
         } = raw;
         let module_info_entry = ctx.constant_pool.get_entry(info_index)?;
         let &Entry::Module { name_index } = module_info_entry else {
-            Err(Error::MismatchedConstantPoolEntryType {
-                expected: "Module",
-                found: module_info_entry.constant_kind(),
-            })?
+            return mismatch("Module", info_index, module_info_entry);
         };
         let name = ctx.constant_pool.get_str(name_index)?.to_owned();
         let flags = ClassElement::from_raw(flags, ctx)?;
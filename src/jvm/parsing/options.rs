@@ -0,0 +1,121 @@
+//! Options for parsing class files.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::jvm::{constant_pool::ConstantPool, custom_attribute::CustomAttribute};
+
+use super::Error;
+
+/// A decoder for attributes that are not defined in the JVM specification.
+pub trait AttributeDecoder: Send + Sync {
+    /// The type of the decoded attributes.
+    type Output: CustomAttribute;
+
+    /// Decodes the content of an attribute, i.e., the `info` bytes after `attribute_length`.
+    ///
+    /// # Errors
+    /// Returns an error if the attribute is malformed.
+    fn decode(&self, info: &[u8], constant_pool: &ConstantPool) -> Result<Self::Output, Error>;
+}
+
+/// An [`AttributeDecoder`] with the output type erased.
+pub(super) trait DynAttributeDecoder: Send + Sync {
+    fn decode_dyn(
+        &self,
+        info: &[u8],
+        constant_pool: &ConstantPool,
+    ) -> Result<Arc<dyn CustomAttribute>, Error>;
+}
+
+impl<D: AttributeDecoder> DynAttributeDecoder for D {
+    fn decode_dyn(
+        &self,
+        info: &[u8],
+        constant_pool: &ConstantPool,
+    ) -> Result<Arc<dyn CustomAttribute>, Error> {
+        let value = self.decode(info, constant_pool)?;
+        Ok(Arc::new(value))
+    }
+}
+
+/// The decoders registered in [`ParseOptions`], keyed by attribute names.
+pub(super) type AttributeDecoders = BTreeMap<String, Arc<dyn DynAttributeDecoder>>;
+
+/// Options for parsing class files.
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    lenient: bool,
+    retain_constant_pool: bool,
+    attribute_decoders: AttributeDecoders,
+}
+
+impl ParseOptions {
+    /// Creates the default options, i.e., strict parsing without custom attribute decoders, and
+    /// without retaining the constant pool.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to recover from the problems in class files and report them as warnings.
+    /// See [`Class::from_reader_lenient`](crate::jvm::class::Class::from_reader_lenient).
+    #[must_use]
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Sets whether to keep the constant pool in [`Class::constant_pool`] and the constant pool
+    /// indices of the operands in [`MethodBody::constant_pool_indices`].
+    ///
+    /// [`Class::constant_pool`]: crate::jvm::class::Class::constant_pool
+    /// [`MethodBody::constant_pool_indices`]: crate::jvm::code::MethodBody::constant_pool_indices
+    #[must_use]
+    pub fn with_retained_constant_pool(mut self, retain: bool) -> Self {
+        self.retain_constant_pool = retain;
+        self
+    }
+
+    /// Registers a decoder for the attributes with the given name.
+    /// The decoded values are stored in the `custom_attributes` of the elements having the
+    /// attributes.
+    /// Attributes defined in the JVM specification are always decoded by the built-in decoders.
+    #[must_use]
+    pub fn with_attribute_decoder<D>(mut self, name: impl Into<String>, decoder: D) -> Self
+    where
+        D: AttributeDecoder + 'static,
+    {
+        self.attribute_decoders
+            .insert(name.into(), Arc::new(decoder));
+        self
+    }
+
+    pub(super) fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    pub(super) fn retains_constant_pool(&self) -> bool {
+        self.retain_constant_pool
+    }
+
+    pub(super) fn attribute_decoders(&self) -> &AttributeDecoders {
+        &self.attribute_decoders
+    }
+}
+
+impl Debug for ParseOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("lenient", &self.lenient)
+            .field("retain_constant_pool", &self.retain_constant_pool)
+            .field(
+                "attribute_decoders",
+                &self.attribute_decoders.keys().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
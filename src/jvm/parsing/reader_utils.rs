@@ -47,6 +47,30 @@ macro_rules! impl_read_bytes_for {
 
 impl_read_bytes_for![u8, u16, u32, i8, i16, i32, i64, f32, f64];
 
+/// A reader that keeps track of the number of bytes read, i.e., the offset of the next byte.
+pub(super) struct TrackedReader<'r, R: ?Sized> {
+    inner: &'r mut R,
+    position: usize,
+}
+
+impl<'r, R: Read + ?Sized> TrackedReader<'r, R> {
+    pub fn new(inner: &'r mut R) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<R: Read + ?Sized> Read for TrackedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.position += len;
+        Ok(len)
+    }
+}
+
 /// Reads [len] bytes and advances the reader by [`len`] bytes.
 pub(super) fn read_byte_chunk<R>(reader: &mut R, len: usize) -> Result<Vec<u8>>
 where
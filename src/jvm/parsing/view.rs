@@ -0,0 +1,429 @@
+//! Zero-copy views of class files.
+//!
+//! A [`ClassView`] borrows the bytes of a class file (e.g., from a memory-mapped file or a buffer
+//! of a zip archive) and decodes its structure without copying any names or descriptors.
+
+use std::{borrow::Cow, io};
+
+use crate::{
+    jvm::{class::Class, constant_pool::BadConstantPoolIndex},
+    macros::see_jvm_spec,
+};
+
+use super::Error;
+
+/// A string in the constant pool, encoded in modified UTF-8.
+#[doc = see_jvm_spec!(4, 4, 7)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JavaStr<'a>(&'a [u8]);
+
+impl<'a> JavaStr<'a> {
+    /// Returns the raw bytes of the string.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Decodes the string.
+    /// The result borrows the bytes if they are also valid UTF-8, which is the case for most
+    /// names and descriptors.
+    ///
+    /// # Errors
+    /// - [`Error::BrokenUTF8`] if the bytes are not valid modified UTF-8.
+    pub fn to_str(&self) -> Result<Cow<'a, str>, Error> {
+        cesu8::from_java_cesu8(self.0).map_err(|_| Error::BrokenUTF8)
+    }
+}
+
+/// A cursor over a byte slice.
+#[derive(Debug, Clone)]
+struct SliceReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Unexpected end of class file at offset {:#x}",
+                        self.position
+                    ),
+                )
+            })?;
+        let chunk = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(chunk)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        self.take(1).map(|it| it[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        self.take(2).map(|it| u16::from_be_bytes([it[0], it[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.take(4)
+            .map(|it| u32::from_be_bytes([it[0], it[1], it[2], it[3]]))
+    }
+}
+
+/// The constant pool of a [`ClassView`], indexed by the offsets of its entries.
+#[derive(Debug, Clone)]
+pub struct ConstantPoolView<'a> {
+    bytes: &'a [u8],
+    /// The offsets of the tags of the entries, where `None` is for the unusable slots.
+    offsets: Vec<Option<usize>>,
+}
+
+impl<'a> ConstantPoolView<'a> {
+    const UTF8: u8 = 1;
+    const CLASS: u8 = 7;
+
+    fn read(reader: &mut SliceReader<'a>) -> Result<Self, Error> {
+        let count = reader.u16()?;
+        // The first slot is unusable.
+        let mut offsets = vec![None];
+        while offsets.len() < usize::from(count) {
+            offsets.push(Some(reader.position));
+            let tag = reader.u8()?;
+            let len = match tag {
+                Self::UTF8 => usize::from(reader.u16()?),
+                // Class, String, MethodType, Module, Package
+                7 | 8 | 16 | 19 | 20 => 2,
+                // MethodHandle
+                15 => 3,
+                // Integer, Float, Field/Method/InterfaceMethod refs, NameAndType, (Invoke)Dynamic
+                3 | 4 | 9..=12 | 17 | 18 => 4,
+                // Long and Double take two slots.
+                5 | 6 => {
+                    offsets.push(None);
+                    8
+                }
+                it => return Err(Error::UnexpectedConstantPoolTag(it)),
+            };
+            reader.take(len)?;
+        }
+        Ok(Self {
+            bytes: reader.bytes,
+            offsets,
+        })
+    }
+
+    fn tag_name(tag: u8) -> &'static str {
+        match tag {
+            Self::UTF8 => "Utf8",
+            3 => "Integer",
+            4 => "Float",
+            5 => "Long",
+            6 => "Double",
+            Self::CLASS => "Class",
+            8 => "String",
+            9 => "Fieldref",
+            10 => "Methodref",
+            11 => "InterfaceMethodref",
+            12 => "NameAndType",
+            15 => "MethodHandle",
+            16 => "MethodType",
+            17 => "Dynamic",
+            18 => "InvokeDynamic",
+            19 => "Module",
+            20 => "Package",
+            _ => "Unknown",
+        }
+    }
+
+    /// Returns the number of slots in the constant pool, including the unusable ones.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Checks whether the constant pool has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.len() <= 1
+    }
+
+    /// Returns the tag and the content (excluding the tag) of an entry.
+    fn entry(&self, index: u16) -> Result<(u8, SliceReader<'a>), Error> {
+        let offset = self
+            .offsets
+            .get(usize::from(index))
+            .copied()
+            .flatten()
+            .ok_or(BadConstantPoolIndex(index))?;
+        let mut reader = SliceReader {
+            bytes: self.bytes,
+            position: offset,
+        };
+        let tag = reader.u8()?;
+        Ok((tag, reader))
+    }
+
+    /// Gets the string in a `CONSTANT_Utf8_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry is not a `CONSTANT_Utf8_info`.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_str(&self, index: u16) -> Result<JavaStr<'a>, Error> {
+        let (tag, mut reader) = self.entry(index)?;
+        if tag != Self::UTF8 {
+            return Err(Error::MismatchedConstantPoolEntryType {
+                expected: "Utf8",
+                found: Self::tag_name(tag),
+            }
+            .at_constant_pool_index(index));
+        }
+        let len = reader.u16()?;
+        reader.take(usize::from(len)).map(JavaStr)
+    }
+
+    /// Gets the binary name in a `CONSTANT_Class_info` entry.
+    ///
+    /// # Errors
+    /// - [`Error::BadConstantPoolIndex`] if `index` does not point to an entry.
+    /// - [`Error::MismatchedConstantPoolEntryType`] if the entry is not a `CONSTANT_Class_info`.
+    ///   It is wrapped in [`Error::Located`] with `index` as the context, so match on
+    ///   [`Error::root`] to check for it.
+    pub fn get_class_name(&self, index: u16) -> Result<JavaStr<'a>, Error> {
+        match self.entry(index)? {
+            (Self::CLASS, mut reader) => self.get_str(reader.u16()?),
+            (tag, _) => Err(Error::MismatchedConstantPoolEntryType {
+                expected: "Class",
+                found: Self::tag_name(tag),
+            }
+            .at_constant_pool_index(index)),
+        }
+    }
+}
+
+/// An attribute of a class, field, or method.
+#[doc = see_jvm_spec!(4, 7)]
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeView<'a> {
+    /// The name of the attribute.
+    pub name: JavaStr<'a>,
+    /// The content of the attribute.
+    pub info: &'a [u8],
+}
+
+impl<'a> AttributeView<'a> {
+    fn read_all(
+        reader: &mut SliceReader<'a>,
+        constant_pool: &ConstantPoolView<'a>,
+    ) -> Result<Vec<Self>, Error> {
+        let count = reader.u16()?;
+        (0..count)
+            .map(|_| {
+                let name = constant_pool.get_str(reader.u16()?)?;
+                let len = usize::try_from(reader.u32()?)
+                    .map_err(|_| Error::Other("The attribute is too large"))?;
+                let info = reader.take(len)?;
+                Ok(Self { name, info })
+            })
+            .collect()
+    }
+}
+
+/// A field or a method in a [`ClassView`].
+#[derive(Debug, Clone)]
+pub struct MemberView<'a> {
+    /// The raw access flags of the member.
+    pub access_flags: u16,
+    /// The name of the member.
+    pub name: JavaStr<'a>,
+    /// The descriptor of the member.
+    pub descriptor: JavaStr<'a>,
+    /// The attributes of the member.
+    pub attributes: Vec<AttributeView<'a>>,
+}
+
+impl<'a> MemberView<'a> {
+    fn read_all(
+        reader: &mut SliceReader<'a>,
+        constant_pool: &ConstantPoolView<'a>,
+    ) -> Result<Vec<Self>, Error> {
+        let count = reader.u16()?;
+        (0..count)
+            .map(|_| {
+                let access_flags = reader.u16()?;
+                let name = constant_pool.get_str(reader.u16()?)?;
+                let descriptor = constant_pool.get_str(reader.u16()?)?;
+                let attributes = AttributeView::read_all(reader, constant_pool)?;
+                Ok(Self {
+                    access_flags,
+                    name,
+                    descriptor,
+                    attributes,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A class file borrowed from a byte slice.
+///
+/// Unlike [`Class::from_reader`], the names and descriptors are not copied, and attributes are
+/// kept as raw bytes.
+/// Use [`ClassView::parse_class`] to get the owned [`Class`] model.
+#[doc = see_jvm_spec!(4, 1)]
+#[derive(Debug, Clone)]
+pub struct ClassView<'a> {
+    bytes: &'a [u8],
+    /// The minor version of the class file.
+    pub minor_version: u16,
+    /// The major version of the class file.
+    pub major_version: u16,
+    /// The constant pool.
+    pub constant_pool: ConstantPoolView<'a>,
+    /// The raw access flags of the class.
+    pub access_flags: u16,
+    /// The binary name of the class.
+    pub binary_name: JavaStr<'a>,
+    /// The binary name of the superclass, which is `None` for `java/lang/Object` and modules.
+    pub super_class: Option<JavaStr<'a>>,
+    /// The binary names of the interfaces.
+    pub interfaces: Vec<JavaStr<'a>>,
+    /// The fields declared in the class.
+    pub fields: Vec<MemberView<'a>>,
+    /// The methods declared in the class.
+    pub methods: Vec<MemberView<'a>>,
+    /// The attributes of the class.
+    pub attributes: Vec<AttributeView<'a>>,
+}
+
+impl<'a> ClassView<'a> {
+    const MAGIC: u32 = 0xCAFE_BABE;
+
+    /// Parses the structure of a class file.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let reader = &mut SliceReader::new(bytes);
+        if reader.u32()? != Self::MAGIC {
+            return Err(Error::IO(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "This is not a Java class file",
+            )));
+        }
+        let minor_version = reader.u16()?;
+        let major_version = reader.u16()?;
+        let constant_pool = ConstantPoolView::read(reader)?;
+        let access_flags = reader.u16()?;
+        let binary_name = constant_pool.get_class_name(reader.u16()?)?;
+        let super_class = match reader.u16()? {
+            0 => None,
+            it => Some(constant_pool.get_class_name(it)?),
+        };
+        let interfaces_count = reader.u16()?;
+        let interfaces = (0..interfaces_count)
+            .map(|_| constant_pool.get_class_name(reader.u16()?))
+            .collect::<Result<_, _>>()?;
+        let fields = MemberView::read_all(reader, &constant_pool)?;
+        let methods = MemberView::read_all(reader, &constant_pool)?;
+        let attributes = AttributeView::read_all(reader, &constant_pool)?;
+        if reader.position != bytes.len() {
+            return Err(Error::IO(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Extra data",
+            )));
+        }
+        Ok(Self {
+            bytes,
+            minor_version,
+            major_version,
+            constant_pool,
+            access_flags,
+            binary_name,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            attributes,
+        })
+    }
+
+    /// Returns the bytes of the class file.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Parses the underlying bytes into the owned [`Class`] model.
+    ///
+    /// This is the same as calling [`Class::from_reader`] on [`ClassView::as_bytes`]; nothing
+    /// decoded by the view is reused, since the owned model needs the whole constant pool and
+    /// every attribute decoded anyway.
+    ///
+    /// # Errors
+    /// See [`Error`] for more information.
+    pub fn parse_class(&self) -> Result<Class, Error> {
+        Class::from_reader(self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_class_file() {
+        let bytes = [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00];
+        assert!(matches!(
+            ClassView::parse(&bytes),
+            Err(Error::IO(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        assert!(matches!(
+            ClassView::parse(&[0xCA, 0xFE, 0xBA, 0xBF]),
+            Err(Error::IO(e)) if e.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(matches!(
+            Class::from_reader(&bytes[..]),
+            Err(Error::IO(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn mismatched_entry_index() {
+        // The constant pool contains a single `CONSTANT_Integer_info` entry.
+        let bytes = [0x03, 0x00, 0x00, 0x00, 0x2A];
+        let constant_pool = ConstantPoolView {
+            bytes: &bytes,
+            offsets: vec![None, Some(0)],
+        };
+        let err = constant_pool.get_str(1).unwrap_err();
+        assert_eq!(err.context().and_then(|it| it.constant_pool_index), Some(1));
+        assert!(matches!(
+            err.root(),
+            Error::MismatchedConstantPoolEntryType {
+                expected: "Utf8",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn modified_utf8() {
+        // The null character is encoded in two bytes in modified UTF-8.
+        let string = JavaStr(&[b'a', 0xC0, 0x80]);
+        assert_eq!(string.to_str().unwrap(), "a\0");
+        assert!(matches!(JavaStr(b"abc").to_str(), Ok(Cow::Borrowed("abc"))));
+        assert!(matches!(JavaStr(&[0xFF]).to_str(), Err(Error::BrokenUTF8)));
+    }
+}
@@ -31,7 +31,7 @@ impl Display for ClassRef {
 }
 
 /// A reference to a [`Field`](crate::jvm::field::Field).
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub struct FieldRef {
     /// A reference to the class that contains the field.
     pub owner: ClassRef,
@@ -48,7 +48,7 @@ impl Display for FieldRef {
 }
 
 /// A reference to a [`Method`].
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub struct MethodRef {
     /// The reference to the class containing the method.
     pub owner: ClassRef,
@@ -0,0 +1,377 @@
+//! Source maps (SMAP) of the `SourceDebugExtension` attribute.
+//!
+//! Compilers of languages other than Java (e.g., JSP, Kotlin, and Groovy) record how the lines
+//! of the generated class map to the lines of the original sources in the format defined by
+//! [JSR 45](https://jcp.org/en/jsr/detail?id=45).
+//! A SMAP consists of strata, each of which maps the output lines (i.e., the line numbers in
+//! [`LineNumberTableEntry`](super::code::LineNumberTableEntry)) to the lines of its input files.
+
+use std::str::FromStr;
+
+use super::{
+    class::Class,
+    code::{MethodBody, ProgramCounter},
+};
+
+/// An error when parsing a SMAP.
+#[derive(Debug, thiserror::Error)]
+pub enum SmapError {
+    /// The SMAP does not start with the `SMAP` header.
+    #[error("Missing SMAP header")]
+    MissingHeader,
+    /// The SMAP ends before the required items.
+    #[error("Unexpected end of SMAP")]
+    UnexpectedEnd,
+    /// A line in the SMAP is malformed.
+    #[error("Invalid SMAP line {line_number}: {line}")]
+    InvalidLine {
+        /// The line number (starting from 1) in the SMAP.
+        line_number: usize,
+        /// The content of the line.
+        line: String,
+    },
+    /// The SMAP contains embedded SMAPs, which are only allowed in unresolved SMAP files.
+    #[error("Embedded SMAPs are not supported")]
+    EmbeddedSmap,
+    /// The `SourceDebugExtension` is not a valid modified UTF-8 string.
+    #[error("Invalid UTF-8 string")]
+    BrokenUTF8,
+}
+
+/// A source map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Smap {
+    /// The name of the generated source file, e.g., `Foo.java` for a JSP.
+    pub output_file: String,
+    /// The stratum used by debuggers by default.
+    pub default_stratum: String,
+    /// The strata in the SMAP.
+    pub strata: Vec<Stratum>,
+}
+
+/// A stratum, i.e., a mapping from the output lines to the lines in the sources of a language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stratum {
+    /// The identifier of the stratum, e.g., `JSP` or `Kotlin`.
+    pub id: String,
+    /// The input source files.
+    pub files: Vec<SourceFileInfo>,
+    /// The mappings between the input lines and the output lines.
+    pub lines: Vec<LineInfo>,
+}
+
+/// An input source file in a [`Stratum`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFileInfo {
+    /// The identifier used by [`LineInfo::file_id`].
+    pub id: u32,
+    /// The name of the file.
+    pub name: String,
+    /// The path of the file relative to the source root, if present.
+    pub path: Option<String>,
+}
+
+/// A mapping from consecutive input lines to output lines.
+///
+/// The `n`-th input line (starting from `0`) maps to the `output_line_increment` output lines
+/// starting from `output_start_line + n * output_line_increment`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineInfo {
+    /// The first input line.
+    pub input_start_line: u32,
+    /// The identifier of the input file.
+    pub file_id: u32,
+    /// The number of input lines.
+    pub repeat_count: u32,
+    /// The first output line.
+    pub output_start_line: u32,
+    /// The number of output lines of each input line.
+    pub output_line_increment: u32,
+}
+
+impl LineInfo {
+    /// Maps an output line to the input line, or `None` if it is not covered.
+    #[must_use]
+    pub fn input_line_of(&self, output_line: u32) -> Option<u32> {
+        let offset = output_line.checked_sub(self.output_start_line)?;
+        if self.output_line_increment == 0 {
+            return (offset == 0).then_some(self.input_start_line);
+        }
+        let n = offset / self.output_line_increment;
+        if n < self.repeat_count {
+            self.input_start_line.checked_add(n)
+        } else {
+            None
+        }
+    }
+}
+
+/// A location in an input source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation<'a> {
+    /// The source file.
+    pub file: &'a SourceFileInfo,
+    /// The line in the source file.
+    pub line: u32,
+}
+
+impl Stratum {
+    /// Maps an output line to the location in the input sources, or `None` if it is not covered.
+    #[must_use]
+    pub fn map_line(&self, output_line: u32) -> Option<SourceLocation<'_>> {
+        self.lines.iter().find_map(|info| {
+            let line = info.input_line_of(output_line)?;
+            let file = self.files.iter().find(|it| it.id == info.file_id)?;
+            Some(SourceLocation { file, line })
+        })
+    }
+}
+
+impl Smap {
+    /// Gets the stratum with the given identifier.
+    #[must_use]
+    pub fn stratum(&self, id: &str) -> Option<&Stratum> {
+        self.strata.iter().find(|it| it.id == id)
+    }
+
+    /// Maps the instruction at `pc` in `body` to the location in the input sources of the default
+    /// stratum.
+    /// Returns `None` if the instruction has no line number or the line is not covered.
+    #[must_use]
+    pub fn map_pc(&self, body: &MethodBody, pc: ProgramCounter) -> Option<SourceLocation<'_>> {
+        let line = body.line_number_of(pc)?;
+        self.stratum(&self.default_stratum)?.map_line(line.into())
+    }
+}
+
+impl FromStr for Smap {
+    type Err = SmapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_end_matches('\r')));
+        let mut next_line = || lines.next().ok_or(SmapError::UnexpectedEnd);
+        if next_line()?.1 != "SMAP" {
+            return Err(SmapError::MissingHeader);
+        }
+        let output_file = next_line()?.1.to_owned();
+        let default_stratum = next_line()?.1.to_owned();
+
+        let mut strata: Vec<Stratum> = Vec::new();
+        let mut section = Section::Unknown;
+        let mut last_file_id = 0;
+        while let Some((line_number, line)) = lines.next() {
+            let invalid = || SmapError::InvalidLine {
+                line_number,
+                line: line.to_owned(),
+            };
+            if let Some(header) = line.strip_prefix('*') {
+                section = match header.split_once(' ') {
+                    Some(("S", id)) => {
+                        strata.push(Stratum {
+                            id: id.to_owned(),
+                            files: Vec::new(),
+                            lines: Vec::new(),
+                        });
+                        last_file_id = 0;
+                        Section::Unknown
+                    }
+                    _ if header == "F" => Section::File,
+                    _ if header == "L" => Section::Line,
+                    _ if header.starts_with('O') || header.starts_with('C') => {
+                        return Err(SmapError::EmbeddedSmap)
+                    }
+                    // The end of the SMAP (some compilers write one after each stratum), the vendor
+                    // sections, and the future sections are ignored.
+                    _ => Section::Unknown,
+                };
+                continue;
+            }
+            match section {
+                Section::Unknown => {}
+                Section::File => {
+                    let stratum = strata.last_mut().ok_or_else(invalid)?;
+                    let (has_path, info) = match line.strip_prefix('+') {
+                        Some(info) => (true, info.trim_start()),
+                        None => (false, line),
+                    };
+                    let (id, name) = info.split_once(' ').ok_or_else(invalid)?;
+                    let id = id.parse().map_err(|_| invalid())?;
+                    let path = if has_path {
+                        Some(lines.next().ok_or(SmapError::UnexpectedEnd)?.1.to_owned())
+                    } else {
+                        None
+                    };
+                    stratum.files.push(SourceFileInfo {
+                        id,
+                        name: name.to_owned(),
+                        path,
+                    });
+                }
+                Section::Line => {
+                    let stratum = strata.last_mut().ok_or_else(invalid)?;
+                    let info = parse_line_info(line, last_file_id).ok_or_else(invalid)?;
+                    last_file_id = info.file_id;
+                    stratum.lines.push(info);
+                }
+            }
+        }
+        Ok(Self {
+            output_file,
+            default_stratum,
+            strata,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Section {
+    File,
+    Line,
+    Unknown,
+}
+
+/// Parses `InputStartLine[#LineFileID][,RepeatCount]:OutputStartLine[,OutputLineIncrement]`.
+fn parse_line_info(line: &str, last_file_id: u32) -> Option<LineInfo> {
+    let (input, output) = line.split_once(':')?;
+    let (input, repeat_count) = match input.split_once(',') {
+        Some((input, count)) => (input, count.parse().ok()?),
+        None => (input, 1),
+    };
+    let (input_start_line, file_id) = match input.split_once('#') {
+        Some((start, id)) => (start.parse().ok()?, id.parse().ok()?),
+        None => (input.parse().ok()?, last_file_id),
+    };
+    let (output_start_line, output_line_increment) = match output.split_once(',') {
+        Some((start, increment)) => (start.parse().ok()?, increment.parse().ok()?),
+        None => (output.parse().ok()?, 1),
+    };
+    Some(LineInfo {
+        input_start_line,
+        file_id,
+        repeat_count,
+        output_start_line,
+        output_line_increment,
+    })
+}
+
+impl Class {
+    /// Parses the SMAP in the `SourceDebugExtension` attribute of the class.
+    /// Returns `None` if the class has no such attribute.
+    ///
+    /// # Errors
+    /// See [`SmapError`].
+    pub fn smap(&self) -> Result<Option<Smap>, SmapError> {
+        let Some(bytes) = &self.source_debug_extension else {
+            return Ok(None);
+        };
+        let content = cesu8::from_java_cesu8(bytes).map_err(|_| SmapError::BrokenUTF8)?;
+        content.parse().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KOTLIN_SMAP: &str = "SMAP
+Main.kt
+Kotlin
+*S Kotlin
+*F
++ 1 Main.kt
+MainKt
++ 2 Collections.kt
+kotlin/collections/CollectionsKt__CollectionsKt
+*L
+1#1,20:1
+5#2,3:21
+*E
+*S KotlinDebug
+*F
++ 1 Main.kt
+MainKt
+*L
+8#1:21,3
+*E
+";
+
+    #[test]
+    fn parse_kotlin_smap() {
+        let smap: Smap = KOTLIN_SMAP.parse().unwrap();
+        assert_eq!(smap.output_file, "Main.kt");
+        assert_eq!(smap.default_stratum, "Kotlin");
+        assert_eq!(smap.strata.len(), 2);
+        let kotlin = smap.stratum("Kotlin").unwrap();
+        assert_eq!(
+            kotlin.files[1],
+            SourceFileInfo {
+                id: 2,
+                name: "Collections.kt".to_owned(),
+                path: Some("kotlin/collections/CollectionsKt__CollectionsKt".to_owned()),
+            }
+        );
+        assert_eq!(kotlin.lines.len(), 2);
+
+        let location = kotlin.map_line(10).unwrap();
+        assert_eq!((location.file.id, location.line), (1, 10));
+        let location = kotlin.map_line(22).unwrap();
+        assert_eq!(
+            (location.file.name.as_str(), location.line),
+            ("Collections.kt", 6)
+        );
+        assert!(kotlin.map_line(24).is_none());
+
+        // The call site of the inlined function
+        let debug = smap.stratum("KotlinDebug").unwrap();
+        assert_eq!(debug.map_line(23).unwrap().line, 8);
+    }
+
+    #[test]
+    fn line_info() {
+        assert_eq!(
+            parse_line_info("123,5:207,3", 2),
+            Some(LineInfo {
+                input_start_line: 123,
+                file_id: 2,
+                repeat_count: 5,
+                output_start_line: 207,
+                output_line_increment: 3,
+            })
+        );
+        let info = parse_line_info("10#3:100,0", 1).unwrap();
+        assert_eq!(info.file_id, 3);
+        assert_eq!(info.input_line_of(100), Some(10));
+        assert_eq!(info.input_line_of(101), None);
+        assert!(parse_line_info("1#x:1", 1).is_none());
+    }
+
+    #[test]
+    fn input_line_overflow() {
+        let info = parse_line_info(&format!("{},3:1", u32::MAX - 1), 1).unwrap();
+        assert_eq!(info.input_line_of(2), Some(u32::MAX));
+        assert_eq!(info.input_line_of(3), None);
+    }
+
+    #[test]
+    fn malformed_smap() {
+        assert!(matches!(
+            "SMAP\nFoo.java".parse::<Smap>(),
+            Err(SmapError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            "Foo".parse::<Smap>(),
+            Err(SmapError::MissingHeader)
+        ));
+        assert!(matches!(
+            "SMAP\nFoo.java\nJSP\n*S JSP\n*L\nnot a line\n*E\n".parse::<Smap>(),
+            Err(SmapError::InvalidLine { line_number: 6, .. })
+        ));
+        assert!(matches!(
+            "SMAP\nFoo.java\nJSP\n*O JSP\n".parse::<Smap>(),
+            Err(SmapError::EmbeddedSmap)
+        ));
+    }
+}
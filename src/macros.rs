@@ -5,12 +5,13 @@ macro_rules! extract_attributes {
          $( let $var: ident: $attr: ident $(as $uw: ident)?, )*
          $( if let $var_true: ident: $attr_true: ident, )*
          $( match $attr_custom: pat => $var_custom: block, )*
-         else let $unrecognized:ident
+         else let $unrecognized:ident, $custom:ident
     }) => {
         use crate::jvm::parsing::attribute::Attribute;
         $( let mut $var = None; )*
         $( let mut $var_true = false; )*
         let mut $unrecognized = Vec::new();
+        let mut $custom = crate::jvm::custom_attribute::CustomAttributes::default();
         {
             for attr in $attrs {
                 match attr {
@@ -34,6 +35,9 @@ macro_rules! extract_attributes {
                     Attribute::Unrecognized(name, bytes) => {
                         $unrecognized.push((name, bytes));
                     }
+                    Attribute::Custom(name, value) => {
+                        $custom.insert_shared(name, value);
+                    }
                     unexpected => {
                         Err(Error::UnexpectedAttribute(
                             unexpected.name().to_owned(),
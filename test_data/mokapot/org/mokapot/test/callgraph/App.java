@@ -0,0 +1,29 @@
+package org.mokapot.test.callgraph;
+
+public class App {
+    public static void main(String[] args) {
+        Shape s = new Circle();
+        s.area();
+        Runnable r = () -> helper();
+        r.run();
+    }
+
+    static void helper() {
+    }
+}
+
+interface Shape {
+    double area();
+}
+
+class Circle implements Shape {
+    public double area() {
+        return 3.14;
+    }
+}
+
+class Square implements Shape {
+    public double area() {
+        return 1.0;
+    }
+}
@@ -0,0 +1,21 @@
+package org.mokapot.test.constraints;
+
+public interface Shape {
+    int SIDES = 0;
+
+    String NAME = "shape";
+
+    double area();
+
+    default double scaled(double factor) {
+        return checked(area() * factor);
+    }
+
+    static Shape unit() {
+        return () -> 1.0;
+    }
+
+    private double checked(double value) {
+        return value < 0 ? 0 : value;
+    }
+}
@@ -0,0 +1,25 @@
+package org.mokapot.test.ifds;
+
+public class Flow {
+    public static void main(String[] args) {
+        String secret = source();
+        sink(identity(secret));
+        sink(clean(secret));
+        sink(identity("constant"));
+    }
+
+    static String source() {
+        return "secret";
+    }
+
+    static void sink(String value) {
+    }
+
+    static String identity(String value) {
+        return value;
+    }
+
+    static String clean(String value) {
+        return "clean";
+    }
+}
@@ -0,0 +1,80 @@
+package org.mokapot.test.pointsto;
+
+public class Aliasing {
+    static Object global;
+
+    public static void main(String[] args) {
+        Item first = new Item();
+        Item second = new Item();
+
+        Box box = new Box();
+        box.value = first;
+        fieldFlow(box.value, first);
+
+        Object[] array = new Object[1];
+        array[0] = second;
+        arrayFlow(array[0], first);
+
+        global = second;
+        staticFlow(global);
+
+        Object chosen = args.length > 0 ? first : second;
+        phiFlow(chosen);
+
+        callSiteFlow(identity(first), identity(second));
+
+        Box left = new Box();
+        left.set(first);
+        Box right = new Box();
+        right.set(second);
+        objectFlow(left.get(), right.get());
+
+        SubBox sub = new SubBox();
+        sub.value = first;
+        Box upcast = sub;
+        inheritedFieldFlow(upcast.value);
+    }
+
+    static Object identity(Object value) {
+        return value;
+    }
+
+    static void fieldFlow(Object actual, Object expected) {
+    }
+
+    static void arrayFlow(Object actual, Object unrelated) {
+    }
+
+    static void staticFlow(Object actual) {
+    }
+
+    static void phiFlow(Object actual) {
+    }
+
+    static void callSiteFlow(Object lhs, Object rhs) {
+    }
+
+    static void objectFlow(Object lhs, Object rhs) {
+    }
+
+    static void inheritedFieldFlow(Object actual) {
+    }
+}
+
+class Item {
+}
+
+class Box {
+    Object value;
+
+    void set(Object value) {
+        this.value = value;
+    }
+
+    Object get() {
+        return value;
+    }
+}
+
+class SubBox extends Box {
+}
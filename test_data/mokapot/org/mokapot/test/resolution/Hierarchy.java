@@ -0,0 +1,60 @@
+package org.mokapot.test.resolution;
+
+public class Hierarchy {
+
+  public String describe(Animal animal) {
+    return animal.sound() + animal.name();
+  }
+}
+
+interface Animal {
+  int LEGS = 4;
+
+  default String sound() {
+    return "...";
+  }
+
+  String name();
+}
+
+interface Dog extends Animal {
+  default String sound() {
+    return "woof";
+  }
+}
+
+interface Pet {
+  default String sound() {
+    return "purr";
+  }
+}
+
+abstract class Base implements Animal {
+  String nickname;
+
+  public String name() {
+    return "base";
+  }
+
+  protected void hook() {}
+
+  void packagePrivate() {}
+
+  private void secret() {}
+
+  static void util() {}
+}
+
+class Puppy extends Base implements Dog {
+  @Override
+  protected void hook() {
+    super.hook();
+  }
+}
+
+class Kitten extends Base implements Dog, Pet {
+  @Override
+  public String sound() {
+    return Pet.super.sound();
+  }
+}
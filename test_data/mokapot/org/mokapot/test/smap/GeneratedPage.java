@@ -0,0 +1,12 @@
+package org.mokapot.test.smap;
+
+/** Mimics a servlet generated from a JSP page, whose SMAP is added to the class file in tests. */
+public class GeneratedPage {
+    /** Puts the name of the attribute into the constant pool. */
+    public static final String DEBUG_ATTRIBUTE = "SourceDebugExtension";
+
+    public String render(String name) {
+        String greeting = "Hello, ";
+        return greeting + name;
+    }
+}
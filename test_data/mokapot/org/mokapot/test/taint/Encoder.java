@@ -0,0 +1,7 @@
+package org.mokapot.test.taint;
+
+public class Encoder {
+    public static String encode(String value) {
+        return value;
+    }
+}
@@ -0,0 +1,7 @@
+package org.mokapot.test.taint;
+
+public class Request {
+    public String getParameter(String name) {
+        return name;
+    }
+}
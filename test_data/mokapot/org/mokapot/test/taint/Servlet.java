@@ -0,0 +1,48 @@
+package org.mokapot.test.taint;
+
+import java.util.ArrayList;
+import java.util.List;
+
+public class Servlet {
+    static String cached;
+
+    public static void main(String[] args) {
+        Request request = new Request();
+        Statement statement = new Statement();
+        String name = request.getParameter("name");
+        statement.executeQuery("SELECT * FROM users WHERE name = '" + name + "'");
+        StringBuilder builder = new StringBuilder("SELECT * FROM users WHERE name = ");
+        builder.append(name);
+        statement.executeQuery(builder.toString());
+        statement.executeQuery(Encoder.encode(name));
+        statement.executeQuery("SELECT * FROM users");
+        cached = name;
+        query(statement);
+        List<String> names = new ArrayList<>();
+        names.add(name);
+        statement.executeQuery(names.get(0));
+        statement.executeQuery(wrap(name));
+        inheritedField(statement, name);
+    }
+
+    static void query(Statement statement) {
+        statement.executeQuery(cached);
+    }
+
+    static String wrap(String value) {
+        return "(" + value + ")";
+    }
+
+    static void inheritedField(Statement statement, String value) {
+        Holder holder = new SubHolder();
+        ((SubHolder) holder).value = value;
+        statement.executeQuery(holder.value);
+    }
+}
+
+class Holder {
+    String value;
+}
+
+class SubHolder extends Holder {
+}
@@ -0,0 +1,6 @@
+package org.mokapot.test.taint;
+
+public class Statement {
+    public void executeQuery(String query) {
+    }
+}
@@ -0,0 +1,63 @@
+package org.mokapot.test.verifier;
+
+public class Sample {
+    private final long total;
+    private int[] values;
+
+    public Sample(long total) {
+        this.total = total;
+        this.values = new int[] {1, 2, 3};
+    }
+
+    public Sample() {
+        this(0L);
+    }
+
+    public long sum() {
+        long sum = total;
+        for (int value : values) {
+            sum += value;
+        }
+        return sum;
+    }
+
+    public static String describe(int kind) {
+        switch (kind) {
+            case 0:
+                return "zero";
+            case 1:
+                return "one";
+            default:
+                return "many: " + kind;
+        }
+    }
+
+    public static Object pick(boolean flag, String text, Object other) {
+        Object result = flag ? text : other;
+        return result;
+    }
+
+    public static int guarded(int[] array) {
+        try {
+            return array[0];
+        } catch (Throwable t) {
+            return -1;
+        }
+    }
+
+    public static double[][] matrix(int size) {
+        double[][] matrix = new double[size][size];
+        matrix[0][0] = 1.0;
+        return matrix;
+    }
+
+    public static Sample create(boolean flag) {
+        return new Sample(flag ? 1L : 2L);
+    }
+
+    public void clear() {
+        synchronized (this) {
+            values = null;
+        }
+    }
+}
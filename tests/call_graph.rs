@@ -0,0 +1,58 @@
+use mokapot::{
+    analysis::call_graph::{Algorithm, CallGraph, CallGraphBuilder, EntryPoint},
+    jvm::references::MethodRef,
+};
+
+mod common;
+
+use common::{class_loader, TestPackage};
+
+const PKG: TestPackage = TestPackage("org/mokapot/test/callgraph");
+
+fn build(algorithm: Algorithm) -> CallGraph {
+    let class_loader = class_loader();
+    let classes = ["App", "Shape", "Circle", "Square"].map(|it| PKG.class(it));
+    CallGraphBuilder::new(&class_loader, classes)
+        .unwrap()
+        .algorithm(algorithm)
+        .entry_point(EntryPoint::MainMethods)
+        .build()
+        .unwrap()
+}
+
+fn main_method() -> MethodRef {
+    PKG.method_ref("App", "main", "([Ljava/lang/String;)V")
+}
+
+#[test]
+fn cha_includes_all_implementations() {
+    let graph = build(Algorithm::ClassHierarchy);
+    assert_eq!(
+        graph.entry_points().collect::<Vec<_>>(),
+        vec![&main_method()]
+    );
+    let callees: Vec<_> = graph.edges().map(|(_, _, callee)| callee).collect();
+    assert!(callees.contains(&&PKG.method_ref("Circle", "area", "()D")));
+    assert!(callees.contains(&&PKG.method_ref("Square", "area", "()D")));
+}
+
+#[test]
+fn rta_includes_instantiated_implementations() {
+    let graph = build(Algorithm::RapidType);
+    assert!(graph.contains(&PKG.method_ref("Circle", "area", "()D")));
+    assert!(graph.contains(&PKG.method_ref("Circle", "<init>", "()V")));
+    assert!(!graph.contains(&PKG.method_ref("Square", "area", "()D")));
+}
+
+#[test]
+fn lambda_implementation_is_reachable() {
+    let graph = build(Algorithm::RapidType);
+    let helper = PKG.method_ref("App", "helper", "()V");
+    let callers: Vec<_> = graph.callers(&helper).map(|(caller, _)| caller).collect();
+    assert_eq!(callers.len(), 1);
+    let lambda = callers[0];
+    assert!(lambda.name.starts_with("lambda$main$"));
+    assert!(graph
+        .call_sites(&main_method())
+        .any(|(_, callees)| callees.contains(lambda)));
+}
@@ -1,13 +1,25 @@
-use std::{cell::Cell, path::PathBuf};
+use std::cell::Cell;
+#[cfg(feature = "jar")]
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 use mokapot::jvm::{
     class::Class,
     class_loader::{
-        class_paths::{DirectoryClassPath, JarClassPath},
+        class_paths::{DirectoryClassPath, MemoryClassPath},
+        hierarchy::{ClassLoaderHierarchy, Delegation, HierarchyError},
         CachingClassLoader, ClassLoader, ClassPath, Error,
     },
+    parsing::{ParseOptions, ParseWarning, WarningKind},
 };
 
+#[cfg(feature = "jrt")]
+use mokapot::jvm::class_loader::class_paths::JrtClassPath;
+#[cfg(feature = "jar")]
+use mokapot::jvm::class_loader::class_paths::{ClasspathString, JarClassPath, NestedJarClassPath};
+
 macro_rules! test_data_class {
     ($folder:literal, $class_name:literal) => {
         include_bytes!(concat!(
@@ -73,6 +85,7 @@ fn caching_class_loader_load_once() {
 }
 
 #[test]
+#[cfg(feature = "jar")]
 fn jar_class_path() {
     let Ok(java_home) = std::env::var("JAVA_HOME") else {
         return;
@@ -87,6 +100,7 @@ fn jar_class_path() {
 }
 
 #[test]
+#[cfg(feature = "jar")]
 fn jar_class_path_not_found() {
     let Ok(java_home) = std::env::var("JAVA_HOME") else {
         return;
@@ -102,6 +116,7 @@ fn jar_class_path_not_found() {
 }
 
 #[test]
+#[cfg(feature = "jar")]
 fn jar_class_path_not_jar() {
     let jar_path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
     let jar_cp = JarClassPath::new(jar_path);
@@ -116,3 +131,492 @@ fn jar_class_path_not_jar() {
 fn _class_path_object_safety(_b: Box<dyn ClassPath>) {
     // For compilation checking only.
 }
+
+#[test]
+#[cfg(feature = "jrt")]
+fn jrt_class_path() {
+    let Ok(java_home) = std::env::var("JAVA_HOME") else {
+        return;
+    };
+    let jrt_cp = JrtClassPath::from_java_home(java_home).unwrap();
+    assert_eq!(
+        jrt_cp.module_of("java/lang/Object").as_deref(),
+        Some("java.base")
+    );
+    assert_eq!(
+        jrt_cp.module_of("java/sql/Connection").as_deref(),
+        Some("java.sql")
+    );
+    let class_loader = ClassLoader::new([jrt_cp]);
+    let object = class_loader.load_class("java/lang/Object").unwrap();
+    assert_eq!(object.binary_name, "java/lang/Object");
+    assert!(class_loader.load_class("java/sql/Connection").is_ok());
+    assert!(matches!(
+        class_loader.load_class("java/lang/Object3"),
+        Err(Error::NotFound)
+    ));
+    assert!(matches!(
+        class_loader.load_class("org/mokapot/test/MyClass"),
+        Err(Error::NotFound)
+    ));
+}
+
+/// Builds a JAR file containing `entries`, which are pairs of names and contents.
+#[cfg(feature = "jar")]
+fn jar_content<'a>(entries: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> Vec<u8> {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    let mut jar = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in entries {
+        jar.start_file(name, FileOptions::default()).unwrap();
+        jar.write_all(bytes).unwrap();
+    }
+    jar.finish().unwrap().into_inner()
+}
+
+/// Creates a JAR file containing `entries`, which are pairs of names and contents.
+#[cfg(feature = "jar")]
+fn create_jar<'a>(path: &Path, entries: impl IntoIterator<Item = (&'a str, &'a [u8])>) {
+    std::fs::write(path, jar_content(entries)).unwrap();
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn multi_release_jar() {
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let jar_path = temp_dir.path().join("multi-release.jar");
+    let entries = [
+        (
+            "META-INF/MANIFEST.MF",
+            concat!(
+                "Manifest-Version: 1.0\r\n",
+                "Main-Class: org.mokapot.test.MyClass\r\n",
+                "Class-Path: lib/a.jar lib/b.jar\r\n",
+                "Automatic-Module-Name: org.mokapot.test\r\n",
+                "Multi-Release: true\r\n",
+                "\r\n"
+            )
+            .as_bytes(),
+        ),
+        (
+            "org/mokapot/test/MyClass.class",
+            test_data_class!("mokapot", "org/mokapot/test/MyClass"),
+        ),
+        (
+            "META-INF/versions/11/org/mokapot/test/MyClass.class",
+            test_data_class!("mokapot", "org/mokapot/test/RecordTest"),
+        ),
+        (
+            "META-INF/versions/17/org/mokapot/test/MyClass.class",
+            test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass"),
+        ),
+    ];
+    create_jar(&jar_path, entries);
+
+    let manifest = JarClassPath::new(&jar_path).manifest().unwrap().unwrap();
+    assert_eq!(manifest.main_class(), Some("org.mokapot.test.MyClass"));
+    assert_eq!(
+        manifest.class_path().collect::<Vec<_>>(),
+        vec!["lib/a.jar", "lib/b.jar"]
+    );
+    assert_eq!(manifest.automatic_module_name(), Some("org.mokapot.test"));
+    assert!(manifest.is_multi_release());
+
+    let load = |jar_cp: JarClassPath| {
+        jar_cp
+            .find_class("org/mokapot/test/MyClass")
+            .unwrap()
+            .binary_name
+    };
+    assert_eq!(
+        load(JarClassPath::new(&jar_path)),
+        "org/mokapot/test/MyClass"
+    );
+    for (release, expected) in [
+        (8, "org/mokapot/test/MyClass"),
+        (11, "org/mokapot/test/RecordTest"),
+        (16, "org/mokapot/test/RecordTest"),
+        (21, "org/mokapot/test/ComplicatedClass"),
+    ] {
+        let jar_cp = JarClassPath::new(&jar_path).with_release(release);
+        assert_eq!(load(jar_cp), expected);
+    }
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn concurrent_jar_loading() {
+    use rayon::prelude::*;
+
+    let classes_dir = PathBuf::from(concat!(env!("OUT_DIR"), "/mokapot/java_classes"));
+    let class_files: Vec<_> = walkdir::WalkDir::new(&classes_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|it| it.path().extension().is_some_and(|ext| ext == "class"))
+        .map(|it| {
+            let name = it
+                .path()
+                .strip_prefix(&classes_dir)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned();
+            (name, std::fs::read(it.path()).unwrap())
+        })
+        .collect();
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let jar_path = temp_dir.path().join("classes.jar");
+    create_jar(
+        &jar_path,
+        class_files
+            .iter()
+            .map(|(name, bytes)| (name.as_str(), bytes.as_slice())),
+    );
+
+    let class_loader = CachingClassLoader::from(ClassLoader::new([JarClassPath::new(jar_path)]));
+    let binary_names: Vec<_> = class_files
+        .iter()
+        .filter_map(|(name, _)| name.strip_suffix(".class"))
+        .filter(|it| *it != "module-info")
+        .collect();
+    assert!(binary_names.len() > 10);
+    binary_names.par_iter().for_each(|binary_name| {
+        let class = class_loader.load_class(*binary_name).unwrap();
+        assert_eq!(class.binary_name, *binary_name);
+    });
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn spring_boot_jar() {
+    let my_class = test_data_class!("mokapot", "org/mokapot/test/MyClass");
+    let record_test = test_data_class!("mokapot", "org/mokapot/test/RecordTest");
+    let library = jar_content([("org/mokapot/test/RecordTest.class", record_test)]);
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let jar_path = temp_dir.path().join("app.jar");
+    create_jar(
+        &jar_path,
+        [
+            ("BOOT-INF/classes/org/mokapot/test/MyClass.class", my_class),
+            ("BOOT-INF/lib/library.jar", library.as_slice()),
+        ],
+    );
+
+    let class_path = NestedJarClassPath::spring_boot(&jar_path);
+    assert_eq!(
+        class_path.class_names().unwrap(),
+        BTreeSet::from([
+            "org/mokapot/test/MyClass".to_owned(),
+            "org/mokapot/test/RecordTest".to_owned(),
+        ])
+    );
+    let class = class_path.find_class("org/mokapot/test/MyClass").unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/MyClass");
+    let class = class_path
+        .find_class("org/mokapot/test/RecordTest")
+        .unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/RecordTest");
+    assert!(matches!(
+        class_path.find_class("org/mokapot/test/NotExist"),
+        Err(Error::NotFound)
+    ));
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn ear_with_nested_war() {
+    let my_class = test_data_class!("mokapot", "org/mokapot/test/MyClass");
+    let record_test = test_data_class!("mokapot", "org/mokapot/test/RecordTest");
+    let web_library = jar_content([("org/mokapot/test/RecordTest.class", record_test)]);
+    let war = jar_content([
+        ("WEB-INF/classes/org/mokapot/test/MyClass.class", my_class),
+        ("WEB-INF/lib/web-library.jar", web_library.as_slice()),
+    ]);
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let ear_path = temp_dir.path().join("app.ear");
+    create_jar(&ear_path, [("web.war", war.as_slice())]);
+
+    let class_path = NestedJarClassPath::ear(&ear_path);
+    let class = class_path.find_class("org/mokapot/test/MyClass").unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/MyClass");
+    let class = class_path
+        .find_class("org/mokapot/test/RecordTest")
+        .unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/RecordTest");
+    // Classes in a WAR file are not at its root.
+    assert!(matches!(
+        NestedJarClassPath::war(temp_dir.path().join("app.ear"))
+            .find_class("org/mokapot/test/MyClass"),
+        Err(Error::NotFound)
+    ));
+}
+
+#[test]
+fn directory_class_names() {
+    let dir_cp = create_test_dir_class_path();
+    let class_names = dir_cp.class_names().unwrap();
+    assert!(class_names.contains("org/mokapot/test/MyClass"));
+    assert!(class_names.contains("org/mokapot/test/ComplicatedClass$InnerClass"));
+
+    let classes = dir_cp.load_all().unwrap();
+    assert_eq!(classes.len(), class_names.len());
+    for (name, class) in classes {
+        assert_eq!(class.unwrap().binary_name, name);
+    }
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn jar_load_all() {
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let jar_path = temp_dir.path().join("classes.jar");
+    create_jar(
+        &jar_path,
+        [
+            (
+                "META-INF/MANIFEST.MF",
+                b"Multi-Release: true\r\n".as_slice(),
+            ),
+            (
+                "org/mokapot/test/MyClass.class",
+                test_data_class!("mokapot", "org/mokapot/test/MyClass"),
+            ),
+            (
+                "META-INF/versions/17/org/mokapot/test/RecordTest.class",
+                test_data_class!("mokapot", "org/mokapot/test/RecordTest"),
+            ),
+            ("org/mokapot/test/Broken.class", b"not a class".as_slice()),
+            ("org/mokapot/test/readme.txt", b"not a class".as_slice()),
+        ],
+    );
+
+    assert_eq!(
+        JarClassPath::new(&jar_path).class_names().unwrap(),
+        BTreeSet::from([
+            "org/mokapot/test/Broken".to_owned(),
+            "org/mokapot/test/MyClass".to_owned(),
+        ])
+    );
+    let classes = JarClassPath::new(&jar_path)
+        .with_release(17)
+        .load_all()
+        .unwrap();
+    assert_eq!(classes.len(), 3);
+    assert!(classes["org/mokapot/test/MyClass"].is_ok());
+    assert!(classes["org/mokapot/test/RecordTest"].is_ok());
+    assert!(matches!(
+        classes["org/mokapot/test/Broken"],
+        Err(Error::Malformed(_))
+    ));
+}
+
+#[test]
+fn memory_class_path() {
+    let my_class = test_data_class!("mokapot", "org/mokapot/test/MyClass");
+    let record_test =
+        Class::from_reader(test_data_class!("mokapot", "org/mokapot/test/RecordTest")).unwrap();
+    let memory_cp = MemoryClassPath::new()
+        .with_class_bytes("org/mokapot/test/MyClass", my_class.to_vec())
+        .with_class(record_test);
+    let class_loader = ClassLoader::new([memory_cp]);
+    let class = class_loader.load_class("org/mokapot/test/MyClass").unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/MyClass");
+    let class = class_loader
+        .load_class("org/mokapot/test/RecordTest")
+        .unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/RecordTest");
+    assert!(matches!(
+        class_loader.load_class("org/mokapot/test/NotExist"),
+        Err(Error::NotFound)
+    ));
+}
+
+#[test]
+fn class_path_parse_options() {
+    let mut bytes = test_data_class!("mokapot", "org/mokapot/test/RecordTest").to_vec();
+    // Make the class version newer than the supported ones
+    bytes[6..8].copy_from_slice(&99u16.to_be_bytes());
+    let strict = MemoryClassPath::new().with_class_bytes("org/mokapot/test/RecordTest", bytes);
+    assert!(matches!(
+        strict.find_class("org/mokapot/test/RecordTest"),
+        Err(Error::Malformed(_))
+    ));
+    let lenient = strict.with_parse_options(ParseOptions::new().with_lenient(true));
+    let class = lenient.find_class("org/mokapot/test/RecordTest").unwrap();
+    assert_eq!(class.version.major(), 99);
+    let (class, warnings) = lenient
+        .find_class_with_warnings("org/mokapot/test/RecordTest")
+        .unwrap();
+    assert_eq!(class.version.major(), 99);
+    assert_eq!(
+        warnings,
+        [ParseWarning {
+            offset: Some(4),
+            kind: WarningKind::UnsupportedVersion {
+                major: 99,
+                minor: 0
+            },
+        }]
+    );
+    // The warnings are forwarded by the class paths wrapping others.
+    let boxed: Box<dyn ClassPath> = Box::new(lenient);
+    let (_, warnings) = boxed
+        .find_class_with_warnings("org/mokapot/test/RecordTest")
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+
+    let dir_cp = create_test_dir_class_path();
+    let (_, warnings) = dir_cp
+        .find_class_with_warnings("org/mokapot/test/MyClass")
+        .unwrap();
+    assert!(warnings.is_empty());
+
+    let dir_cp = create_test_dir_class_path()
+        .with_parse_options(ParseOptions::new().with_retained_constant_pool(true));
+    let class = dir_cp.find_class("org/mokapot/test/MyClass").unwrap();
+    assert!(class.constant_pool.is_some());
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn classpath_string() {
+    let temp_dir = tempdir::TempDir::new("mokapot").unwrap();
+    let lib_dir = temp_dir.path().join("lib");
+    std::fs::create_dir(&lib_dir).unwrap();
+    create_jar(
+        &lib_dir.join("a.jar"),
+        [(
+            "org/mokapot/test/RecordTest.class",
+            test_data_class!("mokapot", "org/mokapot/test/RecordTest"),
+        )],
+    );
+    std::fs::write(lib_dir.join("readme.txt"), "not a jar").unwrap();
+    let classes_dir = concat!(env!("OUT_DIR"), "/mokapot/java_classes");
+    let classpath = std::env::join_paths([classes_dir, &format!("{}/*", lib_dir.display())])
+        .unwrap()
+        .into_string()
+        .unwrap();
+
+    let class_paths = classpath
+        .parse::<ClasspathString>()
+        .unwrap()
+        .class_paths()
+        .unwrap();
+    assert_eq!(class_paths.len(), 2);
+    let class_loader = ClassLoader::new(class_paths);
+    for name in ["org/mokapot/test/MyClass", "org/mokapot/test/RecordTest"] {
+        let class = class_loader.load_class(name).unwrap();
+        assert_eq!(class.binary_name, name);
+    }
+}
+
+#[test]
+fn class_loader_hierarchy() {
+    let my_class = test_data_class!("mokapot", "org/mokapot/test/MyClass").to_vec();
+    let record_test = test_data_class!("mokapot", "org/mokapot/test/RecordTest").to_vec();
+    let memory_cp = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| {
+                let bytes = if name.ends_with("MyClass") {
+                    my_class.clone()
+                } else {
+                    record_test.clone()
+                };
+                ((*name).to_owned(), bytes)
+            })
+            .collect::<MemoryClassPath>()
+    };
+
+    let mut hierarchy = ClassLoaderHierarchy::new();
+    let server = hierarchy.add_root(
+        "server",
+        [memory_cp(&[
+            "org/mokapot/test/MyClass",
+            "org/mokapot/test/RecordTest",
+        ])],
+    );
+    let parent_first = hierarchy
+        .add_child(
+            server,
+            "parent-first",
+            Delegation::ParentFirst,
+            [memory_cp(&["org/mokapot/test/MyClass"])],
+        )
+        .unwrap();
+    let child_first = hierarchy
+        .add_child(
+            server,
+            "child-first",
+            Delegation::ChildFirst,
+            [memory_cp(&["org/mokapot/test/MyClass"])],
+        )
+        .unwrap();
+    let filtered = hierarchy
+        .add_child(
+            server,
+            "filtered",
+            Delegation::ParentFirstFor(vec!["org/mokapot/test/My".to_owned()]),
+            [memory_cp(&[
+                "org/mokapot/test/MyClass",
+                "org/mokapot/test/RecordTest",
+            ])],
+        )
+        .unwrap();
+    let isolated = hierarchy.add_root("isolated", [memory_cp(&[])]);
+    assert_eq!(hierarchy.name(child_first), "child-first");
+    assert_eq!(hierarchy.parent(child_first), Some(server));
+
+    let defining_loader = |loader, name| hierarchy.load_class(loader, name).map(|it| it.loader);
+    assert_eq!(
+        defining_loader(parent_first, "org/mokapot/test/MyClass").unwrap(),
+        server
+    );
+    assert_eq!(
+        defining_loader(child_first, "org/mokapot/test/MyClass").unwrap(),
+        child_first
+    );
+    assert_eq!(
+        defining_loader(child_first, "org/mokapot/test/RecordTest").unwrap(),
+        server
+    );
+    assert_eq!(
+        defining_loader(filtered, "org/mokapot/test/MyClass").unwrap(),
+        server
+    );
+    assert_eq!(
+        defining_loader(filtered, "org/mokapot/test/RecordTest").unwrap(),
+        filtered
+    );
+    assert!(matches!(
+        defining_loader(isolated, "org/mokapot/test/MyClass"),
+        Err(Error::NotFound)
+    ));
+
+    // Classes are cached by their defining loaders.
+    let from_server = hierarchy
+        .load_class(server, "org/mokapot/test/MyClass")
+        .unwrap();
+    let delegated = hierarchy
+        .load_class(parent_first, "org/mokapot/test/MyClass")
+        .unwrap();
+    assert!(std::ptr::eq(from_server.class, delegated.class));
+
+    let class_loader = CachingClassLoader::from(ClassLoader::new([hierarchy.class_path(filtered)]));
+    assert!(class_loader
+        .load_class("org/mokapot/test/RecordTest")
+        .is_ok());
+    assert!(matches!(
+        CachingClassLoader::from(ClassLoader::new([hierarchy.class_path(isolated)]))
+            .load_class("org/mokapot/test/MyClass"),
+        Err(Error::NotFound)
+    ));
+
+    let mut other = ClassLoaderHierarchy::<MemoryClassPath>::new();
+    assert!(matches!(
+        other.add_child(server, "orphan", Delegation::ParentFirst, []),
+        Err(HierarchyError::UnknownLoader(_))
+    ));
+}
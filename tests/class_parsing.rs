@@ -2,8 +2,11 @@ use std::io::{self};
 
 use mokapot::{
     jvm::{
-        class::{AccessFlags, Class, RecordComponent},
-        parsing::Error,
+        class::{AccessFlags, Class, RecordComponent, Version},
+        parsing::{
+            AttributeDecoder, ClassView, Error, LazyClass, ParseOptions, ParseWarning, PathSegment,
+            WarningKind,
+        },
         references::ClassRef,
     },
     types::{
@@ -157,3 +160,485 @@ fn not_a_class_file() {
         Err(Error::IO(e)) if e.kind() == io::ErrorKind::InvalidData
     ));
 }
+
+#[test]
+fn parse_lazy_class() {
+    let bytes = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let class = Class::from_reader(bytes).unwrap();
+    let lazy_class = LazyClass::from_reader(bytes).unwrap();
+    assert_eq!(lazy_class.binary_name, class.binary_name);
+    assert_eq!(lazy_class.super_class, class.super_class);
+    assert_eq!(lazy_class.interfaces, class.interfaces);
+    assert_eq!(lazy_class.fields.len(), class.fields.len());
+    for (lazy_field, field) in lazy_class.fields.iter().zip(&class.fields) {
+        assert_eq!(lazy_field.name, field.name);
+        assert_eq!(lazy_field.field_type, field.field_type);
+    }
+    assert_eq!(lazy_class.methods.len(), class.methods.len());
+    for (lazy_method, method) in lazy_class.methods.iter().zip(&class.methods) {
+        assert_eq!(lazy_method.name, method.name);
+        assert_eq!(lazy_method.descriptor, method.descriptor);
+        let lazy_body = lazy_method.body().unwrap();
+        assert_eq!(
+            lazy_body.map(|it| it.instructions.len()),
+            method.body.as_ref().map(|it| it.instructions.len())
+        );
+        // The decoded method is cached.
+        assert!(std::ptr::eq(
+            lazy_method.decode().unwrap(),
+            lazy_method.decode().unwrap()
+        ));
+    }
+
+    assert_eq!(lazy_class.source_file().unwrap(), class.source_file);
+    assert_eq!(
+        lazy_class.inner_classes().unwrap().len(),
+        class.inner_classes.len()
+    );
+    assert_eq!(lazy_class.nest_members().unwrap(), class.nest_members);
+    assert_eq!(
+        lazy_class.record().unwrap().is_some(),
+        class.record.is_some()
+    );
+
+    let decoded = lazy_class.into_class().unwrap();
+    assert_eq!(decoded.binary_name, class.binary_name);
+    assert_eq!(decoded.methods.len(), class.methods.len());
+    assert_eq!(decoded.inner_classes.len(), class.inner_classes.len());
+}
+
+#[test]
+fn parse_class_view() {
+    let bytes = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let class = Class::from_reader(bytes).unwrap();
+    let view = ClassView::parse(bytes).unwrap();
+    assert_eq!(view.binary_name.to_str().unwrap(), class.binary_name);
+    assert_eq!(
+        view.super_class.map(|it| it.to_str().unwrap().into_owned()),
+        class.super_class.map(|it| it.binary_name)
+    );
+    assert_eq!(view.interfaces.len(), class.interfaces.len());
+    assert_eq!(view.access_flags, class.access_flags.bits());
+    let method_names: Vec<_> = view
+        .methods
+        .iter()
+        .map(|it| it.name.to_str().unwrap())
+        .collect();
+    assert_eq!(
+        method_names,
+        class
+            .methods
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>()
+    );
+    for (view_method, method) in view.methods.iter().zip(&class.methods) {
+        assert_eq!(
+            view_method.descriptor.to_str().unwrap(),
+            method.descriptor.to_string()
+        );
+        assert_eq!(
+            view_method
+                .attributes
+                .iter()
+                .any(|it| it.name.as_bytes() == b"Code"),
+            method.body.is_some()
+        );
+    }
+    assert_eq!(view.parse_class().unwrap().binary_name, class.binary_name);
+}
+
+#[test]
+fn retain_constant_pool() {
+    use mokapot::jvm::{code::Instruction, constant_pool::Entry, references::MethodRef};
+
+    let bytes = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let class = Class::from_reader(bytes).unwrap();
+    assert!(class.constant_pool.is_none());
+    assert!(class
+        .methods
+        .iter()
+        .filter_map(|it| it.body.as_ref())
+        .all(|it| it.constant_pool_indices.is_empty()));
+
+    let options = ParseOptions::new().with_retained_constant_pool(true);
+    let lazy_class = LazyClass::from_reader_with_options(bytes, &options).unwrap();
+    assert!(lazy_class.into_class().unwrap().constant_pool.is_some());
+    let class = Class::from_reader_with_options(bytes, &options).unwrap().0;
+    let constant_pool = class.constant_pool.as_deref().unwrap();
+    assert!(constant_pool
+        .referenced_classes()
+        .unwrap()
+        .contains(&ClassRef::new("java/lang/Object")));
+    let object_init = MethodRef {
+        owner: ClassRef::new("java/lang/Object"),
+        name: "<init>".to_owned(),
+        descriptor: "()V".parse().unwrap(),
+    };
+    assert!(constant_pool
+        .referenced_methods()
+        .unwrap()
+        .contains(&object_init));
+
+    let mut invocations = 0;
+    for method in &class.methods {
+        let Some(body) = &method.body else { continue };
+        for (pc, instruction) in &body.instructions {
+            if let Instruction::InvokeSpecial(_) | Instruction::InvokeVirtual(_) = instruction {
+                let index = body.constant_pool_indices[pc];
+                assert!(matches!(
+                    constant_pool.get_entry(index).unwrap(),
+                    Entry::MethodRef { .. } | Entry::InterfaceMethodRef { .. }
+                ));
+                invocations += 1;
+            }
+        }
+    }
+    assert!(invocations > 0);
+}
+
+#[test]
+fn parse_lenient() {
+    let original = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let view = ClassView::parse(original).unwrap();
+    let source_file = view
+        .attributes
+        .iter()
+        .find(|it| it.name.as_bytes() == b"SourceFile")
+        .unwrap();
+    let info_offset = source_file.info.as_ptr() as usize - original.as_ptr() as usize;
+
+    let mut bytes = original.to_vec();
+    // Make the class version newer than the supported ones
+    bytes[6..8].copy_from_slice(&99u16.to_be_bytes());
+    // Make the SourceFile attribute point to a nonexistent constant pool entry
+    bytes[info_offset..info_offset + 2].copy_from_slice(&u16::MAX.to_be_bytes());
+    assert!(Class::from_reader(bytes.as_slice()).is_err());
+
+    let (class, warnings) = Class::from_reader_lenient(bytes.as_slice()).unwrap();
+    assert_eq!(class.binary_name, "org/mokapot/test/ComplicatedClass");
+    assert_eq!(class.version.major(), 99);
+    assert_eq!(class.source_file, None);
+    assert!(class
+        .free_attributes
+        .iter()
+        .any(|(name, _)| name == "SourceFile"));
+    assert_eq!(
+        warnings[0],
+        ParseWarning {
+            offset: Some(4),
+            kind: WarningKind::UnsupportedVersion {
+                major: 99,
+                minor: 0
+            },
+        }
+    );
+    assert!(matches!(
+        &warnings[1],
+        ParseWarning {
+            offset: Some(offset),
+            kind: WarningKind::MalformedAttribute { name: Some(name), .. },
+        } if *offset == info_offset - 6 && name == "SourceFile"
+    ));
+    assert_eq!(warnings.len(), 2);
+
+    // The problems in attributes are only reported when the attributes are decoded
+    assert!(LazyClass::from_reader(bytes.as_slice()).is_err());
+    let lazy_class = LazyClass::from_reader_lenient(bytes.as_slice()).unwrap();
+    assert_eq!(lazy_class.version.major(), 99);
+    assert_eq!(lazy_class.take_warnings(), warnings[..1]);
+    assert_eq!(lazy_class.source_file().unwrap(), None);
+    assert_eq!(lazy_class.take_warnings(), warnings[1..]);
+    let class = lazy_class.into_class().unwrap();
+    assert_eq!(class.source_file, None);
+
+    let (_, warnings) = Class::from_reader_lenient(original).unwrap();
+    assert!(warnings.is_empty());
+
+    // Make the minor version invalid for the major version
+    let mut bytes = original.to_vec();
+    bytes[4..6].copy_from_slice(&3u16.to_be_bytes());
+    let major = u16::from_be_bytes([bytes[6], bytes[7]]);
+    let (class, warnings) = Class::from_reader_lenient(bytes.as_slice()).unwrap();
+    assert_eq!(class.version, Version::new(major, 0).unwrap());
+    assert_eq!(
+        warnings,
+        [ParseWarning {
+            offset: Some(4),
+            kind: WarningKind::UnsupportedVersion { major, minor: 3 },
+        }]
+    );
+}
+
+#[test]
+fn error_context() {
+    use mokapot::jvm::constant_pool::Entry;
+
+    let original = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let class = LazyClass::from_reader(original).unwrap();
+    let (class_index, _) = class
+        .constant_pool()
+        .entries()
+        .find(|(_, entry)| matches!(entry, Entry::Class { .. }))
+        .unwrap();
+    let view = ClassView::parse(original).unwrap();
+    let source_file = view
+        .attributes
+        .iter()
+        .find(|it| it.name.as_bytes() == b"SourceFile")
+        .unwrap();
+    let info_offset = source_file.info.as_ptr() as usize - original.as_ptr() as usize;
+
+    let mut bytes = original.to_vec();
+    // Make the SourceFile attribute point to a class entry instead of a string
+    bytes[info_offset..info_offset + 2].copy_from_slice(&class_index.to_be_bytes());
+    let error = Class::from_reader(bytes.as_slice()).unwrap_err();
+    let context = error.context().unwrap();
+    assert_eq!(context.offset, Some(info_offset - 6));
+    assert_eq!(context.constant_pool_index, Some(class_index));
+    assert_eq!(
+        context.path,
+        vec![
+            PathSegment::Element("org/mokapot/test/ComplicatedClass".to_owned()),
+            PathSegment::Element("SourceFile".to_owned()),
+        ]
+    );
+    assert!(matches!(
+        error.root(),
+        Error::MismatchedConstantPoolEntryType {
+            expected: "Utf8",
+            found: "CONSTANT_Class"
+        }
+    ));
+    assert!(error
+        .to_string()
+        .starts_with("org/mokapot/test/ComplicatedClass > SourceFile at offset"));
+    assert!(!error.to_string().contains("Mismatched"));
+    assert_eq!(
+        std::error::Error::source(&error).map(ToString::to_string),
+        Some(error.root().to_string())
+    );
+
+    let truncated = &original[..original.len() - 1];
+    let Err(Error::IO(error)) = Class::from_reader(truncated) else {
+        panic!("A truncated class file should fail to parse");
+    };
+    assert!(error.to_string().contains("at offset"));
+}
+
+#[derive(Debug, PartialEq)]
+struct Marker(String);
+
+struct MarkerDecoder;
+
+impl AttributeDecoder for MarkerDecoder {
+    type Output = Marker;
+
+    fn decode(
+        &self,
+        info: &[u8],
+        constant_pool: &mokapot::jvm::constant_pool::ConstantPool,
+    ) -> Result<Self::Output, Error> {
+        let index = u16::from_be_bytes([info[0], info[1]]);
+        Ok(Marker(constant_pool.get_str(index)?.to_owned()))
+    }
+}
+
+#[test]
+fn custom_attribute_decoder() {
+    let original = test_data_class!("mokapot", "org/mokapot/test/ComplicatedClass");
+    let source_file = Class::from_reader(original).unwrap().source_file.unwrap();
+    // Rename the SourceFile attribute to a custom one
+    let name = b"\x01\x00\x0ASourceFile";
+    let position = original
+        .windows(name.len())
+        .position(|it| it == name)
+        .unwrap();
+    let mut bytes = original.to_vec();
+    bytes[position + 3..position + name.len()].copy_from_slice(b"MarkerAttr");
+
+    let class = Class::from_reader(bytes.as_slice()).unwrap();
+    assert!(class.custom_attributes.is_empty());
+    assert!(class
+        .free_attributes
+        .iter()
+        .any(|(name, _)| name == "MarkerAttr"));
+
+    let options = ParseOptions::new().with_attribute_decoder("MarkerAttr", MarkerDecoder);
+    let (class, warnings) = Class::from_reader_with_options(bytes.as_slice(), &options).unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!(class.source_file, None);
+    assert!(class.free_attributes.is_empty());
+    assert_eq!(
+        class.custom_attributes.get::<Marker>("MarkerAttr"),
+        Some(&Marker(source_file))
+    );
+}
+
+#[test]
+fn source_debug_extension_smap() {
+    let original = test_data_class!("mokapot", "org/mokapot/test/smap/GeneratedPage");
+    let lazy_class = LazyClass::from_reader(original).unwrap();
+    let constant_pool = lazy_class.constant_pool();
+    let (name_index, _) = constant_pool
+        .entries()
+        .find(|(index, _)| {
+            constant_pool
+                .get_str(*index)
+                .is_ok_and(|it| it == "SourceDebugExtension")
+        })
+        .unwrap();
+    let view = ClassView::parse(original).unwrap();
+    let first_attribute = view.attributes.first().unwrap();
+    let attributes_count_offset =
+        first_attribute.info.as_ptr() as usize - original.as_ptr() as usize - 8;
+
+    // Maps the lines 9-10 of the class to the lines 3-4 of the JSP page
+    let smap =
+        "SMAP\nGeneratedPage.java\nJSP\n*S JSP\n*F\n+ 1 page.jsp\npages/page.jsp\n*L\n3#1,2:9\n*E\n";
+    let mut bytes = original.to_vec();
+    let attributes_count = u16::from_be_bytes([
+        bytes[attributes_count_offset],
+        bytes[attributes_count_offset + 1],
+    ]);
+    bytes[attributes_count_offset..attributes_count_offset + 2]
+        .copy_from_slice(&(attributes_count + 1).to_be_bytes());
+    bytes.extend_from_slice(&name_index.to_be_bytes());
+    bytes.extend_from_slice(&u32::try_from(smap.len()).unwrap().to_be_bytes());
+    bytes.extend_from_slice(smap.as_bytes());
+
+    let class = Class::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(
+        class.source_debug_extension.as_deref(),
+        Some(smap.as_bytes())
+    );
+    let smap = class.smap().unwrap().unwrap();
+    assert_eq!(smap.default_stratum, "JSP");
+    let render = class.methods.iter().find(|it| it.name == "render").unwrap();
+    let body = render.body.as_ref().unwrap();
+    let locations: Vec<_> = body
+        .instructions
+        .iter()
+        .filter_map(|(pc, _)| smap.map_pc(body, *pc))
+        .map(|it| (it.file.name.as_str(), it.line))
+        .collect();
+    assert_eq!(locations.first(), Some(&("page.jsp", 3)));
+    assert_eq!(locations.last(), Some(&("page.jsp", 4)));
+
+    assert!(Class::from_reader(original)
+        .unwrap()
+        .smap()
+        .unwrap()
+        .is_none());
+}
+
+/// `kotlin.Pair` compiled by kotlinc 1.8 as part of the Kotlin standard library.
+#[cfg(feature = "kotlin")]
+#[test]
+fn kotlin_metadata_of_compiled_class() {
+    use mokapot::jvm::kotlin::{ClassFlags, Classifier, JvmSignature, KotlinType, MetadataKind};
+
+    let bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/kotlin/Pair.class"
+    ));
+    let class = Class::from_reader(bytes.as_slice()).unwrap();
+    let metadata = class.kotlin_metadata().unwrap().unwrap();
+    let MetadataKind::Class(kotlin_class) = &metadata.kind else {
+        panic!("Expected class metadata");
+    };
+    assert_eq!(kotlin_class.name, "kotlin/Pair");
+    assert!(kotlin_class.flags.contains(ClassFlags::DATA));
+    let type_parameters: Vec<_> = kotlin_class
+        .type_parameters
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect();
+    assert_eq!(type_parameters, ["A", "B"]);
+    assert_eq!(
+        kotlin_class.supertypes[0].classifier,
+        Classifier::Class("java/io/Serializable".to_owned())
+    );
+    assert_eq!(
+        kotlin_class.constructors[0].jvm_signature,
+        Some(JvmSignature {
+            name: "<init>".to_owned(),
+            descriptor: "(Ljava/lang/Object;Ljava/lang/Object;)V".to_owned(),
+        })
+    );
+
+    let properties = metadata.properties();
+    assert_eq!(properties.len(), 2);
+    let first = &properties[0];
+    assert_eq!(first.name, "first");
+    assert_eq!(
+        first.return_type.classifier,
+        Classifier::TypeParameter(kotlin_class.type_parameters[0].id)
+    );
+    assert_eq!(
+        first.field_signature,
+        Some(JvmSignature {
+            name: "first".to_owned(),
+            descriptor: "Ljava/lang/Object;".to_owned(),
+        })
+    );
+    assert_eq!(
+        first.getter_signature,
+        Some(JvmSignature {
+            name: "getFirst".to_owned(),
+            descriptor: "()Ljava/lang/Object;".to_owned(),
+        })
+    );
+    assert_eq!(first.setter_signature, None);
+
+    let function_names: Vec<_> = metadata
+        .functions()
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect();
+    assert_eq!(
+        function_names,
+        [
+            "component1",
+            "component2",
+            "copy",
+            "equals",
+            "hashCode",
+            "toString"
+        ]
+    );
+    let equals = metadata
+        .functions()
+        .iter()
+        .find(|it| it.name == "equals")
+        .unwrap();
+    let other: &KotlinType = &equals.value_parameters[0].parameter_type;
+    assert_eq!(other.classifier, Classifier::Class("kotlin/Any".to_owned()));
+    assert!(other.is_nullable);
+
+    let kotlin_functions: Vec<_> = class
+        .methods
+        .iter()
+        .map(|method| {
+            (
+                method.name.as_str(),
+                metadata.function_of(method).map(|it| it.name.as_str()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kotlin_functions,
+        [
+            ("<init>", None),
+            ("getFirst", None),
+            ("getSecond", None),
+            ("toString", Some("toString")),
+            ("component1", Some("component1")),
+            ("component2", Some("component2")),
+            ("copy", Some("copy")),
+            ("copy$default", None),
+            ("hashCode", Some("hashCode")),
+            ("equals", Some("equals")),
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+//! Shared helpers for the integration tests.
+#![allow(dead_code)]
+
+use mokapot::jvm::{
+    class::Class,
+    class_loader::{
+        class_paths::DirectoryClassPath, CachingClassLoader, ClassLoader, ClassPath, Error,
+    },
+    references::{ClassRef, MethodRef},
+};
+
+/// Serves a minimal `java/lang/Object` with a single `public native int hashCode()` method so
+/// that the tests do not depend on a JDK installation.
+pub struct ObjectClassPath;
+
+#[rustfmt::skip]
+const JAVA_LANG_OBJECT: &[u8] = &[
+    0xCA, 0xFE, 0xBA, 0xBE, // Magic
+    0x00, 0x00, 0x00, 0x3D, // Version 61.0
+    0x00, 0x05, // Constant pool count 4+1
+    0x07, 0x00, 0x02, // #1 = Class #2
+    0x01, 0x00, 0x10, // #2 = Utf8 "java/lang/Object"
+    b'j', b'a', b'v', b'a', b'/', b'l', b'a', b'n', b'g', b'/', b'O', b'b', b'j', b'e', b'c', b't',
+    0x01, 0x00, 0x08, // #3 = Utf8 "hashCode"
+    b'h', b'a', b's', b'h', b'C', b'o', b'd', b'e',
+    0x01, 0x00, 0x03, // #4 = Utf8 "()I"
+    b'(', b')', b'I',
+    0x00, 0x21, // Access flags: public super
+    0x00, 0x01, // This class
+    0x00, 0x00, // No super class
+    0x00, 0x00, // Interfaces count
+    0x00, 0x00, // Fields count
+    0x00, 0x01, // Methods count
+    0x01, 0x01, 0x00, 0x03, 0x00, 0x04, 0x00, 0x00, // public native hashCode()I
+    0x00, 0x00, // Attributes count
+];
+
+impl ClassPath for ObjectClassPath {
+    fn find_class(&self, binary_name: &str) -> Result<Class, Error> {
+        if binary_name == "java/lang/Object" {
+            Class::from_reader(JAVA_LANG_OBJECT).map_err(Into::into)
+        } else {
+            Err(Error::NotFound)
+        }
+    }
+}
+
+pub fn class_loader() -> CachingClassLoader<Box<dyn ClassPath>> {
+    let class_paths: Vec<Box<dyn ClassPath>> = vec![
+        Box::new(ObjectClassPath),
+        Box::new(DirectoryClassPath::new(concat!(
+            env!("OUT_DIR"),
+            "/mokapot/java_classes"
+        ))),
+    ];
+    CachingClassLoader::from(ClassLoader::new(class_paths))
+}
+
+/// A package of the test data, used to build references to its classes and methods.
+pub struct TestPackage(pub &'static str);
+
+impl TestPackage {
+    pub fn class(&self, name: &str) -> ClassRef {
+        ClassRef::new(format!("{}/{name}", self.0))
+    }
+
+    pub fn method_ref(&self, owner: &str, name: &str, descriptor: &str) -> MethodRef {
+        MethodRef {
+            owner: self.class(owner),
+            name: name.to_owned(),
+            descriptor: descriptor.parse().unwrap(),
+        }
+    }
+
+    /// A method of `owner` taking `arity` `Object` parameters and returning `void`.
+    pub fn object_consumer(&self, owner: &str, name: &str, arity: usize) -> MethodRef {
+        let descriptor = format!("({})V", "Ljava/lang/Object;".repeat(arity));
+        self.method_ref(owner, name, &descriptor)
+    }
+}
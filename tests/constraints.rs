@@ -0,0 +1,143 @@
+use mokapot::{
+    analysis::constraints::{ConstraintChecker, Diagnostic, Element, Violation},
+    jvm::{
+        class::{self, Class, Version},
+        code::Instruction,
+        field::ConstantValue,
+        method,
+    },
+};
+
+mod common;
+
+use common::class_loader;
+
+fn load(binary_name: &str) -> Class {
+    class_loader().load_class(binary_name).unwrap().clone()
+}
+
+fn violations(class: &Class) -> Vec<Violation> {
+    ConstraintChecker::new(class)
+        .check()
+        .into_iter()
+        .map(|it| it.violation)
+        .collect()
+}
+
+#[test]
+fn compiled_classes_are_valid() {
+    for name in [
+        "java/lang/Object",
+        "org/mokapot/test/constraints/Shape",
+        "org/mokapot/test/verifier/Sample",
+        "org/mokapot/test/RecordTest",
+        "org/mokapot/test/ComplicatedClass",
+        "module-info",
+    ] {
+        let class = load(name);
+        let diagnostics: Vec<_> = ConstraintChecker::new(&class)
+            .check()
+            .iter()
+            .map(Diagnostic::to_string)
+            .collect();
+        assert!(diagnostics.is_empty(), "{diagnostics:#?}");
+    }
+}
+
+#[test]
+fn conflicting_class_flags() {
+    let mut class = load("org/mokapot/test/verifier/Sample");
+    class.access_flags |= class::AccessFlags::ABSTRACT | class::AccessFlags::FINAL;
+    assert_eq!(
+        violations(&class),
+        vec![Violation::ConflictingFlags("FINAL", "ABSTRACT")]
+    );
+}
+
+#[test]
+fn protected_interface_method() {
+    let mut class = load("org/mokapot/test/constraints/Shape");
+    let method = class
+        .methods
+        .iter_mut()
+        .find(|it| it.name == "area")
+        .unwrap();
+    method.access_flags = method::AccessFlags::PROTECTED | method::AccessFlags::ABSTRACT;
+    let diagnostics = ConstraintChecker::new(&class).check();
+    assert!(diagnostics
+        .iter()
+        .all(|it| matches!(&it.element, Element::Method(method) if method.name == "area")));
+    let violations: Vec<_> = diagnostics.into_iter().map(|it| it.violation).collect();
+    assert_eq!(
+        violations,
+        vec![
+            Violation::MissingFlag("PUBLIC"),
+            Violation::ForbiddenFlag("PROTECTED")
+        ]
+    );
+}
+
+#[test]
+fn mismatched_constant_value() {
+    let mut class = load("org/mokapot/test/constraints/Shape");
+    let field = class
+        .fields
+        .iter_mut()
+        .find(|it| it.name == "NAME")
+        .unwrap();
+    field.constant_value = Some(ConstantValue::Integer(0));
+    let violations = violations(&class);
+    assert!(
+        matches!(
+            violations.as_slice(),
+            [Violation::InvalidConstantValue {
+                value: ConstantValue::Integer(0),
+                ..
+            }]
+        ),
+        "{violations:?}"
+    );
+}
+
+#[test]
+fn branch_into_instruction() {
+    let mut class = load("org/mokapot/test/verifier/Sample");
+    let body = class
+        .methods
+        .iter_mut()
+        .find(|it| it.name == "sum")
+        .and_then(|it| it.body.as_mut())
+        .unwrap();
+    let instructions = body
+        .instructions
+        .clone()
+        .into_iter()
+        .map(|(pc, it)| match it {
+            Instruction::Goto(target) => (
+                pc,
+                Instruction::Goto(u16::from(target).wrapping_add(1).into()),
+            ),
+            it => (pc, it),
+        })
+        .collect::<std::collections::BTreeMap<_, _>>();
+    body.instructions = instructions.into();
+    let violations = violations(&class);
+    assert!(
+        matches!(violations.as_slice(), [Violation::InvalidBranchTarget(_)]),
+        "{violations:?}"
+    );
+}
+
+#[test]
+fn attributes_from_newer_versions() {
+    let mut class = load("org/mokapot/test/RecordTest");
+    class.version = Version::JDK_11;
+    let violations = violations(&class);
+    assert!(
+        violations.contains(&Violation::UnsupportedAttribute {
+            attribute: "Record",
+            since: 60
+        }),
+        "{violations:?}"
+    );
+}
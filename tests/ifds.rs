@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+
+use mokapot::{
+    analysis::{
+        call_graph::{CallGraphBuilder, EntryPoint},
+        ifds::{IdeProblem, IfdsProblem, InterproceduralCfg},
+        Location,
+    },
+    ir::{expression::Expression, Argument, Identifier, MokaInstruction},
+    jvm::references::{ClassRef, MethodRef},
+};
+
+mod common;
+
+use common::class_loader;
+
+fn method_ref(name: &str, descriptor: &str) -> MethodRef {
+    MethodRef {
+        owner: ClassRef::new("org/mokapot/test/ifds/Flow"),
+        name: name.to_owned(),
+        descriptor: descriptor.parse().unwrap(),
+    }
+}
+
+fn main_method() -> MethodRef {
+    method_ref("main", "([Ljava/lang/String;)V")
+}
+
+fn icfg() -> InterproceduralCfg {
+    let class_loader = class_loader();
+    let call_graph = CallGraphBuilder::new(&class_loader, [main_method().owner])
+        .unwrap()
+        .entry_point(EntryPoint::MainMethods)
+        .build()
+        .unwrap();
+    InterproceduralCfg::from_call_graph(call_graph, &class_loader)
+}
+
+fn call_at<'a>(
+    icfg: &'a InterproceduralCfg,
+    location: &Location,
+) -> Option<(&'a MethodRef, &'a [Argument])> {
+    match icfg.instruction_at(location)? {
+        MokaInstruction::Definition {
+            expr: Expression::Call { method, args, .. },
+            ..
+        } => Some((method, args)),
+        _ => None,
+    }
+}
+
+/// The calls to `sink` in `main` and the identifiers of their arguments.
+fn sink_calls(icfg: &InterproceduralCfg) -> Vec<(Location, Identifier)> {
+    let main = icfg.method(&main_method()).unwrap();
+    main.instructions
+        .iter()
+        .map(|(pc, _)| Location {
+            method: main_method(),
+            pc: *pc,
+        })
+        .filter_map(|location| {
+            let (method, args) = call_at(icfg, &location)?;
+            let Argument::Id(arg) = args.first()? else {
+                return None;
+            };
+            (method.name == "sink").then_some((location, *arg))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Taint {
+    Zero,
+    Tainted(Identifier),
+}
+
+/// Tracks the values returned by `source` through assignments, calls and returns.
+struct TaintProblem<'a> {
+    icfg: &'a InterproceduralCfg,
+}
+
+impl TaintProblem<'_> {
+    fn return_value(&self, exit: &Location, fact: &Taint) -> bool {
+        matches!(
+            (self.icfg.instruction_at(exit), fact),
+            (Some(MokaInstruction::Return(Some(value))), Taint::Tainted(id))
+                if value.iter().any(|it| it == id)
+        )
+    }
+}
+
+impl IfdsProblem for TaintProblem<'_> {
+    type Fact = Taint;
+
+    fn zero(&self) -> Taint {
+        Taint::Zero
+    }
+
+    fn initial_seeds(&self) -> Vec<(MethodRef, Taint)> {
+        vec![(main_method(), Taint::Zero)]
+    }
+
+    fn normal_flow(&self, _: &Location, _: &Location, fact: &Taint) -> BTreeSet<Taint> {
+        BTreeSet::from([fact.clone()])
+    }
+
+    fn call_flow(&self, call_site: &Location, _: &MethodRef, fact: &Taint) -> BTreeSet<Taint> {
+        let Some((_, args)) = call_at(self.icfg, call_site) else {
+            return BTreeSet::new();
+        };
+        let Taint::Tainted(id) = fact else {
+            return BTreeSet::new();
+        };
+        (0..)
+            .zip(args)
+            .filter(|(_, arg)| arg.iter().any(|it| it == id))
+            .map(|(index, _)| Taint::Tainted(Identifier::Arg(index)))
+            .collect()
+    }
+
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        _: &MethodRef,
+        exit: &Location,
+        _: &Location,
+        fact: &Taint,
+    ) -> BTreeSet<Taint> {
+        match self.icfg.instruction_at(call_site) {
+            Some(MokaInstruction::Definition { value, .. }) if self.return_value(exit, fact) => {
+                BTreeSet::from([Taint::Tainted((*value).into())])
+            }
+            _ => BTreeSet::new(),
+        }
+    }
+
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        _: &Location,
+        fact: &Taint,
+    ) -> BTreeSet<Taint> {
+        let is_source = call_at(self.icfg, call_site).is_some_and(|(it, _)| it.name == "source");
+        match (self.icfg.instruction_at(call_site), fact) {
+            (Some(MokaInstruction::Definition { value, .. }), Taint::Zero) if is_source => {
+                BTreeSet::from([Taint::Tainted((*value).into())])
+            }
+            (_, Taint::Zero) => BTreeSet::new(),
+            _ => BTreeSet::from([fact.clone()]),
+        }
+    }
+}
+
+#[test]
+fn ifds_taint_through_calls() {
+    let icfg = icfg();
+    let problem = TaintProblem { icfg: &icfg };
+    let results = problem.solve(&icfg);
+    let tainted: Vec<_> = sink_calls(&icfg)
+        .into_iter()
+        .map(|(location, arg)| results.holds_at(&location, &Taint::Tainted(arg)))
+        .collect();
+    assert_eq!(tainted, vec![true, false, false]);
+
+    let identity = method_ref("identity", "(Ljava/lang/String;)Ljava/lang/String;");
+    let summary = results.summary(&identity).unwrap();
+    let exits = &summary[&Taint::Tainted(Identifier::Arg(0))];
+    assert!(exits
+        .iter()
+        .any(|(_, fact)| *fact == Taint::Tainted(Identifier::Arg(0))));
+}
+
+/// Computes the number of calls a tainted value passes through, where the value of a fact is the
+/// minimum over all paths.
+struct CallDepthProblem<'a>(TaintProblem<'a>);
+
+impl IdeProblem for CallDepthProblem<'_> {
+    type Fact = Taint;
+    type Value = Option<u32>;
+    type EdgeFunction = u32;
+
+    fn zero(&self) -> Taint {
+        Taint::Zero
+    }
+
+    fn initial_seeds(&self) -> Vec<(MethodRef, Taint)> {
+        self.0.initial_seeds()
+    }
+
+    fn normal_flow(
+        &self,
+        current: &Location,
+        successor: &Location,
+        fact: &Taint,
+    ) -> Vec<(Taint, u32)> {
+        let facts = self.0.normal_flow(current, successor, fact);
+        facts.into_iter().map(|it| (it, 0)).collect()
+    }
+
+    fn call_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        fact: &Taint,
+    ) -> Vec<(Taint, u32)> {
+        let facts = self.0.call_flow(call_site, callee, fact);
+        facts.into_iter().map(|it| (it, 1)).collect()
+    }
+
+    fn return_flow(
+        &self,
+        call_site: &Location,
+        callee: &MethodRef,
+        exit: &Location,
+        return_site: &Location,
+        fact: &Taint,
+    ) -> Vec<(Taint, u32)> {
+        let facts = self
+            .0
+            .return_flow(call_site, callee, exit, return_site, fact);
+        facts.into_iter().map(|it| (it, 0)).collect()
+    }
+
+    fn call_to_return_flow(
+        &self,
+        call_site: &Location,
+        return_site: &Location,
+        fact: &Taint,
+    ) -> Vec<(Taint, u32)> {
+        let facts = self.0.call_to_return_flow(call_site, return_site, fact);
+        facts.into_iter().map(|it| (it, 0)).collect()
+    }
+
+    fn top_value(&self) -> Option<u32> {
+        None
+    }
+
+    fn initial_value(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn join_values(&self, lhs: &Option<u32>, rhs: &Option<u32>) -> Option<u32> {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Some(*lhs.min(rhs)),
+            (it, None) | (None, it) => *it,
+        }
+    }
+
+    fn identity(&self) -> u32 {
+        0
+    }
+
+    fn apply(&self, function: &u32, value: &Option<u32>) -> Option<u32> {
+        value.map(|it| it + function)
+    }
+
+    fn compose(&self, first: &u32, second: &u32) -> u32 {
+        first + second
+    }
+
+    fn join_functions(&self, lhs: &u32, rhs: &u32) -> u32 {
+        *lhs.min(rhs)
+    }
+}
+
+#[test]
+fn ide_values_through_summaries() {
+    let icfg = icfg();
+    let problem = CallDepthProblem(TaintProblem { icfg: &icfg });
+    let results = problem.solve(&icfg);
+    let (location, arg) = sink_calls(&icfg).into_iter().next().unwrap();
+    assert_eq!(
+        results.value_at(&location, &Taint::Tainted(arg)),
+        Some(&Some(1))
+    );
+    assert_eq!(results.value_at(&location, &Taint::Zero), Some(&Some(0)));
+}
@@ -0,0 +1,158 @@
+use mokapot::{
+    analysis::resolution::{InvocationKind, MethodResolver, ResolutionError},
+    jvm::references::{ClassRef, FieldRef},
+};
+
+mod common;
+
+use common::{class_loader, TestPackage};
+
+const PKG: TestPackage = TestPackage("org/mokapot/test/resolution");
+
+#[test]
+fn resolve_inherited_method() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let method = resolver
+        .resolve(&PKG.method_ref("Puppy", "name", "()Ljava/lang/String;"))
+        .unwrap();
+    assert_eq!(method.owner, PKG.class("Base"));
+}
+
+#[test]
+fn resolve_default_method_through_superinterface() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let method = resolver
+        .resolve(&PKG.method_ref("Puppy", "sound", "()Ljava/lang/String;"))
+        .unwrap();
+    assert_eq!(method.owner, PKG.class("Dog"));
+}
+
+#[test]
+fn resolve_interface_method_from_object() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let method = resolver
+        .resolve_interface_method(&PKG.method_ref("Dog", "hashCode", "()I"))
+        .unwrap();
+    assert_eq!(method.owner, ClassRef::new("java/lang/Object"));
+}
+
+#[test]
+fn resolve_incompatible_class_change() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let result = resolver.resolve_method(&PKG.method_ref("Dog", "sound", "()Ljava/lang/String;"));
+    assert!(matches!(
+        result,
+        Err(ResolutionError::IncompatibleClassChange(..))
+    ));
+}
+
+#[test]
+fn resolve_not_found() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let result = resolver.resolve(&PKG.method_ref("Puppy", "fly", "()V"));
+    assert!(matches!(result, Err(ResolutionError::NotFound(_))));
+}
+
+#[test]
+fn select_virtual_prefers_override() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let sound = PKG.method_ref("Animal", "sound", "()Ljava/lang/String;");
+    let kitten = resolver
+        .dispatch(
+            InvocationKind::Interface,
+            &sound,
+            &PKG.class("Hierarchy"),
+            Some(&PKG.class("Kitten")),
+        )
+        .unwrap();
+    assert_eq!(kitten.owner, PKG.class("Kitten"));
+    let puppy = resolver
+        .dispatch(
+            InvocationKind::Interface,
+            &sound,
+            &PKG.class("Hierarchy"),
+            Some(&PKG.class("Puppy")),
+        )
+        .unwrap();
+    assert_eq!(puppy.owner, PKG.class("Dog"));
+}
+
+#[test]
+fn select_special_super_call() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let method = resolver
+        .dispatch(
+            InvocationKind::Special,
+            &PKG.method_ref("Base", "hook", "()V"),
+            &PKG.class("Puppy"),
+            None,
+        )
+        .unwrap();
+    assert_eq!(method.owner, PKG.class("Base"));
+}
+
+#[test]
+fn illegal_access_to_private_method() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let result = resolver.dispatch(
+        InvocationKind::Special,
+        &PKG.method_ref("Base", "secret", "()V"),
+        &ClassRef::new("org/mokapot/test/MyClass"),
+        None,
+    );
+    assert!(matches!(result, Err(ResolutionError::IllegalAccess { .. })));
+}
+
+#[test]
+fn static_invocation_of_instance_method() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let result = resolver.dispatch(
+        InvocationKind::Static,
+        &PKG.method_ref("Base", "name", "()Ljava/lang/String;"),
+        &PKG.class("Hierarchy"),
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(ResolutionError::IncompatibleClassChange(..))
+    ));
+}
+
+fn field_ref(owner: &str, name: &str, descriptor: &str) -> FieldRef {
+    FieldRef {
+        owner: PKG.class(owner),
+        name: name.to_owned(),
+        field_type: descriptor.parse().unwrap(),
+    }
+}
+
+#[test]
+fn resolve_inherited_fields() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let nickname = resolver
+        .resolve_field(&field_ref("Puppy", "nickname", "Ljava/lang/String;"))
+        .unwrap();
+    assert_eq!(nickname.owner, PKG.class("Base"));
+    let legs = resolver
+        .resolve_field(&field_ref("Puppy", "LEGS", "I"))
+        .unwrap();
+    assert_eq!(legs.owner, PKG.class("Animal"));
+}
+
+#[test]
+fn resolve_missing_field() {
+    let class_loader = class_loader();
+    let resolver = MethodResolver::new(&class_loader);
+    let result = resolver.resolve_field(&field_ref("Puppy", "nickname", "I"));
+    assert!(matches!(result, Err(ResolutionError::FieldNotFound(_))));
+}
@@ -0,0 +1,184 @@
+use mokapot::jvm::{
+    class_loader::{
+        class_paths::DirectoryClassPath,
+        module_path::{ModuleError, ModulePath},
+        ClassLoader, ClassPath, Error,
+    },
+    module::{Export, ExportFlags, Flags, Module, Require, RequireFlags},
+    references::{ModuleRef, PackageRef},
+};
+
+mod common;
+
+use common::ObjectClassPath;
+
+fn test_classes() -> Box<dyn ClassPath> {
+    Box::new(DirectoryClassPath::new(concat!(
+        env!("OUT_DIR"),
+        "/mokapot/java_classes"
+    )))
+}
+
+fn module(name: &str, requires: &[&str], exports: &[&str]) -> Module {
+    Module {
+        name: name.to_owned(),
+        flags: Flags::empty(),
+        version: None,
+        requires: requires
+            .iter()
+            .map(|it| Require {
+                module: ModuleRef {
+                    name: (*it).to_owned(),
+                },
+                flags: RequireFlags::empty(),
+                version: None,
+            })
+            .collect(),
+        exports: exports
+            .iter()
+            .map(|it| Export {
+                package: PackageRef {
+                    binary_name: (*it).to_owned(),
+                },
+                flags: ExportFlags::empty(),
+                to: Vec::new(),
+            })
+            .collect(),
+        opens: Vec::new(),
+        uses: Vec::new(),
+        provides: Vec::new(),
+    }
+}
+
+fn module_path() -> ModulePath<Box<dyn ClassPath>> {
+    let mut module_path = ModulePath::new();
+    module_path.add(test_classes()).unwrap();
+    module_path
+        .add_module(
+            module("java.base", &[], &["java/lang"]),
+            Box::new(ObjectClassPath),
+        )
+        .unwrap();
+    module_path
+}
+
+#[test]
+fn read_module_declaration() {
+    let module = test_classes().module().unwrap().unwrap();
+    assert_eq!(module.name, "mokapot_test");
+    let no_module = DirectoryClassPath::new(env!("CARGO_MANIFEST_DIR"));
+    assert!(no_module.module().unwrap().is_none());
+}
+
+#[test]
+fn resolve_modules() {
+    let mut module_path = module_path();
+    module_path
+        .add_module(
+            module("consumer", &["java.base", "mokapot_test"], &[]),
+            Box::new(DirectoryClassPath::new("/nonexistent")),
+        )
+        .unwrap();
+    module_path
+        .add_module(
+            module("unused", &["java.base"], &[]),
+            Box::new(DirectoryClassPath::new("/nonexistent")),
+        )
+        .unwrap();
+    let graph = module_path.resolve(["consumer"]).unwrap();
+    let modules: Vec<_> = graph.modules().map(|it| it.name.as_str()).collect();
+    assert_eq!(modules, vec!["consumer", "java.base", "mokapot_test"]);
+    assert!(graph.reads("consumer", "mokapot_test"));
+    assert!(graph.reads("mokapot_test", "java.base"));
+    assert!(!graph.reads("java.base", "mokapot_test"));
+    assert!(graph.is_exported("mokapot_test", "org/mokapot/test", "consumer"));
+    assert!(!graph.is_exported("mokapot_test", "org/mokapot/test/verifier", "consumer"));
+    assert!(graph.is_opened("mokapot_test", "org/mokapot/test", "java.base"));
+    assert!(!graph.is_opened("mokapot_test", "org/mokapot/test", "consumer"));
+}
+
+#[test]
+fn package_visibility() {
+    let mut module_path = module_path();
+    module_path
+        .add_module(
+            module("consumer", &["java.base", "mokapot_test"], &[]),
+            Box::new(DirectoryClassPath::new("/nonexistent")),
+        )
+        .unwrap();
+    let graph = module_path.resolve(["consumer"]).unwrap();
+
+    let consumer = ClassLoader::new([graph.class_path("consumer").unwrap()]);
+    assert!(consumer.load_class("org/mokapot/test/MyClass").is_ok());
+    assert!(consumer.load_class("java/lang/Object").is_ok());
+    assert!(matches!(
+        consumer.load_class("org/mokapot/test/verifier/Sample"),
+        Err(Error::Inaccessible { module, .. }) if module == "mokapot_test"
+    ));
+
+    let test_module = ClassLoader::new([graph.class_path("mokapot_test").unwrap()]);
+    assert!(test_module
+        .load_class("org/mokapot/test/verifier/Sample")
+        .is_ok());
+
+    let java_base = ClassLoader::new([graph.class_path("java.base").unwrap()]);
+    assert!(matches!(
+        java_base.load_class("org/mokapot/test/MyClass"),
+        Err(Error::NotFound)
+    ));
+}
+
+#[test]
+fn missing_dependency() {
+    let mut module_path = ModulePath::new();
+    module_path.add(test_classes()).unwrap();
+    assert!(matches!(
+        module_path.resolve(["mokapot_test"]),
+        Err(ModuleError::MissingDependency { module, required })
+            if module == "mokapot_test" && required == "java.base"
+    ));
+    let mut module_path = ModulePath::new();
+    module_path.add(test_classes()).unwrap();
+    assert!(matches!(
+        module_path.add(test_classes()),
+        Err(ModuleError::DuplicateModule(name)) if name == "mokapot_test"
+    ));
+}
+
+#[test]
+#[cfg(feature = "jar")]
+fn jmod_module_path() {
+    use std::path::PathBuf;
+
+    use mokapot::jvm::class_loader::class_paths::JmodClassPath;
+
+    let Ok(java_home) = std::env::var("JAVA_HOME") else {
+        return;
+    };
+    let jmods = PathBuf::from(java_home).join("jmods");
+    let mut module_path = ModulePath::new();
+    for name in [
+        "java.base",
+        "java.logging",
+        "java.sql",
+        "java.transaction.xa",
+        "java.xml",
+        "java.desktop",
+    ] {
+        let jmod = JmodClassPath::new(jmods.join(format!("{name}.jmod")));
+        let module = module_path.add(jmod).unwrap();
+        assert_eq!(module.name, name);
+    }
+    let graph = module_path.resolve(["java.sql"]).unwrap();
+    assert!(graph.module("java.desktop").is_none());
+    // `java.sql` requires `java.xml` transitively.
+    assert!(graph.reads("java.sql", "java.xml"));
+    let java_sql = ClassLoader::new([graph.class_path("java.sql").unwrap()]);
+    assert!(java_sql.load_class("java/sql/Connection").is_ok());
+    assert!(java_sql.load_class("java/lang/Object").is_ok());
+    assert!(java_sql.load_class("javax/xml/XMLConstants").is_ok());
+    assert!(matches!(
+        java_sql.load_class("sun/nio/ch/Net"),
+        Err(Error::Inaccessible { module, .. }) if module == "java.base"
+    ));
+}
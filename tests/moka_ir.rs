@@ -2,7 +2,6 @@ use mokapot::{
     ir::MokaIRMethodExt,
     jvm::{class::Class, method::Method},
 };
-use petgraph::dot::Dot;
 
 fn get_test_class() -> Class {
     let bytes = include_bytes!(concat!(
@@ -41,6 +40,7 @@ fn analyze() {
 fn cfg_to_dot() {
     use itertools::Itertools;
     use mokapot::ir::control_flow::ControlTransfer;
+    use petgraph::dot::Dot;
 
     let method = get_test_method();
     let ir = method.brew().unwrap();
@@ -0,0 +1,136 @@
+use mokapot::{
+    analysis::{
+        call_graph::EntryPoint,
+        points_to::{ContextSensitivity, ObjectKind, PointsTo, PointsToBuilder},
+    },
+    ir::Identifier,
+    jvm::references::FieldRef,
+};
+
+mod common;
+
+use common::{class_loader, TestPackage};
+
+const PKG: TestPackage = TestPackage("org/mokapot/test/pointsto");
+
+fn analyze(context_sensitivity: ContextSensitivity) -> PointsTo {
+    let class_loader = class_loader();
+    let classes = ["Aliasing", "Item", "Box", "SubBox"].map(|it| PKG.class(it));
+    PointsToBuilder::new(&class_loader, classes)
+        .unwrap()
+        .context_sensitivity(context_sensitivity)
+        .entry_point(EntryPoint::MainMethods)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn field_sensitive_flow() {
+    let result = analyze(ContextSensitivity::Insensitive);
+    let method = PKG.object_consumer("Aliasing", "fieldFlow", 2);
+    let actual = result.points_to(&method, Identifier::Arg(0));
+    assert_eq!(actual.len(), 1);
+    assert!(actual
+        .iter()
+        .all(|it| it.kind == ObjectKind::Instance(PKG.class("Item"))));
+    assert!(result.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+}
+
+#[test]
+fn array_element_flow() {
+    let result = analyze(ContextSensitivity::Insensitive);
+    let method = PKG.object_consumer("Aliasing", "arrayFlow", 2);
+    assert_eq!(result.points_to(&method, Identifier::Arg(0)).len(), 1);
+    assert!(!result.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+    let array = result
+        .objects()
+        .find(|it| matches!(it.kind, ObjectKind::Array(_)))
+        .unwrap();
+    assert_eq!(
+        result.array_element_points_to(array),
+        result.points_to(&method, Identifier::Arg(0))
+    );
+}
+
+#[test]
+fn static_field_flow() {
+    let result = analyze(ContextSensitivity::Insensitive);
+    let field = FieldRef {
+        owner: PKG.class("Aliasing"),
+        name: "global".to_owned(),
+        field_type: "Ljava/lang/Object;".parse().unwrap(),
+    };
+    let global = result.static_field_points_to(&field);
+    assert_eq!(global.len(), 1);
+    assert_eq!(
+        result.points_to(
+            &PKG.object_consumer("Aliasing", "staticFlow", 1),
+            Identifier::Arg(0)
+        ),
+        global
+    );
+}
+
+#[test]
+fn phi_merges_objects() {
+    let result = analyze(ContextSensitivity::Insensitive);
+    let actual = result.points_to(
+        &PKG.object_consumer("Aliasing", "phiFlow", 1),
+        Identifier::Arg(0),
+    );
+    assert_eq!(actual.len(), 2);
+}
+
+#[test]
+fn call_site_sensitivity() {
+    let method = PKG.object_consumer("Aliasing", "callSiteFlow", 2);
+    let insensitive = analyze(ContextSensitivity::Insensitive);
+    assert!(insensitive.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+
+    let sensitive = analyze(ContextSensitivity::CallSite(1));
+    assert!(!sensitive.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+    assert_eq!(sensitive.points_to(&method, Identifier::Arg(0)).len(), 1);
+}
+
+#[test]
+fn object_sensitivity() {
+    let method = PKG.object_consumer("Aliasing", "objectFlow", 2);
+    let insensitive = analyze(ContextSensitivity::Insensitive);
+    assert!(insensitive.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+
+    let sensitive = analyze(ContextSensitivity::Object(1));
+    assert!(!sensitive.may_alias(&method, Identifier::Arg(0), Identifier::Arg(1)));
+    let boxes: Vec<_> = sensitive
+        .objects()
+        .filter(|it| it.kind == ObjectKind::Instance(PKG.class("Box")))
+        .collect();
+    assert_eq!(boxes.len(), 3);
+    let set = PKG.object_consumer("Box", "set", 1);
+    assert_eq!(
+        sensitive
+            .reachable_methods()
+            .filter(|(method, _)| **method == set)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn inherited_field_flow() {
+    let result = analyze(ContextSensitivity::Insensitive);
+    let actual = result.points_to(
+        &PKG.object_consumer("Aliasing", "inheritedFieldFlow", 1),
+        Identifier::Arg(0),
+    );
+    assert_eq!(actual.len(), 1);
+    let sub_box = result
+        .objects()
+        .find(|it| it.kind == ObjectKind::Instance(PKG.class("SubBox")))
+        .unwrap();
+    let field = FieldRef {
+        owner: PKG.class("SubBox"),
+        name: "value".to_owned(),
+        field_type: "Ljava/lang/Object;".parse().unwrap(),
+    };
+    assert_eq!(result.field_points_to(sub_box, &field), actual);
+}
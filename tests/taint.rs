@@ -0,0 +1,110 @@
+use mokapot::{
+    analysis::{
+        call_graph::{CallGraphBuilder, EntryPoint},
+        ifds::InterproceduralCfg,
+        taint::{Finding, MethodPattern, Sink, TaintAnalysis, TaintPosition, TaintSpec},
+    },
+    jvm::references::ClassRef,
+};
+
+mod common;
+
+use common::class_loader;
+
+#[cfg(feature = "taint-spec")]
+const SPEC: &str = r#"
+sources = ["org/mokapot/test/taint/Request::getParameter"]
+sanitizers = ["org/mokapot/test/taint/Encoder::encode"]
+
+[[sinks]]
+method = "org/mokapot/test/taint/Statement::executeQuery(Ljava/lang/String;)V"
+positions = ["arg0"]
+"#;
+
+fn pattern(s: &str) -> MethodPattern {
+    s.parse().unwrap()
+}
+
+fn spec() -> TaintSpec {
+    TaintSpec {
+        sources: vec![pattern("org/mokapot/test/taint/Request::getParameter")],
+        sanitizers: vec![pattern("org/mokapot/test/taint/Encoder::encode")],
+        sinks: vec![Sink {
+            method: pattern("org/mokapot/test/taint/Statement::executeQuery(Ljava/lang/String;)V"),
+            positions: Some(vec![TaintPosition::Argument(0)]),
+        }],
+        ..Default::default()
+    }
+}
+
+fn findings() -> Vec<Finding> {
+    let class_loader = class_loader();
+    let call_graph = CallGraphBuilder::new(
+        &class_loader,
+        [ClassRef::new("org/mokapot/test/taint/Servlet")],
+    )
+    .unwrap()
+    .entry_point(EntryPoint::MainMethods)
+    .build()
+    .unwrap();
+    let icfg = InterproceduralCfg::from_call_graph(call_graph, &class_loader);
+    let spec = spec();
+    TaintAnalysis::new(&spec, &icfg).findings()
+}
+
+#[test]
+fn taint_flows_to_sinks() {
+    let findings = findings();
+    let mut sink_lines: Vec<_> = findings.iter().map(|it| it.sink.line_number).collect();
+    sink_lines.sort_unstable();
+    // Concatenation, StringBuilder, collection, callee return value, static field, and inherited
+    // instance field.
+    assert_eq!(
+        sink_lines,
+        vec![Some(13), Some(16), Some(23), Some(24), Some(29), Some(39)]
+    );
+    for finding in &findings {
+        assert_eq!(finding.source.line_number, Some(12));
+        assert_eq!(finding.source.location.method.name, "main");
+        assert_eq!(finding.sink_method.name, "executeQuery");
+        assert_eq!(finding.position, TaintPosition::Argument(0));
+    }
+}
+
+#[test]
+fn call_chain_to_sink() {
+    let findings = findings();
+    let finding = findings
+        .iter()
+        .find(|it| it.sink.location.method.name == "query")
+        .unwrap();
+    let chain: Vec<_> = finding
+        .call_chain
+        .iter()
+        .map(|it| (it.location.method.name.as_str(), it.line_number))
+        .collect();
+    assert_eq!(chain, vec![("main", Some(20)), ("query", Some(29))]);
+}
+
+#[test]
+#[cfg(feature = "taint-spec")]
+fn toml_spec() {
+    assert_eq!(TaintSpec::from_toml(SPEC).unwrap(), spec());
+}
+
+#[test]
+#[cfg(feature = "taint-spec")]
+fn json_spec() {
+    let json = r#"{
+        "sources": ["org/mokapot/test/taint/Request::getParameter"],
+        "sanitizers": ["org/mokapot/test/taint/Encoder::encode"],
+        "sinks": [{
+            "method": "org/mokapot/test/taint/Statement::executeQuery(Ljava/lang/String;)V",
+            "positions": ["arg0"]
+        }]
+    }"#;
+    assert_eq!(TaintSpec::from_json(json).unwrap(), spec());
+    let plain = TaintSpec::from_json(r#"{"sinks": ["java/sql/Statement::*"]}"#).unwrap();
+    assert_eq!(plain.sinks[0].positions, None);
+    assert!(TaintSpec::from_json(r#"{"sources": ["getParameter"]}"#).is_err());
+}
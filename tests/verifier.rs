@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use mokapot::{
+    analysis::{
+        class_hierarchy::ClassHierarchy,
+        verifier::{Verifier, VerifyErrorKind},
+    },
+    jvm::{
+        class::{Class, Version},
+        code::{Instruction, MethodBody},
+    },
+};
+
+mod common;
+
+use common::class_loader;
+
+fn sample() -> (Class, ClassHierarchy) {
+    let class_loader = class_loader();
+    let object = class_loader.load_class("java/lang/Object").unwrap();
+    let class = class_loader
+        .load_class("org/mokapot/test/verifier/Sample")
+        .unwrap();
+    let hierarchy = ClassHierarchy::from_classes([object, class]);
+    (class.clone(), hierarchy)
+}
+
+fn body_mut<'a>(class: &'a mut Class, name: &str, descriptor: &str) -> &'a mut MethodBody {
+    let descriptor = descriptor.parse().unwrap();
+    class
+        .methods
+        .iter_mut()
+        .find(|it| it.name == name && it.descriptor == descriptor)
+        .and_then(|it| it.body.as_mut())
+        .unwrap()
+}
+
+/// Replaces the instructions in a method body.
+fn map_instructions(body: &mut MethodBody, f: impl Fn(Instruction) -> Instruction) {
+    let instructions: BTreeMap<_, _> = body
+        .instructions
+        .clone()
+        .into_iter()
+        .map(|(pc, it)| (pc, f(it)))
+        .collect();
+    body.instructions = instructions.into();
+}
+
+fn error_kinds(
+    class: &Class,
+    hierarchy: &ClassHierarchy,
+    name: &str,
+    descriptor: &str,
+) -> Vec<VerifyErrorKind> {
+    let descriptor = descriptor.parse().unwrap();
+    let method = class
+        .methods
+        .iter()
+        .find(|it| it.name == name && it.descriptor == descriptor)
+        .unwrap();
+    Verifier::new(class, hierarchy)
+        .verify_method(method)
+        .into_iter()
+        .map(|it| it.kind)
+        .collect()
+}
+
+#[test]
+fn compiled_class_is_valid() {
+    let (class, hierarchy) = sample();
+    let report = Verifier::new(&class, &hierarchy).verify();
+    let errors: Vec<_> = report
+        .iter()
+        .map(|(method, errors)| format!("{method}: {errors:?}"))
+        .collect();
+    assert!(report.is_valid(), "{errors:#?}");
+}
+
+#[test]
+fn stack_overflow() {
+    let (mut class, hierarchy) = sample();
+    body_mut(&mut class, "sum", "()J").max_stack = 2;
+    let errors = error_kinds(&class, &hierarchy, "sum", "()J");
+    assert!(
+        errors.contains(&VerifyErrorKind::StackOverflow(2)),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn missing_stack_map_frames() {
+    let (mut class, hierarchy) = sample();
+    body_mut(&mut class, "sum", "()J").stack_map_table = None;
+    let errors = error_kinds(&class, &hierarchy, "sum", "()J");
+    assert!(
+        errors
+            .iter()
+            .any(|it| matches!(it, VerifyErrorKind::MissingStackMapFrame(_))),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn type_mismatch() {
+    let (mut class, hierarchy) = sample();
+    map_instructions(body_mut(&mut class, "sum", "()J"), |it| match it {
+        Instruction::LAdd => Instruction::IAdd,
+        it => it,
+    });
+    let errors = error_kinds(&class, &hierarchy, "sum", "()J");
+    assert!(
+        errors
+            .iter()
+            .any(|it| matches!(it, VerifyErrorKind::TypeMismatch { .. })),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn constructor_without_super_call() {
+    let (mut class, hierarchy) = sample();
+    map_instructions(body_mut(&mut class, "<init>", "(J)V"), |it| match it {
+        Instruction::InvokeSpecial(method) if method.name == "<init>" => Instruction::Pop,
+        it => it,
+    });
+    let errors = error_kinds(&class, &hierarchy, "<init>", "(J)V");
+    assert_eq!(errors, vec![VerifyErrorKind::ReturnBeforeInitialization]);
+}
+
+#[test]
+fn old_class_files_are_not_type_checked() {
+    let (mut class, hierarchy) = sample();
+    class.version = Version::JDK_5;
+    let errors = error_kinds(&class, &hierarchy, "sum", "()J");
+    assert_eq!(errors, vec![VerifyErrorKind::UnsupportedVersion(49)]);
+}